@@ -1,3 +1,6 @@
 pub mod channel;
+pub mod channel_sync_state;
+pub mod registered_channel;
 pub mod video;
+pub mod video_comment;
 pub mod video_timestamp;
@@ -0,0 +1,106 @@
+use crate::entities::video::VideoEntity;
+use crate::value_objects::channel_id::ChannelId;
+use crate::value_objects::video_etag::VideoEtag;
+use crate::value_objects::video_id::VideoId;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// チャンネルごとの取得状況
+///
+/// 前回までに取得した内容を覚えておき、次回は新しい動画と変更のあった動画のみを取得する。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelSyncStateEntity {
+    pub channel_id: ChannelId,
+    /// 取得済みの動画のうち最も新しい公開日時
+    pub last_published_at: Option<DateTime<Utc>>,
+    /// 全件取得を中断したページ。次回はここから再開する
+    pub page_token: Option<String>,
+    /// 取得済みの動画ごとの ETag
+    pub etags: HashMap<VideoId, VideoEtag>,
+}
+
+impl ChannelSyncStateEntity {
+    pub fn new(channel_id: ChannelId) -> Self {
+        Self {
+            channel_id,
+            last_published_at: None,
+            page_token: None,
+            etags: HashMap::new(),
+        }
+    }
+
+    pub fn with_last_published_at(mut self, last_published_at: DateTime<Utc>) -> Self {
+        self.last_published_at = Some(last_published_at);
+        self
+    }
+
+    pub fn with_page_token(mut self, page_token: Option<String>) -> Self {
+        self.page_token = page_token;
+        self
+    }
+
+    pub fn with_etags(mut self, etags: HashMap<VideoId, VideoEtag>) -> Self {
+        self.etags = etags;
+        self
+    }
+
+    /// 前回までに取得した範囲の公開日時か
+    pub fn is_known(&self, published_at: DateTime<Utc>) -> bool {
+        self.last_published_at.is_some_and(|l| published_at <= l)
+    }
+
+    /// 前回取得したときから変わったか。ETag の無いものは常に変更ありとみなす
+    pub fn is_changed(&self, video: &VideoEntity) -> bool {
+        match video.etag.as_ref() {
+            Some(etag) => self.etags.get(&video.id) != Some(etag),
+            None => true,
+        }
+    }
+
+    /// 取得した動画を記録する
+    pub fn record(&mut self, video: &VideoEntity) {
+        if let Some(etag) = video.etag.as_ref() {
+            self.etags.insert(video.id.clone(), etag.clone());
+        }
+        self.last_published_at = self.last_published_at.max(Some(video.published_at));
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::entities::channel::ChannelEntity;
+    use crate::value_objects::channel_name::ChannelName;
+    use crate::value_objects::video_title::VideoTitle;
+    use chrono::TimeZone;
+
+    #[test]
+    fn record_and_detect_changes() -> anyhow::Result<()> {
+        let channel = ChannelEntity::with_random_id(ChannelName::new("channel")?);
+        let published_at = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let v = VideoEntity::with_random_id(VideoTitle::new("title")?, channel.clone())
+            .with_published_at(published_at)
+            .with_etag(VideoEtag::new("etag-1")?)
+            .construct()?;
+
+        let mut state = ChannelSyncStateEntity::new(channel.id);
+        assert!(state.is_changed(&v));
+        assert!(!state.is_known(published_at));
+
+        state.record(&v);
+        assert!(!state.is_changed(&v));
+        assert!(state.is_known(published_at));
+        assert!(!state.is_known(published_at + chrono::Duration::seconds(1)));
+
+        let edited = VideoEntity {
+            etag: Some(VideoEtag::new("etag-2")?),
+            ..v.clone()
+        };
+        assert!(state.is_changed(&edited));
+
+        let no_etag = VideoEntity { etag: None, ..v };
+        assert!(state.is_changed(&no_etag));
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use crate::entities::channel::ChannelEntity;
+use serde::{Deserialize, Serialize};
+
+/// 直近の動画を取得する既定の件数
+pub const DEFAULT_NUM_RECENT: u32 = 10;
+
+/// 取得対象として登録したチャンネルと、チャンネルごとの取得設定
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RegisteredChannelEntity {
+    pub channel: ChannelEntity,
+    /// 取得対象とするか
+    pub enabled: bool,
+    /// 直近の動画を取得する件数
+    pub num_recent: u32,
+    /// タイムスタンプ抽出ルールのプロファイル名。無ければ既定のルール
+    pub parser_profile: Option<String>,
+}
+
+impl RegisteredChannelEntity {
+    pub fn new(channel: ChannelEntity) -> Self {
+        Self {
+            channel,
+            enabled: true,
+            num_recent: DEFAULT_NUM_RECENT,
+            parser_profile: None,
+        }
+    }
+
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    pub fn with_num_recent(mut self, num_recent: u32) -> Self {
+        self.num_recent = num_recent;
+        self
+    }
+
+    pub fn with_parser_profile(mut self, parser_profile: Option<String>) -> Self {
+        self.parser_profile = parser_profile;
+        self
+    }
+}
@@ -1,6 +1,11 @@
 use crate::entities::channel::ChannelEntity;
+use crate::value_objects::broadcast_state::BroadcastState;
+use crate::value_objects::playlist_id::PlaylistId;
 use crate::value_objects::thumbnail::Thumbnail;
+use crate::value_objects::video_availability::VideoAvailability;
 use crate::value_objects::video_description::VideoDescription;
+use crate::value_objects::video_duration::VideoDuration;
+use crate::value_objects::video_etag::VideoEtag;
 use crate::value_objects::video_id::VideoId;
 use crate::value_objects::video_tag::VideoTag;
 use crate::value_objects::video_title::VideoTitle;
@@ -20,9 +25,47 @@ pub struct VideoEntity {
     pub thumbnail: Option<Thumbnail>,
     pub published_at: DateTime<Utc>,
     pub actual_start_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub actual_end_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub broadcast_state: BroadcastState,
+    #[serde(default)]
+    pub duration: Option<VideoDuration>,
+    /// 取得元での版。内容が変わると変化する
+    #[serde(default)]
+    pub etag: Option<VideoEtag>,
+    #[serde(default)]
+    pub availability: VideoAvailability,
+    /// 取り込んだ再生リストのうち、この動画を含むもの
+    #[serde(default)]
+    pub playlists: Vec<PlaylistId>,
 }
 
 impl VideoEntity {
+    /// 再生リストへの所属を加える。既に含む場合は何もしない
+    pub fn add_playlist(&mut self, playlist_id: &PlaylistId) {
+        if !self.playlists.contains(playlist_id) {
+            self.playlists.push(playlist_id.clone());
+        }
+    }
+
+    /// 再生リストへの所属を外す。含まない場合は何もしない
+    pub fn remove_playlist(&mut self, playlist_id: &PlaylistId) {
+        self.playlists.retain(|p| p != playlist_id);
+    }
+
+    /// 配信が終わっていないか、ended_after 以降に終了した配信か。視聴できない動画は除く
+    pub fn is_recent_broadcast(&self, ended_after: DateTime<Utc>) -> bool {
+        if !self.availability.is_available() {
+            return false;
+        }
+        if self.broadcast_state.is_pending() {
+            return true;
+        }
+        self.broadcast_state == BroadcastState::Archived
+            && self.actual_end_at.is_some_and(|e| e >= ended_after)
+    }
+
     pub fn build(id: VideoId, title: VideoTitle, channel: ChannelEntity) -> VideoEntityBuilder {
         VideoEntityBuilder::new(id, title, channel)
     }
@@ -46,6 +89,12 @@ pub struct VideoEntityBuilder {
     thumbnail: Option<Thumbnail>,
     published_at: DateTime<Utc>,
     actual_start_at: Option<DateTime<Utc>>,
+    actual_end_at: Option<DateTime<Utc>>,
+    broadcast_state: BroadcastState,
+    duration: Option<VideoDuration>,
+    etag: Option<VideoEtag>,
+    availability: VideoAvailability,
+    playlists: Vec<PlaylistId>,
 }
 
 impl VideoEntityBuilder {
@@ -59,6 +108,12 @@ impl VideoEntityBuilder {
             thumbnail: None,
             published_at: Utc::now(),
             actual_start_at: None,
+            actual_end_at: None,
+            broadcast_state: BroadcastState::default(),
+            duration: None,
+            etag: None,
+            availability: VideoAvailability::default(),
+            playlists: Vec::new(),
         }
     }
 
@@ -87,6 +142,36 @@ impl VideoEntityBuilder {
         self
     }
 
+    pub fn with_actual_end_time(mut self, actual_end_time: DateTime<Utc>) -> Self {
+        self.actual_end_at = Some(actual_end_time);
+        self
+    }
+
+    pub fn with_broadcast_state(mut self, broadcast_state: BroadcastState) -> Self {
+        self.broadcast_state = broadcast_state;
+        self
+    }
+
+    pub fn with_duration(mut self, duration: VideoDuration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub fn with_etag(mut self, etag: VideoEtag) -> Self {
+        self.etag = Some(etag);
+        self
+    }
+
+    pub fn with_availability(mut self, availability: VideoAvailability) -> Self {
+        self.availability = availability;
+        self
+    }
+
+    pub fn with_playlists(mut self, playlists: Vec<PlaylistId>) -> Self {
+        self.playlists = playlists;
+        self
+    }
+
     pub fn construct(self) -> AppResult<VideoEntity> {
         Ok(VideoEntity {
             id: self.id,
@@ -97,6 +182,12 @@ impl VideoEntityBuilder {
             thumbnail: self.thumbnail,
             published_at: self.published_at,
             actual_start_at: self.actual_start_at,
+            actual_end_at: self.actual_end_at,
+            broadcast_state: self.broadcast_state,
+            duration: self.duration,
+            etag: self.etag,
+            availability: self.availability,
+            playlists: self.playlists,
         })
     }
 }
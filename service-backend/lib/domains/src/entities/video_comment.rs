@@ -0,0 +1,25 @@
+use crate::value_objects::comment_author::CommentAuthor;
+use crate::value_objects::comment_id::CommentId;
+use crate::value_objects::comment_text::CommentText;
+use crate::value_objects::video_id::VideoId;
+use serde::{Deserialize, Serialize};
+
+/// 動画に付けられたトップレベルコメント
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VideoCommentEntity {
+    pub id: CommentId,
+    pub video_id: VideoId,
+    pub author: CommentAuthor,
+    pub text: CommentText,
+}
+
+impl VideoCommentEntity {
+    pub fn new(id: CommentId, video_id: VideoId, author: CommentAuthor, text: CommentText) -> Self {
+        Self {
+            id,
+            video_id,
+            author,
+            text,
+        }
+    }
+}
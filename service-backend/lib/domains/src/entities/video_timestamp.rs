@@ -1,4 +1,9 @@
-use crate::value_objects::{timestamp::TimeStamp, video_detail::VideoDetail, video_id::VideoId};
+use crate::value_objects::{
+    confidence::Confidence, setlist_entry::SetlistEntry, timestamp::TimeStamp,
+    timestamp_id::TimestampId, timestamp_source::TimeStampSource, video_detail::VideoDetail,
+    video_id::VideoId,
+};
+use errors::AppResult;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Eq, Serialize, Deserialize)]
@@ -6,6 +11,21 @@ pub struct VideoTimestampEntity {
     pub video_id: VideoId,
     pub timestamp: TimeStamp,
     pub video_details: Option<VideoDetail>,
+    #[serde(default = "default_sources")]
+    pub sources: Vec<TimeStampSource>,
+    /// 説明文がセットリスト形式の場合の曲情報
+    #[serde(default)]
+    pub song: Option<SetlistEntry>,
+    /// 見出しの下にまとめられたタイムスタンプの場合、見出しのID
+    #[serde(default)]
+    pub parent_id: Option<TimestampId>,
+    /// 抽出の確からしさ。抽出時に付与されなかったものは None
+    #[serde(default)]
+    pub confidence: Option<Confidence>,
+}
+
+fn default_sources() -> Vec<TimeStampSource> {
+    vec![TimeStampSource::default()]
 }
 
 impl VideoTimestampEntity {
@@ -14,6 +34,10 @@ impl VideoTimestampEntity {
             video_id,
             timestamp,
             video_details: None,
+            sources: default_sources(),
+            song: None,
+            parent_id: None,
+            confidence: None,
         }
     }
 
@@ -26,6 +50,48 @@ impl VideoTimestampEntity {
             video_id,
             timestamp,
             video_details: details,
+            sources: default_sources(),
+            song: None,
+            parent_id: None,
+            confidence: None,
+        }
+    }
+
+    pub fn with_source(mut self, source: TimeStampSource) -> Self {
+        self.sources = vec![source];
+        self
+    }
+
+    pub fn with_sources(mut self, sources: Vec<TimeStampSource>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    pub fn with_song(mut self, song: Option<SetlistEntry>) -> Self {
+        self.song = song;
+        self
+    }
+
+    pub fn with_parent_id(mut self, parent_id: Option<TimestampId>) -> Self {
+        self.parent_id = parent_id;
+        self
+    }
+
+    pub fn with_confidence(mut self, confidence: Option<Confidence>) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    pub fn id(&self) -> AppResult<TimestampId> {
+        TimestampId::new(&self.video_id, &self.timestamp)
+    }
+
+    /// 取得元を追加する。既に記録済みの取得元は追加しない
+    pub fn merge_sources(&mut self, sources: &[TimeStampSource]) {
+        for s in sources {
+            if !self.sources.contains(s) {
+                self.sources.push(s.clone());
+            }
         }
     }
 }
@@ -1,4 +1,7 @@
+pub mod external_channel_repository;
 pub mod external_video_repository;
+pub mod internal_channel_repository;
+pub mod internal_sync_state_repository;
 pub mod internal_timestamp_repository;
 pub mod internal_timestamp_search_repository;
 pub mod internal_video_repository;
@@ -0,0 +1,16 @@
+// 外部サービスのチャンネルへアクセスするためのリポジトリ
+// 想定される外部サービス: YouTube
+use crate::value_objects::channel_id::ChannelId;
+use crate::value_objects::channel_reference::ChannelReference;
+use errors::AppResult;
+
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait ExternalChannelRepository {
+    /// ハンドル・ユーザー名・動画からチャンネルIDを引く
+    /// # Arguments
+    /// * `reference` - チャンネルの指定
+    /// # Returns
+    /// * `Result<ChannelId, String>` - チャンネルIDまたはエラーメッセージ。見つからない場合は `NotFound`
+    async fn fetch_channel_id(&self, reference: &ChannelReference) -> AppResult<ChannelId>;
+}
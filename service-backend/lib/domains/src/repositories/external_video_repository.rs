@@ -1,8 +1,15 @@
 // 外部サービスのビデオへアクセスするためのリポジトリ
 // 想定される外部サービス: YouTube
+use crate::entities::channel_sync_state::ChannelSyncStateEntity;
 use crate::entities::video::VideoEntity;
+use crate::entities::video_comment::VideoCommentEntity;
 use crate::value_objects::channel_id::ChannelId;
+use crate::value_objects::playlist_id::PlaylistId;
+use crate::value_objects::video_availability::VideoAvailability;
+use crate::value_objects::video_id::VideoId;
 use errors::AppResult;
+use futures::channel::mpsc::Sender;
+use std::collections::HashMap;
 
 #[cfg_attr(test, mockall::automock)]
 #[async_trait::async_trait]
@@ -19,6 +26,19 @@ pub trait ExternalVideoRepository {
         channel_id: &ChannelId,
     ) -> AppResult<Vec<VideoEntity>>;
 
+    /// チャンネルIDからすべてのビデオ情報を取得し、取得できたまとまりごとに公開日時の新しい順に送る
+    /// チャンネル全体を保持せずに後段の処理へ流すために使う
+    /// # Arguments
+    /// * `channel_id` - チャンネルID
+    /// * `sink` - ビデオ情報のまとまりの送り先。受け手が詰まっている間は取得を待つ
+    /// # Returns
+    /// * `Result<(), String>` - 送り終えたかエラーメッセージ
+    async fn stream_all_videos_by_channel_id(
+        &self,
+        channel_id: &ChannelId,
+        sink: Sender<Vec<VideoEntity>>,
+    ) -> AppResult<()>;
+
     /// チャンネルIDから直近のビデオ情報を取得する
     /// # Arguments
     /// * `channel_id` - チャンネルID
@@ -30,4 +50,62 @@ pub trait ExternalVideoRepository {
         channel_id: &ChannelId,
         count: u32,
     ) -> AppResult<Vec<VideoEntity>>;
+
+    /// 前回の取得状況をもとに、新しいビデオと変更のあったビデオのみを取得し、取得できたまとまりごとに送る
+    /// # Arguments
+    /// * `channel_id` - チャンネルID
+    /// * `state` - 前回の取得状況
+    /// * `count` - 少なくとも確認する直近のビデオの数。`None` の場合は全件を確認する
+    /// * `sink` - ビデオ情報のまとまりの送り先。受け手が詰まっている間は取得を待つ
+    /// # Returns
+    /// * `Result<ChannelSyncStateEntity, String>` - 更新後の取得状況、またはエラーメッセージ
+    async fn fetch_updated_videos_by_channel_id(
+        &self,
+        channel_id: &ChannelId,
+        state: &ChannelSyncStateEntity,
+        count: Option<u32>,
+        sink: Sender<Vec<VideoEntity>>,
+    ) -> AppResult<ChannelSyncStateEntity>;
+
+    /// ビデオIDからトップレベルコメントを取得する
+    /// # Arguments
+    /// * `video_id` - ビデオID
+    /// * `max_results` - 取得するコメントの最大数
+    /// # Returns
+    /// * `Result<Vec<VideoCommentEntity>, String>` - コメント情報またはエラーメッセージ
+    async fn fetch_comments_by_video_id(
+        &self,
+        video_id: &VideoId,
+        max_results: u32,
+    ) -> AppResult<Vec<VideoCommentEntity>>;
+
+    /// 再生リストIDから、再生リストに含まれるビデオ情報を取得する
+    /// 取得したビデオは再生リストへの所属を持つ
+    /// # Arguments
+    /// * `playlist_id` - 再生リストID
+    /// # Returns
+    /// * `Result<Vec<VideoEntity>, String>` - ビデオ情報のリストまたはエラーメッセージ
+    async fn fetch_videos_by_playlist_id(
+        &self,
+        playlist_id: &PlaylistId,
+    ) -> AppResult<Vec<VideoEntity>>;
+
+    /// ビデオIDからビデオ情報を取得する
+    /// 削除・非公開などで取得できなかったビデオは含まない
+    /// # Arguments
+    /// * `video_ids` - ビデオID
+    /// # Returns
+    /// * `Result<Vec<VideoEntity>, String>` - ビデオ情報のリストまたはエラーメッセージ
+    async fn fetch_videos_by_ids(&self, video_ids: &[VideoId]) -> AppResult<Vec<VideoEntity>>;
+
+    /// ビデオIDごとに、取得元での公開状態を取得する
+    /// 削除・非公開などで取得できなかったビデオは視聴できないものとする
+    /// # Arguments
+    /// * `video_ids` - ビデオID
+    /// # Returns
+    /// * `Result<HashMap<VideoId, VideoAvailability>, String>` - ビデオIDごとの公開状態またはエラーメッセージ
+    async fn fetch_video_availabilities(
+        &self,
+        video_ids: &[VideoId],
+    ) -> AppResult<HashMap<VideoId, VideoAvailability>>;
 }
@@ -0,0 +1,20 @@
+use crate::entities::registered_channel::RegisteredChannelEntity;
+use crate::value_objects::channel_id::ChannelId;
+use errors::AppResult;
+
+/// 取得対象として登録したチャンネルの保存先
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait InternalChannelRepository {
+    /// 登録済みのものは上書きする
+    async fn add_or_update_channels(&self, channels: &[RegisteredChannelEntity]) -> AppResult<()>;
+
+    async fn get_channel_by_id(
+        &self,
+        channel_id: &ChannelId,
+    ) -> AppResult<Option<RegisteredChannelEntity>>;
+
+    async fn get_all_channels(&self) -> AppResult<Vec<RegisteredChannelEntity>>;
+
+    async fn delete_channel_by_id(&self, channel_id: &ChannelId) -> AppResult<()>;
+}
@@ -0,0 +1,16 @@
+use crate::entities::channel_sync_state::ChannelSyncStateEntity;
+use crate::value_objects::channel_id::ChannelId;
+use errors::AppResult;
+
+/// チャンネルごとの取得状況の保存先
+#[cfg_attr(test, mockall::automock)]
+#[async_trait::async_trait]
+pub trait InternalSyncStateRepository {
+    async fn get_sync_state(
+        &self,
+        channel_id: &ChannelId,
+    ) -> AppResult<Option<ChannelSyncStateEntity>>;
+
+    /// 保存済みのものは上書きする
+    async fn save_sync_state(&self, state: &ChannelSyncStateEntity) -> AppResult<()>;
+}
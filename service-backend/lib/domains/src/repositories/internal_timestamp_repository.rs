@@ -47,6 +47,12 @@ pub trait InternalVideoTimeStampRepository {
 
     async fn get_all_video_timestamp_entities(&self) -> AppResult<Vec<VideoTimestampEntity>>;
 
+    /// 動画のタイムスタンプをすべて取得する
+    async fn get_video_timestamp_entities_by_video_id(
+        &self,
+        video_id: &VideoId,
+    ) -> AppResult<Vec<VideoTimestampEntity>>;
+
     async fn delete_video_timestamp_entity_by_id(&self, video_id: &VideoId) -> AppResult<()>;
 
     async fn delete_video_timestamp_entity_by_video_id(&self, video_id: &VideoId) -> AppResult<()>;
@@ -1,7 +1,14 @@
 use crate::entities::video_timestamp::VideoTimestampEntity;
+use crate::value_objects::channel_id::ChannelId;
+use crate::value_objects::confidence::Confidence;
+use crate::value_objects::elapsed_time::ElapsedTime;
 use crate::value_objects::page::Page;
 use crate::value_objects::per_page::PerPage;
+use crate::value_objects::playlist_id::PlaylistId;
 use crate::value_objects::search_query_text::SearchQueryText;
+use crate::value_objects::song_artist::SongArtist;
+use crate::value_objects::song_key::SongKey;
+use crate::value_objects::video_duration::VideoDuration;
 use crate::value_objects::{limit::Limit, video_id::VideoId, video_tag::VideoTag};
 use chrono::{DateTime, Utc};
 use errors::{AppError, AppResult};
@@ -14,6 +21,20 @@ pub trait InternalVideoTimeStampSearchRepository {
         &self,
         query: VideoTimestampSearchQuery,
     ) -> AppResult<VideoTimestampSearchResult>;
+
+    /// 同じ曲のタイムスタンプを動画の詳細付きですべて取得する。視聴できない動画のものは除く
+    async fn find_timestamps_by_song_key(
+        &self,
+        key: &SongKey,
+        limit: Limit,
+    ) -> AppResult<Vec<VideoTimestampEntity>>;
+
+    /// 動画のタイムスタンプを経過時間順にすべて取得する
+    async fn find_timestamps_by_video_id(
+        &self,
+        video_id: &VideoId,
+        limit: Limit,
+    ) -> AppResult<Vec<VideoTimestampEntity>>;
 }
 
 /// 検索クエリ
@@ -22,9 +43,21 @@ pub struct VideoTimestampSearchQuery {
     pub query: SearchQueryText,
     pub video_ids: Option<Vec<VideoId>>,
     pub video_tags: Option<Vec<VideoTag>>,
+    pub channel_id: Option<ChannelId>,
+    /// 再生リストに含まれる動画のみに絞る
+    pub playlist_id: Option<PlaylistId>,
     pub actual_start_from: Option<DateTime<Utc>>, // Unix timestamp in seconds
     pub actual_start_to: Option<DateTime<Utc>>,   // Unix timestamp in seconds
     pub actual_start_at: Option<DateTime<Utc>>,
+    pub elapsed_time_from: Option<ElapsedTime>,
+    pub elapsed_time_to: Option<ElapsedTime>,
+    pub video_duration_min: Option<VideoDuration>,
+    pub video_duration_max: Option<VideoDuration>,
+    pub artist: Option<SongArtist>,
+    /// 抽出の確からしさの下限。下回るタイムスタンプは除外する
+    pub min_confidence: Option<Confidence>,
+    /// 削除・非公開などで視聴できない動画も含める
+    pub include_unavailable: bool,
     pub parts: Option<Vec<Part>>,
     pub limit: Limit,
     pub page: Page,
@@ -48,6 +81,7 @@ pub enum Part {
     ThumbnailUrl,
     ActualStartAt,
     PublishedAt,
+    Duration,
 }
 
 impl FromStr for Part {
@@ -61,6 +95,7 @@ impl FromStr for Part {
             "thumbnailUrl" => Ok(Part::ThumbnailUrl),
             "actualStartAt" => Ok(Part::ActualStartAt),
             "publishedAt" => Ok(Part::PublishedAt),
+            "duration" => Ok(Part::Duration),
             _ => Err(AppError::InvalidInput(format!(
                 r#"Invalid part. \
                     required 'videoDetail',\
@@ -68,7 +103,8 @@ impl FromStr for Part {
                     'videoTags',\
                     'thumbnailUrl',\
                     'actualStartAt',\
-                    'publishedAt'.\
+                    'publishedAt',\
+                    'duration'.\
                     input : {}"#,
                 s
             ))),
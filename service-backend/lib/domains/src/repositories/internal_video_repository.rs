@@ -1,5 +1,7 @@
 use crate::entities::video::VideoEntity;
+use crate::value_objects::playlist_id::PlaylistId;
 use crate::value_objects::video_id::VideoId;
+use chrono::{DateTime, Utc};
 use errors::AppResult;
 
 #[cfg_attr(test, mockall::automock)]
@@ -17,8 +19,24 @@ pub trait InternalVideoRepository {
 
     async fn get_video_entity_by_id(&self, video_id: &VideoId) -> AppResult<Option<VideoEntity>>;
 
+    /// IDの一致する動画をまとめて取得する。保存されていないIDは無視する
+    async fn get_video_entities_by_ids(&self, video_ids: &[VideoId])
+    -> AppResult<Vec<VideoEntity>>;
+
     async fn get_all_video_entities(&self) -> AppResult<Vec<VideoEntity>>;
 
+    /// 再生リストに所属する動画を取得する
+    async fn get_video_entities_by_playlist_id(
+        &self,
+        playlist_id: &PlaylistId,
+    ) -> AppResult<Vec<VideoEntity>>;
+
+    /// 配信が終わっていない動画と、ended_after 以降に終了した配信を取得する。視聴できない動画は除く
+    async fn get_recent_broadcast_entities(
+        &self,
+        ended_after: DateTime<Utc>,
+    ) -> AppResult<Vec<VideoEntity>>;
+
     async fn delete_video_entity_by_id(&self, video_id: &VideoId) -> AppResult<()>;
 
     async fn delete_all_video_entities(&self) -> AppResult<()>;
@@ -33,8 +51,10 @@ mod unit_tests {
     };
     use crate::value_objects::channel_id::ChannelId;
     use crate::value_objects::channel_name::ChannelName;
+    use crate::value_objects::playlist_id::PlaylistId;
     use crate::value_objects::video_id::VideoId;
     use crate::value_objects::video_title::VideoTitle;
+    use chrono::{DateTime, Utc};
     use errors::{AppError, AppResult};
     use mockall::predicate::eq;
     use std::collections::HashMap;
@@ -109,6 +129,20 @@ mod unit_tests {
             Ok(db.get(video_id).cloned())
         }
 
+        async fn get_video_entities_by_ids(
+            &self,
+            video_ids: &[VideoId],
+        ) -> AppResult<Vec<VideoEntity>> {
+            let db = self
+                .db
+                .lock()
+                .map_err(|_| AppError::InternalServerError(anyhow::anyhow!("mutex poisoned.")))?;
+            Ok(video_ids
+                .iter()
+                .filter_map(|id| db.get(id).cloned())
+                .collect())
+        }
+
         async fn get_all_video_entities(&self) -> AppResult<Vec<VideoEntity>> {
             let db = self
                 .db
@@ -118,6 +152,36 @@ mod unit_tests {
             Ok(vs)
         }
 
+        async fn get_video_entities_by_playlist_id(
+            &self,
+            playlist_id: &PlaylistId,
+        ) -> AppResult<Vec<VideoEntity>> {
+            let db = self
+                .db
+                .lock()
+                .map_err(|_| AppError::InternalServerError(anyhow::anyhow!("mutex poisoned.")))?;
+            Ok(db
+                .values()
+                .filter(|v| v.playlists.contains(playlist_id))
+                .cloned()
+                .collect())
+        }
+
+        async fn get_recent_broadcast_entities(
+            &self,
+            ended_after: DateTime<Utc>,
+        ) -> AppResult<Vec<VideoEntity>> {
+            let db = self
+                .db
+                .lock()
+                .map_err(|_| AppError::InternalServerError(anyhow::anyhow!("mutex poisoned.")))?;
+            Ok(db
+                .values()
+                .filter(|v| v.is_recent_broadcast(ended_after))
+                .cloned()
+                .collect())
+        }
+
         async fn delete_video_entity_by_id(&self, video_id: &VideoId) -> AppResult<()> {
             let mut db = self
                 .db
@@ -1,19 +1,35 @@
+pub mod broadcast_state;
 pub mod channel_id;
 pub mod channel_name;
+pub mod channel_reference;
+pub mod comment_author;
+pub mod comment_id;
+pub mod comment_text;
+pub mod confidence;
 pub mod elapsed_time;
 pub mod height;
 pub mod limit;
 pub mod offset;
 pub mod page;
 pub mod per_page;
+pub mod playlist_id;
 pub mod search_query_text;
+pub mod setlist_entry;
+pub mod song_artist;
+pub mod song_key;
+pub mod song_tag;
+pub mod song_title;
 pub mod thumbnail;
 pub mod thumbnail_url;
 pub mod timestamp;
 pub mod timestamp_description;
 pub mod timestamp_id;
+pub mod timestamp_source;
+pub mod video_availability;
 pub mod video_description;
 pub mod video_detail;
+pub mod video_duration;
+pub mod video_etag;
 pub mod video_id;
 pub mod video_tag;
 pub mod video_title;
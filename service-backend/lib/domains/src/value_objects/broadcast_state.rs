@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// 動画の配信の状態
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BroadcastState {
+    /// 配信予定
+    Upcoming,
+    /// 配信中
+    Live,
+    /// 配信のアーカイブ
+    Archived,
+    /// プレミア公開の予定・公開中
+    Premiere,
+    /// 通常の動画
+    #[default]
+    Upload,
+    /// ショート動画
+    Short,
+}
+
+impl BroadcastState {
+    /// 配信が終わっておらず、後で取得し直す必要があるか
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self,
+            BroadcastState::Upcoming | BroadcastState::Live | BroadcastState::Premiere
+        )
+    }
+}
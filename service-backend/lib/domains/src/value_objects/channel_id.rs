@@ -6,7 +6,7 @@ impl ChannelId {
     /// * `id` - A string slice that holds the channel ID.
     /// # Example
     /// ```
-    /// use domains::value_objects::ChannelId;
+    /// use domains::value_objects::channel_id::ChannelId;
     /// let channel_id = ChannelId::new("UC_x5XG1OV2P6uZZ5FSM9Ttw");
     /// assert!(channel_id.is_ok());
     /// let invalid_channel_id = ChannelId::new("invalid");
@@ -7,7 +7,7 @@ impl ChannelName {
     /// * `name` - A string slice that holds the name of the channel.
     /// # Example
     /// ```
-    /// use domains::value_objects::ChannelName;
+    /// use domains::value_objects::channel_name::ChannelName;
     /// let channel_name = ChannelName::new("MyChannel");
     /// assert!(channel_name.is_ok());
     /// let invalid_channel_name = ChannelName::new("");
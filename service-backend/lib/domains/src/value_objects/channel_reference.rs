@@ -0,0 +1,181 @@
+use crate::value_objects::channel_id::ChannelId;
+use crate::value_objects::video_id::VideoId;
+use errors::{AppError, AppResult};
+use std::fmt;
+
+const YOUTUBE_HOSTS: [&str; 4] = [
+    "youtube.com",
+    "www.youtube.com",
+    "m.youtube.com",
+    "music.youtube.com",
+];
+
+/// チャンネルの指定
+///
+/// チャンネルIDのほか、`@handle` やチャンネル・動画の URL を受け付ける。
+/// チャンネルID以外は取得元でチャンネルIDに解決する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChannelReference {
+    /// `UC` で始まるチャンネルID
+    Id(ChannelId),
+    /// `@` を除いたハンドル
+    Handle(String),
+    /// 旧形式の `/user/<name>` のユーザー名
+    Username(String),
+    /// チャンネルの動画
+    Video(VideoId),
+}
+
+impl ChannelReference {
+    /// 前後の空白は無視する。スキームの無い URL も受け付ける
+    ///
+    /// カスタム URL（`/c/<name>`）を引く API は無く、同名のハンドルが別のチャンネルのこともあるため受け付けない。
+    pub fn parse(s: &str) -> AppResult<Self> {
+        let s = s.trim();
+        if let Ok(id) = ChannelId::new(s) {
+            return Ok(Self::Id(id));
+        }
+        if let Some(handle) = s.strip_prefix('@') {
+            return Self::handle(handle);
+        }
+
+        let url = match s.contains("://") {
+            true => s.to_string(),
+            false => format!("https://{}", s),
+        };
+        let url = url::Url::parse(&url).map_err(|_| unsupported(s))?;
+        let host = url.host_str().unwrap_or_default();
+        let segments = url
+            .path_segments()
+            .map(|p| p.filter(|s| !s.is_empty()).map(decode).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+
+        match (host, segments.as_slice()) {
+            ("youtu.be", [id, ..]) => Ok(Self::Video(VideoId::new(id)?)),
+            (host, _) if !YOUTUBE_HOSTS.contains(&host) => Err(unsupported(s)),
+            (_, ["channel", id, ..]) => Ok(Self::Id(ChannelId::new(id)?)),
+            (_, ["user", name, ..]) => Ok(Self::Username(name.to_string())),
+            (_, ["c", name, ..]) => Err(AppError::DomainParseError(format!(
+                "Custom URL cannot be resolved. Use the channel ID or @handle instead : /c/{}",
+                name
+            ))),
+            (_, ["watch"]) => {
+                let id = url
+                    .query_pairs()
+                    .find(|(k, _)| k == "v")
+                    .ok_or_else(|| unsupported(s))?
+                    .1;
+                Ok(Self::Video(VideoId::new(&id)?))
+            }
+            (_, ["live" | "shorts" | "embed", id, ..]) => Ok(Self::Video(VideoId::new(id)?)),
+            (_, [name, ..]) if name.starts_with('@') => Self::handle(&name[1..]),
+            _ => Err(unsupported(s)),
+        }
+    }
+
+    /// ハンドルは 3 ~ 30 文字の英数字・`_`・`-`・`.`
+    fn handle(handle: &str) -> AppResult<Self> {
+        if !(3..=30).contains(&handle.chars().count()) {
+            return Err(AppError::DomainParseError(format!(
+                "Handle must be 3 to 30 characters long : {}",
+                handle
+            )));
+        }
+        if !handle
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '·'))
+        {
+            return Err(AppError::DomainParseError(format!(
+                "Handle must contain only letters, digits, underscores, hyphens or periods : {}",
+                handle
+            )));
+        }
+        Ok(Self::Handle(handle.to_string()))
+    }
+}
+
+impl fmt::Display for ChannelReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChannelReference::Id(id) => write!(f, "{}", id),
+            ChannelReference::Handle(handle) => write!(f, "@{}", handle),
+            ChannelReference::Username(name) => write!(f, "user/{}", name),
+            ChannelReference::Video(id) => write!(f, "video {}", id),
+        }
+    }
+}
+
+/// パーセントエンコードされたパスの一部を戻す
+fn decode(segment: &str) -> String {
+    percent_encoding::percent_decode_str(segment)
+        .decode_utf8_lossy()
+        .into_owned()
+}
+
+fn unsupported(s: &str) -> AppError {
+    AppError::DomainParseError(format!(
+        "Channel must be a channel ID, @handle, or channel or video URL : {}",
+        s
+    ))
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use rstest::rstest;
+
+    const CHANNEL_ID: &str = "UC_x5XG1OV2P6uZZ5FSM9Ttw";
+
+    fn id() -> ChannelReference {
+        ChannelReference::Id(ChannelId::new(CHANNEL_ID).unwrap())
+    }
+
+    fn handle(h: &str) -> ChannelReference {
+        ChannelReference::Handle(h.to_string())
+    }
+
+    fn video() -> ChannelReference {
+        ChannelReference::Video(VideoId::new("aqz-KE-bpKQ").unwrap())
+    }
+
+    #[rstest]
+    #[case::id(CHANNEL_ID, id())]
+    #[case::padded(" UC_x5XG1OV2P6uZZ5FSM9Ttw\n", id())]
+    #[case::channel_url("https://www.youtube.com/channel/UC_x5XG1OV2P6uZZ5FSM9Ttw", id())]
+    #[case::channel_tab("youtube.com/channel/UC_x5XG1OV2P6uZZ5FSM9Ttw/videos", id())]
+    #[case::handle("@GoogleDevelopers", handle("GoogleDevelopers"))]
+    #[case::handle_url(
+        "https://www.youtube.com/@GoogleDevelopers",
+        handle("GoogleDevelopers")
+    )]
+    #[case::handle_tab("m.youtube.com/@google.dev/streams", handle("google.dev"))]
+    #[case::encoded_handle(
+        "https://www.youtube.com/@%E3%83%86%E3%82%B9%E3%83%88",
+        handle("テスト")
+    )]
+    #[case::username(
+        "http://youtube.com/user/GoogleDevelopers",
+        ChannelReference::Username("GoogleDevelopers".to_string())
+    )]
+    #[case::watch("https://www.youtube.com/watch?v=aqz-KE-bpKQ&t=60s", video())]
+    #[case::short_url("https://youtu.be/aqz-KE-bpKQ?si=share", video())]
+    #[case::live("https://www.youtube.com/live/aqz-KE-bpKQ", video())]
+    #[case::shorts("youtube.com/shorts/aqz-KE-bpKQ", video())]
+    fn parse_references(#[case] s: &str, #[case] expected: ChannelReference) {
+        assert_eq!(ChannelReference::parse(s).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::short_handle("@ab")]
+    #[case::invalid_handle("@google developers")]
+    #[case::other_host("https://example.com/@GoogleDevelopers")]
+    #[case::invalid_channel_id("https://www.youtube.com/channel/invalid")]
+    #[case::watch_without_id("https://www.youtube.com/watch?list=PL0123456789")]
+    #[case::home("https://www.youtube.com/")]
+    #[case::custom_url("https://www.youtube.com/c/GoogleDevelopers")]
+    fn reject_references(#[case] s: &str) {
+        assert!(ChannelReference::parse(s).is_err());
+    }
+}
@@ -0,0 +1,14 @@
+use errors::{AppError, AppResult};
+
+types::impl_string_value!(CommentAuthor);
+
+impl CommentAuthor {
+    pub fn new(name: &str) -> AppResult<Self> {
+        if name.is_empty() {
+            return Err(AppError::DomainParseError(
+                "Comment author cannot be empty".to_string(),
+            ));
+        }
+        Ok(CommentAuthor(name.to_string()))
+    }
+}
@@ -0,0 +1,14 @@
+use errors::{AppError, AppResult};
+
+types::impl_string_value!(CommentId);
+
+impl CommentId {
+    pub fn new(id: &str) -> AppResult<Self> {
+        if id.is_empty() {
+            return Err(AppError::DomainParseError(
+                "Comment ID cannot be empty".to_string(),
+            ));
+        }
+        Ok(CommentId(id.to_string()))
+    }
+}
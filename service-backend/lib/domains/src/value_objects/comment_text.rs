@@ -0,0 +1,9 @@
+use errors::AppResult;
+
+types::impl_string_value!(CommentText);
+
+impl CommentText {
+    pub fn new(text: &str) -> AppResult<Self> {
+        Ok(CommentText(text.to_string()))
+    }
+}
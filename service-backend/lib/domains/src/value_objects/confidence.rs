@@ -0,0 +1,34 @@
+use errors::AppError::DomainParseError;
+use errors::AppResult;
+use types::impl_numeric_value;
+
+impl_numeric_value!(Confidence, u8);
+
+impl Confidence {
+    pub const MAX: u8 = 100;
+
+    /// タイムスタンプ抽出の確からしさ（0 ~ 100）
+    pub fn new(score: u8) -> AppResult<Self> {
+        if score > Self::MAX {
+            return Err(DomainParseError(format!(
+                "from Confidence : must be 0 ~ {} : {}",
+                Self::MAX,
+                score
+            )));
+        }
+        Ok(Confidence(score))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[rstest::rstest]
+    #[case(0, true)]
+    #[case(100, true)]
+    #[case(101, false)]
+    fn new_confidence(#[case] score: u8, #[case] ok: bool) {
+        assert_eq!(Confidence::new(score).is_ok(), ok);
+    }
+}
@@ -9,41 +9,110 @@ impl ElapsedTime {
         Ok(ElapsedTime(seconds))
     }
 
-    pub fn from_hhmmss(s: &str) -> AppResult<Self> {
-        {
-            let parts: Vec<&str> = s.split(':').collect();
-
-            match parts.len() {
-                2 => {
-                    let minutes = parts[0]
-                        .parse::<u64>()
-                        .map_err(|e| DomainParseError(e.to_string()))?;
-                    let seconds = parts[1]
-                        .parse::<u64>()
-                        .map_err(|e| DomainParseError(e.to_string()))?;
-                    Self::new(minutes * 60 + seconds)
-                }
-                3 => {
-                    let hours = parts[0]
-                        .parse::<u64>()
-                        .map_err(|e| DomainParseError(e.to_string()))?;
-                    let minutes = parts[1]
-                        .parse::<u64>()
-                        .map_err(|e| DomainParseError(e.to_string()))?;
-                    let seconds = parts[2]
-                        .parse::<u64>()
-                        .map_err(|e| DomainParseError(e.to_string()))?;
-                    Self::new(hours * 60 * 60 + minutes * 60 + seconds)
-                }
-                _ => Err(DomainParseError(format!(
-                    "from ElapsedTime : Invalid time format : {}",
-                    s
-                ))),
+    /// 時刻表記から経過時間を作る
+    ///
+    /// `h:mm:ss` / `mm:ss` に加えて、全角数字・全角コロン（`１２：３４`）、
+    /// `1時間2分3秒` や `12分34秒`、`1h02m03s` のような単位付きの表記を受け付ける。
+    pub fn from_notation(s: &str) -> AppResult<Self> {
+        let s = normalize_width(s.trim());
+        if s.contains(':') {
+            Self::from_hhmmss(&s)
+        } else {
+            Self::from_units(&s)
+        }
+    }
+
+    /// `1h02m03s` や `1時間2分3秒` のような単位付き表記。単位は時・分・秒の順で各1回まで
+    fn from_units(s: &str) -> AppResult<Self> {
+        let invalid =
+            || DomainParseError(format!("from ElapsedTime : Invalid time format : {}", s));
+
+        let mut total = 0u64;
+        let mut digits = String::new();
+        // 直前に読んだ単位の順位（時:3, 分:2, 秒:1）。後の単位ほど小さくなければならない
+        let mut last_rank = 4;
+        let mut rest = s;
+
+        while let Some(c) = rest.chars().next() {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+
+            let (rank, scale, len) = UNITS
+                .iter()
+                .find(|(unit, ..)| rest.starts_with(unit))
+                .map(|(unit, rank, scale)| (*rank, *scale, unit.len()))
+                .ok_or_else(invalid)?;
+            if digits.is_empty() || rank >= last_rank {
+                return Err(invalid());
             }
+
+            let value = digits
+                .parse::<u64>()
+                .map_err(|e| DomainParseError(e.to_string()))?;
+            total = value
+                .checked_mul(scale)
+                .and_then(|v| total.checked_add(v))
+                .ok_or_else(invalid)?;
+            digits.clear();
+            last_rank = rank;
+            rest = &rest[len..];
+        }
+
+        if !digits.is_empty() || last_rank == 4 {
+            return Err(invalid());
         }
+        Self::new(total)
+    }
+
+    /// `h:mm:ss` / `mm:ss`。桁が大きすぎて秒に直せない場合はエラー
+    pub fn from_hhmmss(s: &str) -> AppResult<Self> {
+        let invalid =
+            || DomainParseError(format!("from ElapsedTime : Invalid time format : {}", s));
+
+        let parts: Vec<&str> = s.split(':').collect();
+        if !(2..=3).contains(&parts.len()) {
+            return Err(invalid());
+        }
+
+        let mut total = 0u64;
+        for p in parts {
+            let value = p
+                .parse::<u64>()
+                .map_err(|e| DomainParseError(e.to_string()))?;
+            total = total
+                .checked_mul(60)
+                .and_then(|t| t.checked_add(value))
+                .ok_or_else(invalid)?;
+        }
+        Self::new(total)
     }
 }
 
+/// 単位の表記、順位、秒への換算。前方一致で探すため長い表記を先に置く
+const UNITS: &[(&str, u8, u64)] = &[
+    ("時間", 3, 3600),
+    ("時", 3, 3600),
+    ("h", 3, 3600),
+    ("分", 2, 60),
+    ("m", 2, 60),
+    ("秒", 1, 1),
+    ("s", 1, 1),
+];
+
+/// 全角数字・全角コロンを半角に変換する
+fn normalize_width(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+            '：' => ':',
+            _ => c,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod unit_tests {
     use super::*;
@@ -72,7 +141,42 @@ mod unit_tests {
     #[test]
     #[case(":12:23")]
     #[case("23")]
+    #[case::overflow_hours("99999999999999999:00:00")]
+    #[case::overflow_minutes("307445734561825861:00")]
     fn invalid_hhmmss(#[case] fmt: &str) {
         assert!(ElapsedTime::from_hhmmss(fmt).is_err());
     }
+
+    #[rstest::rstest]
+    #[test]
+    #[case::ascii_colon("1:02:03", 3723)]
+    #[case::full_width_colon("１２：３４", 754)]
+    #[case::full_width_hhmmss("１：０２：０３", 3723)]
+    #[case::japanese_hms("1時間2分3秒", 3723)]
+    #[case::japanese_short_hour("1時2分3秒", 3723)]
+    #[case::japanese_ms("12分34秒", 754)]
+    #[case::japanese_full_width("１２分３４秒", 754)]
+    #[case::unit_hms("1h02m03s", 3723)]
+    #[case::unit_ms("2m03s", 123)]
+    #[case::unit_hours_only("1h", 3600)]
+    #[case::unit_seconds_only("45s", 45)]
+    fn valid_notation(#[case] fmt: &str, #[case] expected: u64) {
+        assert_eq!(ElapsedTime::from_notation(fmt).unwrap(), expected);
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case::no_unit("123")]
+    #[case::trailing_digits("1h02")]
+    #[case::unit_without_digits("h02m")]
+    #[case::reversed_units("3s2m")]
+    #[case::duplicated_units("1m2m")]
+    #[case::unknown_unit("1d")]
+    #[case::empty("")]
+    #[case::overflow_hours("9999999999999999h")]
+    #[case::overflow_sum("5124095576030431h16s")]
+    #[case::overflow_colon("99999999999999999:00:00")]
+    fn invalid_notation(#[case] fmt: &str) {
+        assert!(ElapsedTime::from_notation(fmt).is_err());
+    }
 }
@@ -0,0 +1,39 @@
+use errors::{AppError, AppResult};
+types::impl_string_value!(PlaylistId);
+
+impl PlaylistId {
+    /// 再生リストID。種類によって長さが異なるため、文字種と長さの範囲のみを確かめる
+    pub fn new(id: &str) -> AppResult<Self> {
+        if !(2..=64).contains(&id.len()) {
+            return Err(AppError::InvalidInput(
+                "Playlist ID must be 2 to 64 characters long".to_string(),
+            ));
+        }
+        if !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(AppError::InvalidInput(
+                "Playlist ID must contain only alphanumeric characters, hyphens, or underscores"
+                    .to_string(),
+            ));
+        }
+        Ok(PlaylistId(id.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("PLrAXtmErZgOeiKm4sgNOknGvNjby9efdf", true)]
+    #[case("UU_x5XG1OV2P6uZZ5FSM9Ttw", true)]
+    #[case("P", false)]
+    #[case("PL invalid", false)]
+    #[case("PL\"quoted\"", false)]
+    fn playlist_id(#[case] id: &str, #[case] valid: bool) {
+        assert_eq!(PlaylistId::new(id).is_ok(), valid);
+    }
+}
@@ -0,0 +1,24 @@
+use crate::value_objects::song_artist::SongArtist;
+use crate::value_objects::song_tag::SongTag;
+use crate::value_objects::song_title::SongTitle;
+use serde::{Deserialize, Serialize};
+
+/// セットリストの１曲分。タイムスタンプの説明文から曲名・アーティスト・タグを分離したもの
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetlistEntry {
+    pub title: SongTitle,
+    pub artist: Option<SongArtist>,
+    #[serde(default)]
+    pub tags: Vec<SongTag>,
+}
+
+impl SetlistEntry {
+    pub fn new(title: SongTitle, artist: Option<SongArtist>, tags: Vec<SongTag>) -> Self {
+        SetlistEntry {
+            title,
+            artist,
+            tags,
+        }
+    }
+}
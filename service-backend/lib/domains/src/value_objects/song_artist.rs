@@ -0,0 +1,15 @@
+use errors::{AppError, AppResult};
+
+types::impl_string_value!(SongArtist);
+
+impl SongArtist {
+    pub fn new(artist: &str) -> AppResult<Self> {
+        let artist = artist.trim();
+        if artist.is_empty() {
+            return Err(AppError::DomainParseError(
+                "Song artist cannot be empty".to_string(),
+            ));
+        }
+        Ok(SongArtist(artist.to_string()))
+    }
+}
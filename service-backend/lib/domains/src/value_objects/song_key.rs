@@ -0,0 +1,40 @@
+use crate::value_objects::timestamp_description::fold_text;
+use errors::{AppError, AppResult};
+
+types::impl_string_value!(SongKey);
+
+impl SongKey {
+    /// 曲名・説明文から同じ曲をまとめるためのキーを作る
+    ///
+    /// 全角・半角、大文字・小文字、空白・記号の違いを吸収する。
+    /// 既にキーになっている文字列を渡しても同じキーになる。
+    pub fn new(text: &str) -> AppResult<Self> {
+        let key = fold_text(text);
+        if key.is_empty() {
+            return Err(AppError::InvalidInput(
+                "Song key cannot be empty".to_string(),
+            ));
+        }
+        Ok(SongKey(key))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[rstest::rstest]
+    #[test]
+    #[case("Song Title!", "songtitle")]
+    #[case("ＳＯＮＧ　ＴＩＴＬＥ", "songtitle")]
+    #[case("songtitle", "songtitle")]
+    #[case("曲名（Ver.2）", "曲名ver2")]
+    fn song_key(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(SongKey::new(text).unwrap(), expected);
+    }
+
+    #[test]
+    fn empty_song_key() {
+        assert!(SongKey::new(" !? ").is_err());
+    }
+}
@@ -0,0 +1,15 @@
+use errors::{AppError, AppResult};
+
+types::impl_string_value!(SongTag);
+
+impl SongTag {
+    pub fn new(tag: &str) -> AppResult<Self> {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return Err(AppError::DomainParseError(
+                "Song tag cannot be empty".to_string(),
+            ));
+        }
+        Ok(SongTag(tag.to_string()))
+    }
+}
@@ -0,0 +1,15 @@
+use errors::{AppError, AppResult};
+
+types::impl_string_value!(SongTitle);
+
+impl SongTitle {
+    pub fn new(title: &str) -> AppResult<Self> {
+        let title = title.trim();
+        if title.is_empty() {
+            return Err(AppError::DomainParseError(
+                "Song title cannot be empty".to_string(),
+            ));
+        }
+        Ok(SongTitle(title.to_string()))
+    }
+}
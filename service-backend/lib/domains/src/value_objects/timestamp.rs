@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 pub struct TimeStamp {
     pub elapsed_time: ElapsedTime,
     pub description: TimeStampDescription,
+    /// 区間の終了時間。次のタイムスタンプまたは動画の長さから導出する
+    #[serde(default)]
+    pub end_time: Option<ElapsedTime>,
 }
 
 impl TimeStamp {
@@ -19,6 +22,42 @@ impl TimeStamp {
                 .try_into()
                 .map_err(|_| AppError::DomainParseError("Invalid seconds".to_string()))?,
             description,
+            end_time: None,
         })
     }
+
+    pub fn with_end_time(mut self, end_time: ElapsedTime) -> Self {
+        self.end_time = Some(end_time);
+        self
+    }
+
+    /// 区間の長さ。終了時間が不明、または開始より前の場合は None
+    pub fn duration(&self) -> Option<ElapsedTime> {
+        self.end_time
+            .and_then(|e| e.checked_sub(*self.elapsed_time))
+            .map(ElapsedTime)
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[rstest::rstest]
+    #[test]
+    #[case(60, None, None)]
+    #[case(60, Some(200), Some(140))]
+    #[case(60, Some(60), Some(0))]
+    #[case(60, Some(10), None)]
+    fn timestamp_duration(
+        #[case] start: u64,
+        #[case] end: Option<u64>,
+        #[case] expected: Option<u64>,
+    ) {
+        let mut ts = TimeStamp::new(start, TimeStampDescription::new("test").unwrap()).unwrap();
+        if let Some(end) = end {
+            ts = ts.with_end_time(ElapsedTime(end));
+        }
+        assert_eq!(ts.duration().map(u64::from), expected);
+    }
 }
@@ -1,10 +1,142 @@
 use errors::AppResult;
+use fancy_regex::Regex;
+use std::sync::LazyLock;
 use types::impl_string_value;
 
 impl_string_value!(TimeStampDescription);
 
+/// ゼロ幅文字・BOM
+const ZERO_WIDTH: &[char] = &['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}'];
+/// 行頭の番号・箇条書き記号。`01.` / `1)` / `・` / `- ` など
+static LEADING_MARKER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:[0-9０-９]{1,3}[.)）](?=\s)|#[0-9０-９]{1,3}(?=\s)|[・*●○▶►♪\-–—]+)\s*")
+        .unwrap()
+});
+/// 末尾に残った区切り記号
+const TRAILING_SEPARATORS: &[char] = &['|', '｜', '/', '／', '-', '–', '—', ':', '：', '・'];
+static URL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"https?://\S+").unwrap());
+static HASHTAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[#＃][^\s#＃]+").unwrap());
+
 impl TimeStampDescription {
     pub fn new(description: &str) -> AppResult<Self> {
         Ok(TimeStampDescription(description.to_string()))
     }
+
+    /// 表記揺れを吸収した比較用のキーを返す
+    ///
+    /// 全角英数記号を半角に寄せ、小文字化したうえで空白・記号を取り除く。
+    /// 句読点の違いしかない説明文は同じキーになる。
+    pub fn folded(&self) -> String {
+        fold_text(&self.0)
+    }
+
+    /// 表示用の説明文
+    ///
+    /// 元の文字列は保持したまま、ゼロ幅文字・URL・ハッシュタグ・行頭の番号や箇条書き記号・
+    /// 末尾の区切り記号を取り除き、連続する空白を1つにまとめる。
+    /// 取り除いた結果が空になる場合は元の文字列の空白を整えたものを返す。
+    pub fn display(&self) -> String {
+        let s = self.0.replace(ZERO_WIDTH, "");
+        let s = URL.replace_all(&s, " ");
+        let s = HASHTAG.replace_all(&s, " ");
+        let s = s
+            .lines()
+            .map(|l| {
+                let l = LEADING_MARKER.replace(l, "");
+                collapse_whitespace(&l)
+                    .trim_end_matches(|c: char| {
+                        c.is_whitespace() || TRAILING_SEPARATORS.contains(&c)
+                    })
+                    .to_string()
+            })
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if s.is_empty() {
+            collapse_whitespace(&self.0.replace(ZERO_WIDTH, ""))
+        } else {
+            s
+        }
+    }
+
+    /// 検索用の説明文
+    ///
+    /// 表示用の説明文の全角英数記号を半角に寄せ、小文字化して改行を空白にしたもの。
+    pub fn search_text(&self) -> String {
+        self.display()
+            .chars()
+            .map(fold_width)
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// 連続する空白（改行を除く）を1つにまとめ、前後の空白を取り除く
+fn collapse_whitespace(s: &str) -> String {
+    s.lines()
+        .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 全角・半角、大文字・小文字、空白・記号の違いを吸収した文字列を返す
+pub(crate) fn fold_text(s: &str) -> String {
+    s.chars()
+        .map(fold_width)
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 全角英数記号（U+FF01..=U+FF5E）と全角空白を半角へ変換する
+fn fold_width(c: char) -> char {
+    match c {
+        '\u{3000}' => ' ',
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[rstest::rstest]
+    #[test]
+    #[case("Opening Talk!", "openingtalk")]
+    #[case("opening talk", "openingtalk")]
+    #[case("ＯＰＥＮＩＮＧ　ＴＡＬＫ", "openingtalk")]
+    #[case("【歌枠】 曲名／アーティスト", "歌枠曲名アーティスト")]
+    fn folded_description(#[case] d: &str, #[case] expected: &str) {
+        let d = TimeStampDescription::new(d).unwrap();
+        assert_eq!(d.folded(), expected);
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case::plain("Opening Talk", "Opening Talk", "opening talk")]
+    #[case::bullet("・曲名 / アーティスト", "曲名 / アーティスト", "曲名 / アーティスト")]
+    #[case::numbering("01. Song Title", "Song Title", "song title")]
+    #[case::trailing_separator("Song Title |", "Song Title", "song title")]
+    #[case::url("Song https://example.com/x Title", "Song Title", "song title")]
+    #[case::hashtag("Song Title #歌枠 #karaoke", "Song Title", "song title")]
+    #[case::zero_width("Song\u{200B} Title\u{FEFF}", "Song Title", "song title")]
+    #[case::full_width("ＳＯＮＧ　Ｔｉｔｌｅ", "ＳＯＮＧ Ｔｉｔｌｅ", "song title")]
+    #[case::multi_line(
+        "Song Title |\n  - continued",
+        "Song Title\ncontinued",
+        "song title continued"
+    )]
+    #[case::only_noise("#歌枠", "#歌枠", "#歌枠")]
+    fn normalized_description(#[case] raw: &str, #[case] display: &str, #[case] search: &str) {
+        let d = TimeStampDescription::new(raw).unwrap();
+        assert_eq!(d.as_str(), raw);
+        assert_eq!(d.display(), display);
+        assert_eq!(d.search_text(), search);
+    }
 }
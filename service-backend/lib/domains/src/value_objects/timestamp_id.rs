@@ -7,12 +7,32 @@ types::impl_string_value!(TimestampId);
 
 impl TimestampId {
     pub fn new(video_id: &VideoId, timestamp: &TimeStamp) -> AppResult<Self> {
-        // read hash digest and consume hasher
-        let result = Sha224::digest(timestamp.description.as_bytes());
-        Ok(TimestampId(format!(
+        // 句読点や全角半角の違いで別ドキュメントにならないよう、正規化したキーをハッシュする
+        // 記号だけの説明は正規化すると空になるため、そのままハッシュする
+        let folded = timestamp.description.folded();
+        let key = match folded.is_empty() {
+            true => timestamp.description.as_str(),
+            false => folded.as_str(),
+        };
+        Ok(Self::hashed(video_id, timestamp, key))
+    }
+
+    /// 以前の形式のID。説明文をそのままハッシュしたものと、記号だけの説明を空としてハッシュしたもの
+    ///
+    /// 保存し直すときに、古いIDで指している見出しを探すために使う。
+    pub fn legacy(video_id: &VideoId, timestamp: &TimeStamp) -> Vec<Self> {
+        vec![
+            Self::hashed(video_id, timestamp, timestamp.description.as_str()),
+            Self::hashed(video_id, timestamp, &timestamp.description.folded()),
+        ]
+    }
+
+    fn hashed(video_id: &VideoId, timestamp: &TimeStamp, key: &str) -> Self {
+        let result = Sha224::digest(key.as_bytes());
+        TimestampId(format!(
             "{}-{}-{:x}",
             video_id, timestamp.elapsed_time, result
-        )))
+        ))
     }
 }
 
@@ -40,5 +60,30 @@ mod unit_tests {
                 .0
                 .contains(&timestamp.elapsed_time.0.to_string())
         );
+
+        let punctuated = TimeStamp::new(
+            ElapsedTime::new(60).unwrap(),
+            TimeStampDescription::new("Test, description!").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            timestamp_id,
+            TimestampId::new(&video_id, &punctuated).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_timestamp_id_of_symbols() {
+        let video_id = VideoId::new("abc-def-ghi").unwrap();
+        let id = |s: &str| {
+            let timestamp = TimeStamp::new(
+                ElapsedTime::new(60).unwrap(),
+                TimeStampDescription::new(s).unwrap(),
+            )
+            .unwrap();
+            TimestampId::new(&video_id, &timestamp).unwrap()
+        };
+
+        assert_ne!(id("♪"), id("★"));
     }
 }
@@ -0,0 +1,48 @@
+use crate::value_objects::comment_author::CommentAuthor;
+use crate::value_objects::comment_id::CommentId;
+use serde::{Deserialize, Serialize};
+
+/// タイムスタンプの取得元
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TimeStampSource {
+    /// 動画の概要欄
+    #[default]
+    Description,
+    /// 動画へのコメント
+    #[serde(rename_all = "camelCase")]
+    Comment {
+        comment_id: CommentId,
+        author: CommentAuthor,
+    },
+    /// 運営による手動登録
+    Manual,
+    /// 外部データからの取り込み
+    Import,
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn serialize_source() -> anyhow::Result<()> {
+        let s = serde_json::to_string(&TimeStampSource::Description)?;
+        assert_eq!(s, r#"{"type":"description"}"#);
+
+        let c = TimeStampSource::Comment {
+            comment_id: CommentId::new("Ugw-comment-id")?,
+            author: CommentAuthor::new("@author")?,
+        };
+        let s = serde_json::to_string(&c)?;
+        assert_eq!(
+            s,
+            r#"{"type":"comment","commentId":"Ugw-comment-id","author":"@author"}"#
+        );
+        assert_eq!(serde_json::from_str::<TimeStampSource>(&s)?, c);
+
+        let s = serde_json::to_string(&vec![TimeStampSource::Manual, TimeStampSource::Import])?;
+        assert_eq!(s, r#"[{"type":"manual"},{"type":"import"}]"#);
+        Ok(())
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// 動画の公開状態
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VideoAvailability {
+    /// 公開
+    #[default]
+    Public,
+    /// 限定公開。リンクを知っていれば視聴できる
+    Unlisted,
+    /// 削除・非公開などで視聴できない
+    Unavailable,
+}
+
+impl VideoAvailability {
+    pub fn is_available(&self) -> bool {
+        *self != VideoAvailability::Unavailable
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn serialize_availability() -> anyhow::Result<()> {
+        let s = serde_json::to_string(&VideoAvailability::Unavailable)?;
+        assert_eq!(s, r#""unavailable""#);
+        assert!(!serde_json::from_str::<VideoAvailability>(&s)?.is_available());
+        Ok(())
+    }
+}
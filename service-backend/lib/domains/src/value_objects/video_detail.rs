@@ -1,4 +1,6 @@
+use crate::value_objects::channel_id::ChannelId;
 use crate::value_objects::thumbnail_url::ThumbnailUrl;
+use crate::value_objects::video_duration::VideoDuration;
 use crate::value_objects::video_tag::VideoTag;
 use crate::value_objects::video_title::VideoTitle;
 use chrono::{DateTime, Utc};
@@ -11,4 +13,7 @@ pub struct VideoDetail {
     pub thumbnail_url: Option<ThumbnailUrl>,
     pub published_at: Option<DateTime<Utc>>,
     pub actual_start_at: Option<DateTime<Utc>>,
+    pub duration: Option<VideoDuration>,
+    #[serde(default)]
+    pub channel_id: Option<ChannelId>,
 }
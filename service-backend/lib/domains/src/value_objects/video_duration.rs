@@ -0,0 +1,88 @@
+use errors::AppError::DomainParseError;
+use errors::AppResult;
+use types::impl_numeric_value;
+
+impl_numeric_value!(VideoDuration, u64);
+
+impl VideoDuration {
+    pub fn new(seconds: u64) -> AppResult<Self> {
+        Ok(VideoDuration(seconds))
+    }
+
+    /// YouTube Data API の contentDetails.duration (ISO 8601 duration) から生成する
+    ///
+    /// 例: `PT1H2M3S`, `PT15M`, `P1DT2H`
+    ///
+    /// https://developers.google.com/youtube/v3/docs/videos#contentDetails.duration
+    pub fn from_iso8601(s: &str) -> AppResult<Self> {
+        let invalid = || DomainParseError(format!("from VideoDuration : Invalid format : {}", s));
+
+        let rest = s.strip_prefix('P').ok_or_else(invalid)?;
+        let (date, time) = match rest.split_once('T') {
+            Some((d, t)) if !t.is_empty() => (d, t),
+            Some(_) => return Err(invalid()),
+            None => (rest, ""),
+        };
+        if date.is_empty() && time.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut seconds = 0u64;
+        for (part, units) in [
+            (date, &[('W', 7 * 24 * 60 * 60), ('D', 24 * 60 * 60)][..]),
+            (time, &[('H', 60 * 60), ('M', 60), ('S', 1)][..]),
+        ] {
+            let mut num = String::new();
+            for c in part.chars() {
+                if c.is_ascii_digit() {
+                    num.push(c);
+                    continue;
+                }
+                let (_, unit) = units.iter().find(|(u, _)| *u == c).ok_or_else(invalid)?;
+                let n = num.parse::<u64>().map_err(|_| invalid())?;
+                seconds = n
+                    .checked_mul(*unit)
+                    .and_then(|n| seconds.checked_add(n))
+                    .ok_or_else(invalid)?;
+                num.clear();
+            }
+            if !num.is_empty() {
+                return Err(invalid());
+            }
+        }
+
+        Self::new(seconds)
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[rstest::rstest]
+    #[test]
+    #[case("PT1H2M3S", 3723)]
+    #[case("PT15M", 900)]
+    #[case("PT59S", 59)]
+    #[case("P1DT2H", 93600)]
+    #[case("P0D", 0)]
+    fn valid_iso8601(#[case] fmt: &str, #[case] expected: u64) {
+        let d = VideoDuration::from_iso8601(fmt);
+        assert!(d.is_ok());
+        assert_eq!(expected, d.unwrap());
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case("")]
+    #[case("P")]
+    #[case("PT")]
+    #[case("1H2M")]
+    #[case("PT1X")]
+    #[case("PT12")]
+    #[case::overflow("P99999999999999999W")]
+    #[case::overflow_sum("P1DT18446744073709551615S")]
+    fn invalid_iso8601(#[case] fmt: &str) {
+        assert!(VideoDuration::from_iso8601(fmt).is_err());
+    }
+}
@@ -0,0 +1,12 @@
+use errors::{AppError, AppResult};
+
+types::impl_string_value!(VideoEtag);
+
+impl VideoEtag {
+    pub fn new(etag: &str) -> AppResult<Self> {
+        if etag.is_empty() {
+            return Err(AppError::InvalidInput("ETag cannot be empty".to_string()));
+        }
+        Ok(VideoEtag(etag.to_string()))
+    }
+}
@@ -40,6 +40,18 @@ pub enum AppError {
     /// 429 Too Many Requests
     TooManyRequests,
 
+    #[error("429 Quota exceeded : {0}")]
+    /// 外部APIの利用枠を使い切った
+    QuotaExceeded(String),
+
+    #[error("502 Upstream unauthorized : {0}")]
+    /// 外部APIの認証情報が無い・無効である
+    UpstreamUnauthorized(String),
+
+    #[error("503 Upstream unavailable : {0}")]
+    /// 外部APIが一時的に利用できない。時間をおけば成功しうる
+    UpstreamUnavailable(String),
+
     #[error("502 Bad Gateway")]
     /// 外部APIやバックエンドサービスとの通信失敗
     BadGateway(#[source] anyhow::Error),
@@ -51,12 +63,48 @@ pub enum AppError {
 
 impl From<Youtube3Error> for AppError {
     fn from(e: Youtube3Error) -> Self {
-        match e {
+        match &e {
+            Youtube3Error::HttpError(_) | Youtube3Error::Io(_) => {
+                AppError::UpstreamUnavailable(e.to_string())
+            }
+            Youtube3Error::MissingAPIKey | Youtube3Error::MissingToken(_) => {
+                AppError::UpstreamUnauthorized(e.to_string())
+            }
+            Youtube3Error::BadRequest(body) => {
+                // {"error": {"code": 403, "errors": [{"reason": "quotaExceeded", ...}], ...}}
+                let reasons = body["error"]["errors"]
+                    .as_array()
+                    .map(|v| v.iter().filter_map(|r| r["reason"].as_str()).collect())
+                    .unwrap_or(Vec::new());
+                let status = body["error"]["code"].as_u64().unwrap_or(400) as u16;
+                youtube_error(status, &reasons, e.to_string())
+            }
+            Youtube3Error::Failure(res) => youtube_error(res.status().as_u16(), &[], e.to_string()),
             _ => AppError::BadGateway(anyhow::anyhow!(e)),
         }
     }
 }
 
+/// YouTube Data API のエラーをステータスと理由から分類する
+fn youtube_error(status: u16, reasons: &[&str], message: String) -> AppError {
+    let has = |r: &[&str]| reasons.iter().any(|reason| r.contains(reason));
+    if has(&["quotaExceeded", "dailyLimitExceeded"]) {
+        return AppError::QuotaExceeded(message);
+    }
+    if has(&["rateLimitExceeded", "userRateLimitExceeded"]) {
+        return AppError::UpstreamUnavailable(message);
+    }
+    if has(&["insufficientPermissions", "accessNotConfigured"]) {
+        return AppError::UpstreamUnauthorized(message);
+    }
+    match status {
+        401 => AppError::UpstreamUnauthorized(message),
+        404 => AppError::NotFound(message),
+        408 | 429 | 500 | 502 | 503 | 504 => AppError::UpstreamUnavailable(message),
+        _ => AppError::BadGateway(anyhow::anyhow!(message)),
+    }
+}
+
 impl From<MeiliSearchError> for AppError {
     fn from(e: MeiliSearchError) -> AppError {
         match e {
@@ -124,12 +172,17 @@ impl IntoResponse for AppError {
                 )
             }
             AppError::TooManyRequests => (StatusCode::TOO_MANY_REQUESTS, "429 Too Many Requests."),
+            AppError::QuotaExceeded(_) => (StatusCode::TOO_MANY_REQUESTS, "429 Too Many Requests."),
             AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "401 Unauthorized."),
             AppError::Forbidden => (StatusCode::FORBIDDEN, "403 Forbidden."),
             AppError::BadGateway(e) => {
                 tracing::error!("{}", e);
                 (StatusCode::BAD_GATEWAY, "502 Bad Gateway.")
             }
+            AppError::UpstreamUnauthorized(_) => (StatusCode::BAD_GATEWAY, "502 Bad Gateway."),
+            AppError::UpstreamUnavailable(_) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "503 Service Unavailable.")
+            }
             AppError::ServiceUnavailable => {
                 (StatusCode::SERVICE_UNAVAILABLE, "503 Service Unavailable.")
             }
@@ -63,6 +63,7 @@ mod unit_tests {
         let ts = TimeStamp {
             elapsed_time: ElapsedTime(1),
             description: TimeStampDescription::new("description").unwrap(),
+            end_time: None,
         };
 
         let i = TimeStampIndex::from_entity(v, ts);
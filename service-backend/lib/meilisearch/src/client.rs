@@ -2,19 +2,29 @@ use crate::config::CONFIG;
 use crate::index::Index;
 use crate::index::timestamp::TimeStampIndex;
 use crate::repositories::{MeilisearchCrudApi, MeilisearchSearchApi};
-use chrono::{FixedOffset, NaiveTime, TimeZone};
+use chrono::{FixedOffset, TimeZone};
 use domains::repositories::internal_timestamp_search_repository::{
     Part, VideoTimestampSearchQuery,
 };
+use domains::value_objects::limit::Limit;
+use domains::value_objects::song_key::SongKey;
+use domains::value_objects::video_id::VideoId;
 use errors::AppResult;
 use itertools::Itertools;
 use meilisearch_sdk::client::Client;
+use meilisearch_sdk::documents::{DocumentDeletionQuery, DocumentsQuery};
 use meilisearch_sdk::errors::{Error as MeilisearchError, ErrorCode};
 use meilisearch_sdk::search::{SearchQuery as MeilisearchSearchQuery, SearchResults, Selectors};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::collections::HashSet;
 
+/// ドキュメントを一度に取得する件数
+const DOCUMENTS_PAGE_SIZE: usize = 1000;
+
+/// 視聴できる動画のみに絞る。公開状態の無い古いドキュメントも含む
+const AVAILABLE_FILTER: &str = r#"videoDetails.availability != "unavailable""#;
+
 // 日本時間 (UTC+9) の定義
 static JST_OFFSET: FixedOffset = FixedOffset::east_opt(9 * 3600).unwrap();
 
@@ -35,6 +45,30 @@ impl ApiClient {
         tracing::info!("Connection to MeiliSearch established");
         Self { client }
     }
+
+    /// ページを辿ってドキュメントをすべて取得する
+    async fn get_documents<I: DeserializeOwned + Send + Sync + 'static>(
+        &self,
+        index_name: &str,
+        filter: Option<&str>,
+    ) -> Result<Vec<I>, MeilisearchError> {
+        let i = self.client.get_index(index_name).await?;
+        let mut documents = Vec::new();
+        loop {
+            let mut q = DocumentsQuery::new(&i);
+            q.with_offset(documents.len())
+                .with_limit(DOCUMENTS_PAGE_SIZE);
+            if let Some(filter) = filter {
+                q.with_filter(filter);
+            }
+            let r = q.execute::<I>().await?;
+            let fetched = r.results.len();
+            documents.extend(r.results);
+            if fetched < DOCUMENTS_PAGE_SIZE || documents.len() >= r.total as usize {
+                return Ok(documents);
+            }
+        }
+    }
 }
 
 impl Default for ApiClient {
@@ -165,13 +199,15 @@ impl<I: Index + Serialize + DeserializeOwned + Sync + Send + 'static> Meilisearc
     }
 
     async fn get_all_entities(&self, index_name: &str) -> Result<Vec<I>, MeilisearchError> {
-        let i = self.client.get_index(index_name).await?;
-        let result = i.get_documents::<I>().await;
+        self.get_documents(index_name, None).await
+    }
 
-        match result {
-            Ok(documents) => Ok(documents.results),
-            Err(e) => Err(e),
-        }
+    async fn get_entities_by_filter(
+        &self,
+        index_name: &str,
+        filter: &str,
+    ) -> Result<Vec<I>, MeilisearchError> {
+        self.get_documents(index_name, Some(filter)).await
     }
 
     async fn delete_entity_by_id(
@@ -184,6 +220,31 @@ impl<I: Index + Serialize + DeserializeOwned + Sync + Send + 'static> Meilisearc
         Ok(())
     }
 
+    ///
+    /// 削除し終えるまで待つ。続けて追加するドキュメントが消されないようにするため
+    ///
+    async fn delete_entities_by_filter(
+        &self,
+        index_name: &str,
+        filter: &str,
+    ) -> Result<(), MeilisearchError> {
+        let i = self.client.get_index(index_name).await?;
+        let mut q = DocumentDeletionQuery::new(&i);
+        q.with_filter(filter);
+        let task = i
+            .delete_documents_with(&q)
+            .await?
+            .wait_for_completion(&self.client, None, None)
+            .await?;
+
+        if task.is_failure() {
+            let f = task.unwrap_failure();
+            tracing::error!("task failure : {}", f.error_message);
+        }
+
+        Ok(())
+    }
+
     async fn delete_all_entities(&self, index_name: &str) -> Result<(), MeilisearchError> {
         let i = self.client.get_index(index_name).await?;
         let _ = i.delete_all_documents().await?;
@@ -206,7 +267,8 @@ impl MeilisearchSearchApi<TimeStampIndex> for ApiClient {
 
         // set query
         q.with_query(search_query.query.as_str());
-        q.with_attributes_to_search_on(&["description"]);
+        // searchText を持たない古いドキュメントのため、元の説明文も対象にする
+        q.with_attributes_to_search_on(&["searchText", "songTitle", "artist", "description"]);
 
         // construct filter
         let filter_text = {
@@ -226,6 +288,17 @@ impl MeilisearchSearchApi<TimeStampIndex> for ApiClient {
                 }
             }
 
+            if let Some(channel_id) = search_query.channel_id {
+                v.push(format!(r#"videoDetails.channelId = "{}""#, channel_id));
+            }
+
+            if let Some(playlist_id) = search_query.playlist_id {
+                v.push(format!(
+                    r#"videoDetails.playlists = "{}""#,
+                    escape_filter_value(&playlist_id)
+                ));
+            }
+
             if let Some(at) = search_query.actual_start_at {
                 // 日本時間 (UTC+9) の定義
                 let start = JST_OFFSET
@@ -256,6 +329,34 @@ impl MeilisearchSearchApi<TimeStampIndex> for ApiClient {
                     v.push(format!("videoDetails.actualStartAt < {}", ts));
                 }
             }
+
+            if let Some(from) = search_query.elapsed_time_from {
+                v.push(format!("elapsedTime >= {}", from));
+            }
+
+            if let Some(to) = search_query.elapsed_time_to {
+                v.push(format!("elapsedTime <= {}", to));
+            }
+
+            if let Some(min) = search_query.video_duration_min {
+                v.push(format!("videoDetails.duration >= {}", min));
+            }
+
+            if let Some(max) = search_query.video_duration_max {
+                v.push(format!("videoDetails.duration <= {}", max));
+            }
+
+            if let Some(artist) = search_query.artist {
+                v.push(format!(r#"artist = "{}""#, escape_filter_value(&artist)));
+            }
+
+            if let Some(min) = search_query.min_confidence {
+                v.push(format!("confidence >= {}", min));
+            }
+
+            if !search_query.include_unavailable {
+                v.push(AVAILABLE_FILTER.to_string());
+            }
             v.into_iter().join(" AND ")
         };
 
@@ -270,6 +371,13 @@ impl MeilisearchSearchApi<TimeStampIndex> for ApiClient {
         a.insert("videoId");
         a.insert("description");
         a.insert("elapsedTime");
+        a.insert("endTime");
+        a.insert("sources");
+        a.insert("songTitle");
+        a.insert("artist");
+        a.insert("songTags");
+        a.insert("parentId");
+        a.insert("confidence");
 
         tracing::debug!("parts : {:?}", search_query.parts);
         if let Some(parts) = search_query.parts {
@@ -281,6 +389,8 @@ impl MeilisearchSearchApi<TimeStampIndex> for ApiClient {
                         a.insert("videoDetails.thumbnailUrl");
                         a.insert("videoDetails.actualStartAt");
                         a.insert("videoDetails.publishedAt");
+                        a.insert("videoDetails.duration");
+                        a.insert("videoDetails.channelId");
                     }
                     Part::VideoTitle => {
                         a.insert("videoDetails.videoTitle");
@@ -297,6 +407,9 @@ impl MeilisearchSearchApi<TimeStampIndex> for ApiClient {
                     Part::PublishedAt => {
                         a.insert("videoDetails.publishedAt");
                     }
+                    Part::Duration => {
+                        a.insert("videoDetails.duration");
+                    }
                 };
             }
         }
@@ -317,6 +430,47 @@ impl MeilisearchSearchApi<TimeStampIndex> for ApiClient {
         tracing::debug!("Search query : {:?}", search_query.query);
         Ok(q.execute().await?)
     }
+
+    async fn search_by_song_key(
+        &self,
+        key: &SongKey,
+        limit: Limit,
+    ) -> AppResult<SearchResults<TimeStampIndex>> {
+        let i = self.client.get_index(TimeStampIndex::name()).await?;
+        let mut q = MeilisearchSearchQuery::new(&i);
+
+        // キーは英数字のみなのでエスケープは不要
+        let filter_text = format!(r#"songKey = "{}" AND {}"#, key, AVAILABLE_FILTER);
+        tracing::debug!("filter : {:?}", filter_text);
+        q.with_filter(&filter_text);
+        q.with_limit(limit.into());
+        q.with_sort(&["videoDetails.actualStartAt:asc"]);
+
+        Ok(q.execute().await?)
+    }
+
+    async fn search_by_video_id(
+        &self,
+        video_id: &VideoId,
+        limit: Limit,
+    ) -> AppResult<SearchResults<TimeStampIndex>> {
+        let i = self.client.get_index(TimeStampIndex::name()).await?;
+        let mut q = MeilisearchSearchQuery::new(&i);
+
+        // 動画IDは英数字と `-` `_` のみなのでエスケープは不要
+        let filter_text = format!(r#"videoId = "{}""#, video_id);
+        tracing::debug!("filter : {:?}", filter_text);
+        q.with_filter(&filter_text);
+        q.with_limit(limit.into());
+        q.with_sort(&["elapsedTime:asc"]);
+
+        Ok(q.execute().await?)
+    }
+}
+
+/// フィルタの文字列リテラル用にエスケープする。`\` を先に置き換えないと `"` のエスケープを打ち消してしまう
+fn escape_filter_value(s: &str) -> String {
+    s.replace('\\', r"\\").replace('"', r#"\""#)
 }
 
 #[cfg(test)]
@@ -327,4 +481,12 @@ mod unit_tests {
     async fn create_client() {
         let _hub = ApiClient::new();
     }
+
+    #[test]
+    fn escape_filter_values() {
+        assert_eq!(escape_filter_value(r#"AC/DC"#), r#"AC/DC"#);
+        assert_eq!(escape_filter_value(r#"say "hi""#), r#"say \"hi\""#);
+        assert_eq!(escape_filter_value(r#"back\"#), r#"back\\"#);
+        assert_eq!(escape_filter_value(r#"a\" OR x"#), r#"a\\\" OR x"#);
+    }
 }
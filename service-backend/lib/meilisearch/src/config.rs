@@ -7,6 +7,18 @@ pub struct Config {
     pub master_key: String,
     pub video_index_name: String,
     pub timestamp_index_name: String,
+    #[serde(default = "default_channel_index_name")]
+    pub channel_index_name: String,
+    #[serde(default = "default_sync_state_index_name")]
+    pub sync_state_index_name: String,
+}
+
+fn default_channel_index_name() -> String {
+    "channels".to_string()
+}
+
+fn default_sync_state_index_name() -> String {
+    "sync_states".to_string()
 }
 
 pub static CONFIG: Lazy<Config> = Lazy::new(|| {
@@ -15,6 +27,8 @@ pub static CONFIG: Lazy<Config> = Lazy::new(|| {
         master_key: "masterKey28562856".to_string(),
         video_index_name: "videos".to_string(),
         timestamp_index_name: "timestamps".to_string(),
+        channel_index_name: default_channel_index_name(),
+        sync_state_index_name: default_sync_state_index_name(),
     };
 
     if let Ok(env_config) = envy::prefixed("MEILI_").from_env::<Config>() {
@@ -1,3 +1,5 @@
+pub mod channel;
+pub mod sync_state;
 pub mod timestamp;
 pub mod video;
 
@@ -0,0 +1,84 @@
+use crate::config::CONFIG;
+use crate::index::Index;
+use domains::entities::channel::ChannelEntity;
+use domains::entities::registered_channel::RegisteredChannelEntity;
+use domains::value_objects::channel_id::ChannelId;
+use domains::value_objects::channel_name::ChannelName;
+use serde::{Deserialize, Serialize};
+
+/// 取得対象として登録したチャンネル
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelIndex {
+    pub channel_id: ChannelId,
+    pub channel_name: ChannelName,
+    pub enabled: bool,
+    pub num_recent: u32,
+    #[serde(default)]
+    pub parser_profile: Option<String>,
+}
+
+impl ChannelIndex {
+    pub fn from_entity(channel: RegisteredChannelEntity) -> Self {
+        ChannelIndex {
+            channel_id: channel.channel.id,
+            channel_name: channel.channel.name,
+            enabled: channel.enabled,
+            num_recent: channel.num_recent,
+            parser_profile: channel.parser_profile,
+        }
+    }
+}
+
+impl From<RegisteredChannelEntity> for ChannelIndex {
+    fn from(channel: RegisteredChannelEntity) -> Self {
+        ChannelIndex::from_entity(channel)
+    }
+}
+
+impl From<ChannelIndex> for RegisteredChannelEntity {
+    fn from(c: ChannelIndex) -> RegisteredChannelEntity {
+        RegisteredChannelEntity::new(ChannelEntity::new(c.channel_id, c.channel_name))
+            .with_enabled(c.enabled)
+            .with_num_recent(c.num_recent)
+            .with_parser_profile(c.parser_profile)
+    }
+}
+
+impl Index for ChannelIndex {
+    fn pid(&self) -> Option<&str> {
+        Some(self.channel_id.as_str())
+    }
+
+    fn pid_field() -> Option<&'static str> {
+        Some("channelId")
+    }
+
+    fn name() -> &'static str {
+        &CONFIG.channel_index_name
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn conversion_index_entity_test() -> anyhow::Result<()> {
+        let channel = ChannelEntity::with_random_id(ChannelName::new("Channel Name")?);
+        let entity = RegisteredChannelEntity::new(channel.clone())
+            .with_enabled(false)
+            .with_num_recent(30)
+            .with_parser_profile(Some("karaoke".to_string()));
+
+        let index = ChannelIndex::from_entity(entity.clone());
+        assert_eq!(index.channel_id, channel.id);
+        assert_eq!(index.channel_name, channel.name);
+        assert!(!index.enabled);
+        assert_eq!(index.num_recent, 30);
+        assert_eq!(index.parser_profile.as_deref(), Some("karaoke"));
+
+        assert_eq!(RegisteredChannelEntity::from(index), entity);
+        Ok(())
+    }
+}
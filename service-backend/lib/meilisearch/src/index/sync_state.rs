@@ -0,0 +1,97 @@
+use crate::config::CONFIG;
+use crate::index::Index;
+use chrono::DateTime;
+use domains::entities::channel_sync_state::ChannelSyncStateEntity;
+use domains::value_objects::channel_id::ChannelId;
+use domains::value_objects::video_etag::VideoEtag;
+use domains::value_objects::video_id::VideoId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// チャンネルごとの取得状況
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStateIndex {
+    pub channel_id: ChannelId,
+    pub last_published_at: Option<i64>,
+    pub page_token: Option<String>,
+    #[serde(default)]
+    pub etags: HashMap<VideoId, VideoEtag>,
+}
+
+impl SyncStateIndex {
+    pub fn from_entity(state: ChannelSyncStateEntity) -> Self {
+        SyncStateIndex {
+            channel_id: state.channel_id,
+            last_published_at: state.last_published_at.map(|t| t.timestamp()),
+            page_token: state.page_token,
+            etags: state.etags,
+        }
+    }
+}
+
+impl From<ChannelSyncStateEntity> for SyncStateIndex {
+    fn from(state: ChannelSyncStateEntity) -> Self {
+        SyncStateIndex::from_entity(state)
+    }
+}
+
+impl From<SyncStateIndex> for ChannelSyncStateEntity {
+    fn from(s: SyncStateIndex) -> ChannelSyncStateEntity {
+        let mut state = ChannelSyncStateEntity::new(s.channel_id)
+            .with_page_token(s.page_token)
+            .with_etags(s.etags);
+
+        if let Some(t) = s
+            .last_published_at
+            .and_then(|t| DateTime::from_timestamp(t, 0))
+        {
+            state = state.with_last_published_at(t);
+        }
+        state
+    }
+}
+
+impl Index for SyncStateIndex {
+    fn pid(&self) -> Option<&str> {
+        Some(self.channel_id.as_str())
+    }
+
+    fn pid_field() -> Option<&'static str> {
+        Some("channelId")
+    }
+
+    fn name() -> &'static str {
+        &CONFIG.sync_state_index_name
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use domains::entities::channel::ChannelEntity;
+    use domains::value_objects::channel_name::ChannelName;
+
+    #[test]
+    fn conversion_index_entity_test() -> anyhow::Result<()> {
+        let channel = ChannelEntity::with_random_id(ChannelName::new("Channel Name")?);
+        let entity = ChannelSyncStateEntity::new(channel.id.clone())
+            .with_last_published_at(DateTime::from_timestamp(1_700_000_000, 0).unwrap())
+            .with_page_token(Some("token".to_string()))
+            .with_etags(HashMap::from([(
+                VideoId::new("aqz-KE-bpKQ")?,
+                VideoEtag::new("etag")?,
+            )]));
+
+        let index = SyncStateIndex::from_entity(entity.clone());
+        assert_eq!(index.channel_id, channel.id);
+        assert_eq!(index.last_published_at, Some(1_700_000_000));
+
+        // キーが動画IDの JSON オブジェクトとして保存される
+        let json = serde_json::to_value(&index)?;
+        assert_eq!(json["etags"]["aqz-KE-bpKQ"], "etag");
+
+        assert_eq!(ChannelSyncStateEntity::from(index), entity);
+        Ok(())
+    }
+}
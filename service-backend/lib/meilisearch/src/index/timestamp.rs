@@ -3,12 +3,23 @@ use crate::index::Index;
 use chrono::DateTime;
 use domains::entities::video::VideoEntity;
 use domains::entities::video_timestamp::VideoTimestampEntity;
+use domains::value_objects::channel_id::ChannelId;
+use domains::value_objects::confidence::Confidence;
 use domains::value_objects::elapsed_time::ElapsedTime;
+use domains::value_objects::playlist_id::PlaylistId;
+use domains::value_objects::setlist_entry::SetlistEntry;
+use domains::value_objects::song_artist::SongArtist;
+use domains::value_objects::song_key::SongKey;
+use domains::value_objects::song_tag::SongTag;
+use domains::value_objects::song_title::SongTitle;
 use domains::value_objects::thumbnail_url::ThumbnailUrl;
 use domains::value_objects::timestamp::TimeStamp;
 use domains::value_objects::timestamp_description::TimeStampDescription;
 use domains::value_objects::timestamp_id::TimestampId;
+use domains::value_objects::timestamp_source::TimeStampSource;
+use domains::value_objects::video_availability::VideoAvailability;
 use domains::value_objects::video_detail::VideoDetail;
+use domains::value_objects::video_duration::VideoDuration;
 use domains::value_objects::video_id::VideoId;
 use domains::value_objects::video_tag::VideoTag;
 use domains::value_objects::video_title::VideoTitle;
@@ -25,8 +36,30 @@ pub struct TimeStampIndex {
     pub pid: TimestampId,
     pub video_id: VideoId,
     pub description: TimeStampDescription,
+    /// 検索用に正規化した説明文
+    ///
+    /// これを持たない古いドキュメントは `video_fetch reindex` で埋める。埋めるまでは説明文でも検索する
+    #[serde(default)]
+    pub search_text: String,
     pub elapsed_time: ElapsedTime, // 秒単位
+    #[serde(default)]
+    pub end_time: Option<ElapsedTime>, // 秒単位
     pub video_details: Option<VideoTimeStampDetails>,
+    #[serde(default)]
+    pub sources: Vec<TimeStampSource>,
+    #[serde(default)]
+    pub song_title: Option<SongTitle>,
+    #[serde(default)]
+    pub artist: Option<SongArtist>,
+    #[serde(default)]
+    pub song_tags: Vec<SongTag>,
+    /// 同じ曲をまとめるためのキー。曲名があれば曲名、無ければ説明文から作る
+    #[serde(default)]
+    pub song_key: Option<SongKey>,
+    #[serde(default)]
+    pub parent_id: Option<TimestampId>,
+    #[serde(default)]
+    pub confidence: Option<Confidence>,
 }
 
 impl TimeStampIndex {
@@ -37,15 +70,73 @@ impl TimeStampIndex {
         start_time: S,
         video_details: Option<VideoTimeStampDetails>,
     ) -> Self {
+        let song_key = SongKey::new(&description.display()).ok();
         TimeStampIndex {
             pid,
             video_id,
+            search_text: description.search_text(),
             description,
             elapsed_time: start_time.into(),
+            end_time: None,
             video_details,
+            sources: vec![TimeStampSource::default()],
+            song_title: None,
+            artist: None,
+            song_tags: vec![],
+            song_key,
+            parent_id: None,
+            confidence: None,
         }
     }
 
+    pub fn with_end_time(mut self, end_time: Option<ElapsedTime>) -> Self {
+        self.end_time = end_time;
+        self
+    }
+
+    pub fn with_sources(mut self, sources: Vec<TimeStampSource>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    pub fn with_parent_id(mut self, parent_id: Option<TimestampId>) -> Self {
+        self.parent_id = parent_id;
+        self
+    }
+
+    pub fn with_confidence(mut self, confidence: Option<Confidence>) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    pub fn with_song(mut self, song: Option<SetlistEntry>) -> Self {
+        match song {
+            Some(s) => {
+                self.song_title = Some(s.title);
+                self.artist = s.artist;
+                self.song_tags = s.tags;
+            }
+            None => {
+                self.song_title = None;
+                self.artist = None;
+                self.song_tags = vec![];
+            }
+        }
+        self.song_key = match self.song_title.as_ref() {
+            Some(title) => SongKey::new(title),
+            None => SongKey::new(&self.description.display()),
+        }
+        .ok();
+        self
+    }
+
+    /// 曲名が無い場合は曲情報なしとする
+    fn song(&self) -> Option<SetlistEntry> {
+        self.song_title
+            .clone()
+            .map(|title| SetlistEntry::new(title, self.artist.clone(), self.song_tags.clone()))
+    }
+
     pub fn from_entity(video: VideoEntity, timestamp: TimeStamp) -> Self {
         TimeStampIndex::new(
             TimestampId::new(&video.id, &timestamp).unwrap(),
@@ -54,6 +145,16 @@ impl TimeStampIndex {
             timestamp.elapsed_time,
             Some(VideoTimeStampDetails::from_entity(video)),
         )
+        .with_end_time(timestamp.end_time)
+    }
+
+    /// 取得元・曲情報・親も含めてタイムスタンプのエンティティから作る
+    pub fn from_timestamp_entity(video: VideoEntity, entity: &VideoTimestampEntity) -> Self {
+        TimeStampIndex::from_entity(video, entity.timestamp.clone())
+            .with_sources(entity.sources.clone())
+            .with_song(entity.song.clone())
+            .with_parent_id(entity.parent_id.clone())
+            .with_confidence(entity.confidence)
     }
 
     pub fn take_video_details(self) -> AppResult<VideoTimeStampDetails> {
@@ -63,22 +164,37 @@ impl TimeStampIndex {
     }
 
     pub fn into_entity(self) -> VideoTimestampEntity {
-        VideoTimestampEntity::new(
-            self.video_id,
-            TimeStamp::new(self.elapsed_time, self.description).unwrap(),
-        )
+        let video_id = self.video_id.clone();
+        let sources = self.sources.clone();
+        let song = self.song();
+        let parent_id = self.parent_id.clone();
+        let confidence = self.confidence;
+        VideoTimestampEntity::new(video_id, self.into_timestamp())
+            .with_sources(sources)
+            .with_song(song)
+            .with_parent_id(parent_id)
+            .with_confidence(confidence)
     }
 
     pub fn into_timestamp(self) -> TimeStamp {
-        TimeStamp::new(self.elapsed_time, self.description).unwrap()
+        TimeStamp {
+            elapsed_time: self.elapsed_time,
+            description: self.description,
+            end_time: self.end_time,
+        }
     }
 }
 
 impl From<TimeStampIndex> for VideoTimestampEntity {
     fn from(v: TimeStampIndex) -> VideoTimestampEntity {
+        let song = v.song();
         VideoTimestampEntity::with_details(
             v.video_id,
-            TimeStamp::new(v.elapsed_time, v.description).unwrap(),
+            TimeStamp {
+                elapsed_time: v.elapsed_time,
+                description: v.description,
+                end_time: v.end_time,
+            },
             v.video_details.map(|d| VideoDetail {
                 video_title: d.video_title,
                 video_tags: d.video_tags,
@@ -91,8 +207,14 @@ impl From<TimeStampIndex> for VideoTimestampEntity {
                     .actual_start_at
                     .map(|t| DateTime::from_timestamp(t, 0))
                     .unwrap_or(None),
+                duration: d.duration.map(VideoDuration),
+                channel_id: d.channel_id,
             }),
         )
+        .with_sources(v.sources)
+        .with_song(song)
+        .with_parent_id(v.parent_id)
+        .with_confidence(v.confidence)
     }
 }
 
@@ -118,6 +240,16 @@ pub struct VideoTimeStampDetails {
     pub thumbnail_url: Option<ThumbnailUrl>,
     pub published_at: Option<i64>,
     pub actual_start_at: Option<i64>,
+    #[serde(default)]
+    pub duration: Option<u64>,
+    #[serde(default)]
+    pub channel_id: Option<ChannelId>,
+    /// 検索時に視聴できない動画を除くために使う
+    #[serde(default)]
+    pub availability: VideoAvailability,
+    /// 検索時に再生リストで絞り込むために使う
+    #[serde(default)]
+    pub playlists: Vec<PlaylistId>,
 }
 impl VideoTimeStampDetails {
     pub fn new(
@@ -133,9 +265,33 @@ impl VideoTimeStampDetails {
             thumbnail_url,
             published_at,
             actual_start_at,
+            duration: None,
+            channel_id: None,
+            availability: VideoAvailability::default(),
+            playlists: vec![],
         }
     }
 
+    pub fn with_duration(mut self, duration: Option<u64>) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_channel_id(mut self, channel_id: Option<ChannelId>) -> Self {
+        self.channel_id = channel_id;
+        self
+    }
+
+    pub fn with_availability(mut self, availability: VideoAvailability) -> Self {
+        self.availability = availability;
+        self
+    }
+
+    pub fn with_playlists(mut self, playlists: Vec<PlaylistId>) -> Self {
+        self.playlists = playlists;
+        self
+    }
+
     pub fn from_entity(video: VideoEntity) -> Self {
         VideoTimeStampDetails::new(
             Some(video.title),
@@ -144,6 +300,10 @@ impl VideoTimeStampDetails {
             Some(video.published_at.timestamp()),
             video.actual_start_at.map(|t| t.timestamp()),
         )
+        .with_duration(video.duration.map(|d| d.value()))
+        .with_channel_id(Some(video.channel.id))
+        .with_availability(video.availability)
+        .with_playlists(video.playlists)
     }
 }
 
@@ -173,8 +333,9 @@ mod unit_tests {
             .construct()?;
 
         let timestamp = TimeStamp {
-            description: TimeStampDescription::new("test")?,
+            description: TimeStampDescription::new("・Test #tag |")?,
             elapsed_time: ElapsedTime::new(100)?,
+            end_time: Some(ElapsedTime::new(160)?),
         };
 
         let index = TimeStampIndex::from_entity(video, timestamp.clone());
@@ -182,7 +343,10 @@ mod unit_tests {
         assert_eq!(index.pid, TimestampId::new(&id, &timestamp)?);
         assert_eq!(index.video_id, id);
         assert_eq!(index.description, timestamp.description);
+        assert_eq!(index.search_text, "test");
         assert_eq!(index.elapsed_time, timestamp.elapsed_time);
+        assert_eq!(index.end_time, timestamp.end_time);
+        assert_eq!(index.song_key, Some(SongKey::new("test")?));
 
         if let Some(d) = index.video_details.as_ref() {
             assert_eq!(d.video_title, Some(title));
@@ -190,12 +354,36 @@ mod unit_tests {
             assert_eq!(d.thumbnail_url, None);
             assert!(d.published_at.is_some()); // published_atは生成時に自動でなにか入っている
             assert_eq!(d.actual_start_at, None);
+            assert_eq!(d.duration, None);
+            assert_eq!(d.channel_id, Some(channel.id.clone()));
         };
 
         let e = index.clone().into_entity();
         let e2 = VideoTimestampEntity::from(index.clone());
 
         assert_eq!(e, e2);
+        assert_eq!(e.sources, vec![TimeStampSource::Description]);
+
+        let sources = vec![TimeStampSource::Description, TimeStampSource::Manual];
+        let e = index.clone().with_sources(sources.clone()).into_entity();
+        assert_eq!(e.sources, sources);
+        assert_eq!(e.song, None);
+
+        let song = SetlistEntry::new(
+            SongTitle::new("Song")?,
+            Some(SongArtist::new("Artist")?),
+            vec![SongTag::new("cover")?],
+        );
+        let index = index.with_song(Some(song.clone()));
+        assert_eq!(index.artist, Some(SongArtist::new("Artist")?));
+        assert_eq!(index.song_key, Some(SongKey::new("song")?));
+        assert_eq!(index.clone().into_entity().song, Some(song.clone()));
+        assert_eq!(VideoTimestampEntity::from(index.clone()).song, Some(song));
+
+        let parent = Some(index.pid.clone());
+        let index = index.with_parent_id(parent.clone());
+        assert_eq!(index.clone().into_entity().parent_id, parent);
+        assert_eq!(VideoTimestampEntity::from(index).parent_id, parent);
 
         Ok(())
     }
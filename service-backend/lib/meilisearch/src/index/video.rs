@@ -3,11 +3,16 @@ use crate::index::Index;
 use chrono::DateTime;
 use domains::entities::channel::ChannelEntity;
 use domains::entities::video::VideoEntity;
+use domains::value_objects::broadcast_state::BroadcastState;
 use domains::value_objects::channel_id::ChannelId;
 use domains::value_objects::channel_name::ChannelName;
+use domains::value_objects::playlist_id::PlaylistId;
 use domains::value_objects::thumbnail::Thumbnail;
 use domains::value_objects::thumbnail_url::ThumbnailUrl;
+use domains::value_objects::video_availability::VideoAvailability;
 use domains::value_objects::video_description::VideoDescription;
+use domains::value_objects::video_duration::VideoDuration;
+use domains::value_objects::video_etag::VideoEtag;
 use domains::value_objects::video_id::VideoId;
 use domains::value_objects::video_tag::VideoTag;
 use domains::value_objects::video_title::VideoTitle;
@@ -24,7 +29,19 @@ pub struct VideoIndex {
     pub channel_name: ChannelName,
     pub thumbnail_url: Option<ThumbnailUrl>,
     pub actual_start_at: Option<i64>,
+    #[serde(default)]
+    pub actual_end_at: Option<i64>,
+    #[serde(default)]
+    pub broadcast_state: BroadcastState,
     pub published_at: i64,
+    #[serde(default)]
+    pub duration: Option<u64>, // 秒単位
+    #[serde(default)]
+    pub etag: Option<VideoEtag>,
+    #[serde(default)]
+    pub availability: VideoAvailability,
+    #[serde(default)]
+    pub playlists: Vec<PlaylistId>,
 }
 
 impl VideoIndex {
@@ -38,7 +55,13 @@ impl VideoIndex {
             channel_name: video.channel.name,
             thumbnail_url: video.thumbnail.map(|t| t.url().clone()),
             actual_start_at: video.actual_start_at.map(|t| t.timestamp()),
+            actual_end_at: video.actual_end_at.map(|t| t.timestamp()),
+            broadcast_state: video.broadcast_state,
             published_at: video.published_at.timestamp(),
+            duration: video.duration.map(|d| d.value()),
+            etag: video.etag,
+            availability: video.availability,
+            playlists: video.playlists,
         }
     }
 }
@@ -67,7 +90,24 @@ impl From<VideoIndex> for VideoEntity {
         if let Some(t) = v.actual_start_at {
             builder = builder.with_actual_start_time(DateTime::from_timestamp(t, 0).unwrap());
         }
-        builder.construct().unwrap()
+
+        if let Some(t) = v.actual_end_at {
+            builder = builder.with_actual_end_time(DateTime::from_timestamp(t, 0).unwrap());
+        }
+
+        if let Some(d) = v.duration {
+            builder = builder.with_duration(VideoDuration(d));
+        }
+
+        if let Some(e) = v.etag {
+            builder = builder.with_etag(e);
+        }
+        builder
+            .with_broadcast_state(v.broadcast_state)
+            .with_availability(v.availability)
+            .with_playlists(v.playlists)
+            .construct()
+            .unwrap()
     }
 }
 
@@ -107,6 +147,8 @@ mod unit_tests {
 
         let entity = VideoEntityBuilder::new(id.clone(), title.clone(), channel.clone())
             .with_description(description)
+            .with_duration(VideoDuration::new(3600)?)
+            .with_etag(VideoEtag::new("etag")?)
             .construct()?;
         let index = VideoIndex::from_entity(entity.clone());
 
@@ -127,7 +169,12 @@ mod unit_tests {
             entity.actual_start_at.map(|t| t.timestamp())
         );
 
+        assert_eq!(index.duration, Some(3600));
+        assert_eq!(index.etag, entity.etag);
+
         let entity = VideoEntity::from(index.clone());
+        assert_eq!(entity.duration, Some(VideoDuration(3600)));
+        assert_eq!(entity.etag, Some(VideoEtag::new("etag")?));
         assert_eq!(index.video_id, entity.id);
         assert_eq!(index.video_tags, entity.tags);
         assert_eq!(index.video_title, entity.title);
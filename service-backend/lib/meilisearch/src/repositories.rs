@@ -1,15 +1,25 @@
 use crate::index::Index;
 use domains::repositories::internal_timestamp_search_repository::VideoTimestampSearchQuery;
+use domains::value_objects::limit::Limit;
+use domains::value_objects::song_key::SongKey;
+use domains::value_objects::video_id::VideoId;
 use errors::AppResult;
-use meilisearch_sdk::errors::Error as MeilisearchError;
+use meilisearch_sdk::errors::{Error as MeilisearchError, ErrorCode};
 use meilisearch_sdk::search::SearchResults;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 
+pub mod channel_crud;
+pub mod sync_state_crud;
 pub mod timestamp_crud;
 pub mod timestamp_search;
 pub mod video_crud;
 
+/// インデックスが作成されていないことによるエラーか
+pub(crate) fn is_index_not_found(e: &MeilisearchError) -> bool {
+    matches!(e, MeilisearchError::Meilisearch(e) if e.error_code == ErrorCode::IndexNotFound)
+}
+
 #[cfg_attr(test, mockall::automock)]
 #[async_trait::async_trait]
 pub trait MeilisearchCrudApi<I: Serialize + Index + DeserializeOwned + Send + Sync + 'static> {
@@ -36,9 +46,23 @@ pub trait MeilisearchCrudApi<I: Serialize + Index + DeserializeOwned + Send + Sy
 
     async fn get_all_entities(&self, index_name: &str) -> Result<Vec<I>, MeilisearchError>;
 
+    /// フィルタに一致するものをすべて取得する。フィルタの属性は filterableAttributes に含める必要がある
+    async fn get_entities_by_filter(
+        &self,
+        index_name: &str,
+        filter: &str,
+    ) -> Result<Vec<I>, MeilisearchError>;
+
     async fn delete_entity_by_id(&self, index_name: &str, id: &str)
     -> Result<(), MeilisearchError>;
 
+    /// フィルタに一致するものをすべて削除する。フィルタの属性は filterableAttributes に含める必要がある
+    async fn delete_entities_by_filter(
+        &self,
+        index_name: &str,
+        filter: &str,
+    ) -> Result<(), MeilisearchError>;
+
     async fn delete_all_entities(&self, index_name: &str) -> Result<(), MeilisearchError>;
 }
 
@@ -49,4 +73,12 @@ pub trait MeilisearchSearchApi<I: Serialize + Index + DeserializeOwned + Send +
         &self,
         search_query: VideoTimestampSearchQuery,
     ) -> AppResult<SearchResults<I>>;
+
+    async fn search_by_song_key(&self, key: &SongKey, limit: Limit) -> AppResult<SearchResults<I>>;
+
+    async fn search_by_video_id(
+        &self,
+        video_id: &VideoId,
+        limit: Limit,
+    ) -> AppResult<SearchResults<I>>;
 }
@@ -0,0 +1,107 @@
+use domains::entities::registered_channel::RegisteredChannelEntity;
+use domains::repositories::internal_channel_repository::InternalChannelRepository;
+use domains::value_objects::channel_id::ChannelId;
+
+use crate::client::ApiClient;
+use crate::index::Index;
+use crate::index::channel::ChannelIndex;
+use crate::repositories::{MeilisearchCrudApi, is_index_not_found};
+use errors::{AppError, AppResult};
+
+pub struct MeiliSearchChannelCrudRepository<T: MeilisearchCrudApi<ChannelIndex> + Send + Sync> {
+    client: T,
+}
+
+pub fn create_channel_crud_repository() -> MeiliSearchChannelCrudRepository<ApiClient> {
+    MeiliSearchChannelCrudRepository {
+        client: ApiClient::new(),
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: MeilisearchCrudApi<ChannelIndex> + Send + Sync> InternalChannelRepository
+    for MeiliSearchChannelCrudRepository<T>
+{
+    async fn add_or_update_channels(&self, channels: &[RegisteredChannelEntity]) -> AppResult<()> {
+        tracing::debug!("add_or_update_channels");
+        let i: Vec<ChannelIndex> = channels
+            .iter()
+            .map(|c| ChannelIndex::from_entity(c.clone()))
+            .collect();
+
+        self.client
+            .update_entities(ChannelIndex::name(), &i)
+            .await
+            .map_err(AppError::from)?;
+        Ok(())
+    }
+
+    async fn get_channel_by_id(
+        &self,
+        channel_id: &ChannelId,
+    ) -> AppResult<Option<RegisteredChannelEntity>> {
+        let result = self
+            .client
+            .get_entity_by_id(ChannelIndex::name(), channel_id.as_str())
+            .await
+            .map_err(AppError::from)?;
+        Ok(result.map(|c| c.into()))
+    }
+
+    async fn get_all_channels(&self) -> AppResult<Vec<RegisteredChannelEntity>> {
+        let result = match self.client.get_all_entities(ChannelIndex::name()).await {
+            Ok(result) => result,
+            // インデックスが未作成の場合は、登録が無いものとして扱う
+            Err(e) if is_index_not_found(&e) => {
+                tracing::warn!("Index {} not found", ChannelIndex::name());
+                return Ok(vec![]);
+            }
+            Err(e) => return Err(AppError::from(e)),
+        };
+        Ok(result.into_iter().map(|c| c.into()).collect())
+    }
+
+    async fn delete_channel_by_id(&self, channel_id: &ChannelId) -> AppResult<()> {
+        self.client
+            .delete_entity_by_id(ChannelIndex::name(), channel_id.as_str())
+            .await
+            .map_err(AppError::from)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::repositories::MockMeilisearchCrudApi;
+    use meilisearch_sdk::errors::{Error, ErrorCode, ErrorType, MeilisearchError};
+
+    fn meilisearch_error(code: ErrorCode) -> Error {
+        Error::Meilisearch(MeilisearchError {
+            error_message: String::new(),
+            error_code: code,
+            error_type: ErrorType::InvalidRequest,
+            error_link: String::new(),
+        })
+    }
+
+    #[tokio::test]
+    async fn missing_index_has_no_channels() {
+        let mut client = MockMeilisearchCrudApi::<ChannelIndex>::new();
+        client
+            .expect_get_all_entities()
+            .returning(|_| Err(meilisearch_error(ErrorCode::IndexNotFound)));
+        let repo = MeiliSearchChannelCrudRepository { client };
+        assert!(repo.get_all_channels().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn other_errors_are_returned() {
+        let mut client = MockMeilisearchCrudApi::<ChannelIndex>::new();
+        client
+            .expect_get_all_entities()
+            .returning(|_| Err(meilisearch_error(ErrorCode::InvalidApiKey)));
+        let repo = MeiliSearchChannelCrudRepository { client };
+        assert!(repo.get_all_channels().await.is_err());
+    }
+}
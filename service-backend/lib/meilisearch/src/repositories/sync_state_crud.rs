@@ -0,0 +1,46 @@
+use domains::entities::channel_sync_state::ChannelSyncStateEntity;
+use domains::repositories::internal_sync_state_repository::InternalSyncStateRepository;
+use domains::value_objects::channel_id::ChannelId;
+
+use crate::client::ApiClient;
+use crate::index::Index;
+use crate::index::sync_state::SyncStateIndex;
+use crate::repositories::MeilisearchCrudApi;
+use errors::{AppError, AppResult};
+
+pub struct MeiliSearchSyncStateCrudRepository<T: MeilisearchCrudApi<SyncStateIndex> + Send + Sync> {
+    client: T,
+}
+
+pub fn create_sync_state_crud_repository() -> MeiliSearchSyncStateCrudRepository<ApiClient> {
+    MeiliSearchSyncStateCrudRepository {
+        client: ApiClient::new(),
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: MeilisearchCrudApi<SyncStateIndex> + Send + Sync> InternalSyncStateRepository
+    for MeiliSearchSyncStateCrudRepository<T>
+{
+    async fn get_sync_state(
+        &self,
+        channel_id: &ChannelId,
+    ) -> AppResult<Option<ChannelSyncStateEntity>> {
+        let result = self
+            .client
+            .get_entity_by_id(SyncStateIndex::name(), channel_id.as_str())
+            .await
+            .map_err(AppError::from)?;
+        Ok(result.map(|s| s.into()))
+    }
+
+    async fn save_sync_state(&self, state: &ChannelSyncStateEntity) -> AppResult<()> {
+        tracing::debug!("save_sync_state : {}", state.channel_id);
+        let i = SyncStateIndex::from_entity(state.clone());
+        self.client
+            .update_entity(SyncStateIndex::name(), &i)
+            .await
+            .map_err(AppError::from)?;
+        Ok(())
+    }
+}
@@ -34,8 +34,7 @@ impl<T: MeilisearchCrudApi<TimeStampIndex> + Send + Sync> InternalVideoTimeStamp
         video_entity: &VideoEntity,
         timestamp_entity: &VideoTimestampEntity,
     ) -> AppResult<()> {
-        let i =
-            TimeStampIndex::from_entity(video_entity.clone(), timestamp_entity.timestamp.clone());
+        let i = TimeStampIndex::from_timestamp_entity(video_entity.clone(), timestamp_entity);
 
         self.client.add_entity(TimeStampIndex::name(), &i).await?;
         Ok(())
@@ -48,7 +47,7 @@ impl<T: MeilisearchCrudApi<TimeStampIndex> + Send + Sync> InternalVideoTimeStamp
     ) -> AppResult<()> {
         let v = entities
             .iter()
-            .map(|e| TimeStampIndex::from_entity(video_entity.clone(), e.timestamp.clone()))
+            .map(|e| TimeStampIndex::from_timestamp_entity(video_entity.clone(), e))
             .collect::<Vec<TimeStampIndex>>();
 
         // Implementation for adding a video entity to MeiliSearch
@@ -64,7 +63,7 @@ impl<T: MeilisearchCrudApi<TimeStampIndex> + Send + Sync> InternalVideoTimeStamp
         video_entity: &VideoEntity,
         entity: &VideoTimestampEntity,
     ) -> AppResult<()> {
-        let i = TimeStampIndex::from_entity(video_entity.clone(), entity.timestamp.clone());
+        let i = TimeStampIndex::from_timestamp_entity(video_entity.clone(), entity);
 
         self.client
             .update_entity(TimeStampIndex::name(), &i)
@@ -80,7 +79,7 @@ impl<T: MeilisearchCrudApi<TimeStampIndex> + Send + Sync> InternalVideoTimeStamp
     ) -> AppResult<()> {
         let v = entities
             .iter()
-            .map(|e| TimeStampIndex::from_entity(video_entity.clone(), e.timestamp.clone()))
+            .map(|e| TimeStampIndex::from_timestamp_entity(video_entity.clone(), e))
             .collect::<Vec<TimeStampIndex>>();
 
         self.client
@@ -115,6 +114,19 @@ impl<T: MeilisearchCrudApi<TimeStampIndex> + Send + Sync> InternalVideoTimeStamp
         Ok(entities.into_iter().map(|i| i.into()).collect())
     }
 
+    async fn get_video_timestamp_entities_by_video_id(
+        &self,
+        video_id: &VideoId,
+    ) -> AppResult<Vec<VideoTimestampEntity>> {
+        // 動画IDは英数字と `-` `_` のみなのでエスケープは不要
+        let filter = format!(r#"videoId = "{}""#, video_id);
+        let entities = self
+            .client
+            .get_entities_by_filter(TimeStampIndex::name(), &filter)
+            .await?;
+        Ok(entities.into_iter().map(|i| i.into_entity()).collect())
+    }
+
     async fn delete_video_timestamp_entity_by_id(&self, video_id: &VideoId) -> AppResult<()> {
         self.client
             .delete_entity_by_id(TimeStampIndex::name(), video_id.as_str())
@@ -124,8 +136,9 @@ impl<T: MeilisearchCrudApi<TimeStampIndex> + Send + Sync> InternalVideoTimeStamp
     }
 
     async fn delete_video_timestamp_entity_by_video_id(&self, video_id: &VideoId) -> AppResult<()> {
+        let filter = format!(r#"videoId = "{}""#, video_id);
         self.client
-            .delete_entity_by_id(TimeStampIndex::name(), video_id.as_str())
+            .delete_entities_by_filter(TimeStampIndex::name(), &filter)
             .await
             .map_err(AppError::from)?;
         Ok(())
@@ -1,9 +1,13 @@
 use crate::adapter::SearchResultConverter;
 use crate::client::ApiClient;
 use crate::repositories::MeilisearchSearchApi;
+use domains::entities::video_timestamp::VideoTimestampEntity;
 use domains::repositories::internal_timestamp_search_repository::{
     InternalVideoTimeStampSearchRepository, VideoTimestampSearchQuery, VideoTimestampSearchResult,
 };
+use domains::value_objects::limit::Limit;
+use domains::value_objects::song_key::SongKey;
+use domains::value_objects::video_id::VideoId;
 use errors::AppResult;
 
 pub struct MeilisearchTimestampSearchRepository {
@@ -28,4 +32,26 @@ impl InternalVideoTimeStampSearchRepository for MeilisearchTimestampSearchReposi
         tracing::debug!("Result : {:?}", ret);
         SearchResultConverter::convert_to_domain(ret)
     }
+
+    async fn find_timestamps_by_song_key(
+        &self,
+        key: &SongKey,
+        limit: Limit,
+    ) -> AppResult<Vec<VideoTimestampEntity>> {
+        tracing::debug!("Song key : {:?}", key);
+        let ret = self.client.search_by_song_key(key, limit).await?;
+
+        Ok(ret.hits.into_iter().map(|v| v.result.into()).collect())
+    }
+
+    async fn find_timestamps_by_video_id(
+        &self,
+        video_id: &VideoId,
+        limit: Limit,
+    ) -> AppResult<Vec<VideoTimestampEntity>> {
+        tracing::debug!("Video id : {:?}", video_id);
+        let ret = self.client.search_by_video_id(video_id, limit).await?;
+
+        Ok(ret.hits.into_iter().map(|v| v.result.into()).collect())
+    }
 }
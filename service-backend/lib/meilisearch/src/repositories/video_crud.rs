@@ -1,5 +1,7 @@
+use chrono::{DateTime, Utc};
 use domains::entities::video::VideoEntity;
 use domains::repositories::internal_video_repository::InternalVideoRepository;
+use domains::value_objects::playlist_id::PlaylistId;
 use domains::value_objects::video_id::VideoId;
 
 use crate::client::ApiClient;
@@ -7,6 +9,10 @@ use crate::index::Index;
 use crate::index::video::VideoIndex;
 use crate::repositories::MeilisearchCrudApi;
 use errors::{AppError, AppResult};
+use itertools::Itertools;
+
+/// 1回のフィルタで指定する動画IDの数
+const VIDEO_IDS_PER_FILTER: usize = 100;
 
 pub struct MeiliSearchVideoCrudRepository<T: MeilisearchCrudApi<VideoIndex> + Send + Sync> {
     client: T,
@@ -95,6 +101,28 @@ impl<T: MeilisearchCrudApi<VideoIndex> + Send + Sync> InternalVideoRepository
         Ok(result.map(|entity| entity.into()))
     }
 
+    async fn get_video_entities_by_ids(
+        &self,
+        video_ids: &[VideoId],
+    ) -> AppResult<Vec<VideoEntity>> {
+        let mut entities = Vec::with_capacity(video_ids.len());
+        // フィルタが長くなりすぎないよう、分けて取得する
+        for ids in video_ids.chunks(VIDEO_IDS_PER_FILTER) {
+            // 動画IDは英数字と `-` `_` のみなのでエスケープは不要
+            let filter = format!(
+                "videoId IN [{}]",
+                ids.iter().map(|id| format!(r#""{}""#, id)).join(", ")
+            );
+            let result = self
+                .client
+                .get_entities_by_filter(VideoIndex::name(), &filter)
+                .await
+                .map_err(AppError::from)?;
+            entities.extend(result.into_iter().map(|entity| entity.into()));
+        }
+        Ok(entities)
+    }
+
     async fn get_all_video_entities(&self) -> AppResult<Vec<VideoEntity>> {
         // Implementation for getting all video entities from MeiliSearch
         let result = self
@@ -105,6 +133,36 @@ impl<T: MeilisearchCrudApi<VideoIndex> + Send + Sync> InternalVideoRepository
         Ok(result.into_iter().map(|entity| entity.into()).collect())
     }
 
+    async fn get_video_entities_by_playlist_id(
+        &self,
+        playlist_id: &PlaylistId,
+    ) -> AppResult<Vec<VideoEntity>> {
+        // 再生リストIDは英数字と `-` `_` のみなのでエスケープは不要
+        let filter = format!(r#"playlists = "{}""#, playlist_id);
+        let result = self
+            .client
+            .get_entities_by_filter(VideoIndex::name(), &filter)
+            .await
+            .map_err(AppError::from)?;
+        Ok(result.into_iter().map(|entity| entity.into()).collect())
+    }
+
+    async fn get_recent_broadcast_entities(
+        &self,
+        ended_after: DateTime<Utc>,
+    ) -> AppResult<Vec<VideoEntity>> {
+        let filter = format!(
+            r#"availability != "unavailable" AND (broadcastState IN ["upcoming", "live", "premiere"] OR (broadcastState = "archived" AND actualEndAt >= {}))"#,
+            ended_after.timestamp()
+        );
+        let result = self
+            .client
+            .get_entities_by_filter(VideoIndex::name(), &filter)
+            .await
+            .map_err(AppError::from)?;
+        Ok(result.into_iter().map(|entity| entity.into()).collect())
+    }
+
     async fn delete_video_entity_by_id(&self, video_id: &VideoId) -> AppResult<()> {
         // Implementation for deleting a video entity by ID from MeiliSearch
         self.client
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Duration, Utc};
+use domains::entities::video::VideoEntity;
+use domains::repositories::external_video_repository::ExternalVideoRepository;
+use domains::repositories::internal_video_repository::InternalVideoRepository;
+use errors::AppResult;
+use std::sync::Arc;
+
+/// 配信の終了後に取得し直す期間の既定値（日）
+const DEFAULT_WINDOW_DAYS: i64 = 7;
+
+/// 配信予定・配信中の動画と、終了から一定期間内の配信を取得し直す
+///
+/// 配信の前や最中に登録した動画には、終了後に概要欄やコメントへタイムスタンプが書き足されるため、
+/// しばらくの間は取得し直して解析できるようにする。
+pub struct BroadcastRevisitService<E: ExternalVideoRepository, V: InternalVideoRepository> {
+    external_video_repository: Arc<E>,
+    video_repository: Arc<V>,
+    window: Duration,
+}
+
+impl<E: ExternalVideoRepository, V: InternalVideoRepository> BroadcastRevisitService<E, V> {
+    pub fn new(external_video_repository: Arc<E>, video_repository: Arc<V>) -> Self {
+        Self {
+            external_video_repository,
+            video_repository,
+            window: Duration::days(DEFAULT_WINDOW_DAYS),
+        }
+    }
+
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// 保存済みの動画から対象を選び、取得し直したものを返す
+    pub async fn revisit(&self, now: DateTime<Utc>) -> AppResult<Vec<VideoEntity>> {
+        let ids = self
+            .video_repository
+            .get_recent_broadcast_entities(now - self.window)
+            .await?
+            .into_iter()
+            .map(|v| v.id)
+            .collect::<Vec<_>>();
+        tracing::info!("Revisit videos count {}", ids.len());
+
+        self.external_video_repository
+            .fetch_videos_by_ids(&ids)
+            .await
+    }
+}
@@ -0,0 +1,217 @@
+use crate::channel_resolve_service::ChannelResolveService;
+use domains::entities::channel::ChannelEntity;
+use domains::entities::registered_channel::{DEFAULT_NUM_RECENT, RegisteredChannelEntity};
+use domains::repositories::external_channel_repository::ExternalChannelRepository;
+use domains::repositories::internal_channel_repository::InternalChannelRepository;
+use domains::value_objects::channel_name::ChannelName;
+use domains::value_objects::channel_reference::ChannelReference;
+use errors::{AppError, AppResult};
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::Arc;
+
+/// 登録ファイル上のチャンネル
+/// `id` にはチャンネルIDのほか、`@handle` やチャンネル・動画の URL も書ける
+///
+/// ```toml
+/// [[channels]]
+/// id = "UC_x5XG1OV2P6uZZ5FSM9Ttw"
+/// name = "Channel"
+/// num_recent = 20
+/// parser_profile = "karaoke"
+///
+/// [[channels]]
+/// id = "https://www.youtube.com/@GoogleDevelopers"
+/// name = "Handle"
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChannelRegistryEntry {
+    id: String,
+    name: String,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    #[serde(default = "default_num_recent")]
+    num_recent: u32,
+    #[serde(default)]
+    parser_profile: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_num_recent() -> u32 {
+    DEFAULT_NUM_RECENT
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ChannelRegistryFile {
+    #[serde(default)]
+    channels: Vec<ChannelRegistryEntry>,
+}
+
+/// 取得対象のチャンネルの登録と一覧
+pub struct ChannelRegistryService {
+    repository: Arc<dyn InternalChannelRepository + Send + Sync>,
+}
+
+impl ChannelRegistryService {
+    pub fn new(repository: Arc<dyn InternalChannelRepository + Send + Sync>) -> Self {
+        Self { repository }
+    }
+
+    /// TOML 形式の登録ファイルを読み込む
+    /// チャンネルID以外で書かれたチャンネルは `resolver` でチャンネルIDに解決する
+    /// 無効にしたチャンネルは利用枠を使って解決せず、チャンネルIDで書かれたもののみを無効として登録する
+    pub async fn load<R: ExternalChannelRepository>(
+        path: impl AsRef<Path>,
+        resolver: &ChannelResolveService<R>,
+    ) -> AppResult<Vec<RegisteredChannelEntity>> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path).map_err(|e| {
+            AppError::InvalidInput(format!("Unable to read {} : {}", path.display(), e))
+        })?;
+        Self::from_toml(&s, resolver).await
+    }
+
+    pub async fn from_toml<R: ExternalChannelRepository>(
+        s: &str,
+        resolver: &ChannelResolveService<R>,
+    ) -> AppResult<Vec<RegisteredChannelEntity>> {
+        let file: ChannelRegistryFile =
+            toml::from_str(s).map_err(|e| AppError::InvalidInput(e.to_string()))?;
+
+        let mut channels = Vec::with_capacity(file.channels.len());
+        for c in file.channels {
+            let id = match (c.enabled, ChannelReference::parse(&c.id)?) {
+                (_, ChannelReference::Id(id)) => id,
+                (true, _) => resolver.resolve(&c.id).await?,
+                (false, _) => {
+                    tracing::warn!("Skip disabled channel without channel ID : {}", c.id);
+                    continue;
+                }
+            };
+            let channel = ChannelEntity::new(id, ChannelName::new(&c.name)?);
+            channels.push(
+                RegisteredChannelEntity::new(channel)
+                    .with_enabled(c.enabled)
+                    .with_num_recent(c.num_recent)
+                    .with_parser_profile(c.parser_profile),
+            );
+        }
+        Ok(channels)
+    }
+
+    /// 登録する。登録済みのチャンネルは設定を上書きする
+    pub async fn register(&self, channels: &[RegisteredChannelEntity]) -> AppResult<()> {
+        if channels.is_empty() {
+            return Ok(());
+        }
+        tracing::info!("register channels count {}", channels.len());
+        self.repository.add_or_update_channels(channels).await
+    }
+
+    /// 取得対象のチャンネル
+    pub async fn enabled_channels(&self) -> AppResult<Vec<RegisteredChannelEntity>> {
+        let mut v = self
+            .repository
+            .get_all_channels()
+            .await?
+            .into_iter()
+            .filter(|c| c.enabled)
+            .collect::<Vec<_>>();
+        v.sort_by(|a, b| a.channel.id.as_str().cmp(b.channel.id.as_str()));
+        Ok(v)
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use crate::channel_resolve_service::unit_tests::StubChannelRepository;
+    use domains::value_objects::channel_id::ChannelId;
+
+    fn resolver() -> ChannelResolveService<StubChannelRepository> {
+        ChannelResolveService::new(Arc::new(StubChannelRepository::default()))
+    }
+
+    #[tokio::test]
+    async fn skip_resolving_disabled_channels() -> anyhow::Result<()> {
+        let repo = Arc::new(StubChannelRepository::default());
+        let resolver = ChannelResolveService::new(repo.clone());
+        let v = ChannelRegistryService::from_toml(
+            r#"
+[[channels]]
+id = "@unknown"
+name = "Disabled handle"
+enabled = false
+
+[[channels]]
+id = "UC_1234567890abcdefghijk"
+name = "Disabled"
+enabled = false
+"#,
+            &resolver,
+        )
+        .await?;
+
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].channel.id, ChannelId::new("UC_1234567890abcdefghijk")?);
+        assert!(!v[0].enabled);
+        assert!(repo.requested.lock().unwrap().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn load_registry() -> anyhow::Result<()> {
+        let v = ChannelRegistryService::from_toml(
+            r#"
+[[channels]]
+id = "UC_x5XG1OV2P6uZZ5FSM9Ttw"
+name = "Main"
+num_recent = 20
+parser_profile = "karaoke"
+
+[[channels]]
+id = "UC_1234567890abcdefghijk"
+name = "Sub"
+enabled = false
+
+[[channels]]
+id = "https://www.youtube.com/@googledevelopers"
+name = "Handle"
+"#,
+            &resolver(),
+        )
+        .await?;
+
+        assert_eq!(v.len(), 3);
+        assert_eq!(v[0].channel.id, ChannelId::new("UC_x5XG1OV2P6uZZ5FSM9Ttw")?);
+        assert_eq!(v[0].num_recent, 20);
+        assert_eq!(v[0].parser_profile.as_deref(), Some("karaoke"));
+        assert!(v[0].enabled);
+        assert_eq!(v[1].num_recent, DEFAULT_NUM_RECENT);
+        assert!(!v[1].enabled);
+        assert_eq!(v[2].channel.id, ChannelId::new("UC_x5XG1OV2P6uZZ5FSM9Ttw")?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reject_invalid_registry() {
+        let resolver = resolver();
+        for s in [
+            "[[channels]]\nid = \"invalid\"\nname = \"x\"",
+            "[[channels]]\nname = \"x\"",
+            "[[channels]]\nid = \"@unknown\"\nname = \"x\"",
+        ] {
+            assert!(
+                ChannelRegistryService::from_toml(s, &resolver)
+                    .await
+                    .is_err(),
+                "{s}"
+            );
+        }
+    }
+}
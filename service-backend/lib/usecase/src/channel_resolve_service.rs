@@ -0,0 +1,84 @@
+use domains::repositories::external_channel_repository::ExternalChannelRepository;
+use domains::value_objects::channel_id::ChannelId;
+use domains::value_objects::channel_reference::ChannelReference;
+use errors::AppResult;
+use std::sync::Arc;
+
+/// チャンネルID・`@handle`・チャンネルや動画の URL を、チャンネルIDに解決する
+/// チャンネルIDやチャンネルの URL は取得元に問い合わせない
+pub struct ChannelResolveService<R: ExternalChannelRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: ExternalChannelRepository> ChannelResolveService<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+
+    pub async fn resolve(&self, s: &str) -> AppResult<ChannelId> {
+        match ChannelReference::parse(s)? {
+            ChannelReference::Id(id) => Ok(id),
+            reference => self.repository.fetch_channel_id(&reference).await,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod unit_tests {
+    use super::*;
+    use errors::AppError;
+    use std::sync::Mutex;
+
+    pub(crate) const CHANNEL_ID: &str = "UC_x5XG1OV2P6uZZ5FSM9Ttw";
+
+    /// `@googledevelopers` のみを解決する
+    #[derive(Default)]
+    pub(crate) struct StubChannelRepository {
+        pub(crate) requested: Mutex<Vec<ChannelReference>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ExternalChannelRepository for StubChannelRepository {
+        async fn fetch_channel_id(&self, reference: &ChannelReference) -> AppResult<ChannelId> {
+            self.requested.lock().unwrap().push(reference.clone());
+            match reference {
+                ChannelReference::Handle(h) if h == "googledevelopers" => {
+                    ChannelId::new(CHANNEL_ID)
+                }
+                _ => Err(AppError::NotFound(reference.to_string())),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn resolve_references() -> anyhow::Result<()> {
+        let repo = Arc::new(StubChannelRepository::default());
+        let service = ChannelResolveService::new(repo.clone());
+
+        let expected = ChannelId::new(CHANNEL_ID)?;
+        assert_eq!(service.resolve(CHANNEL_ID).await?, expected);
+        assert_eq!(
+            service
+                .resolve("https://www.youtube.com/channel/UC_x5XG1OV2P6uZZ5FSM9Ttw")
+                .await?,
+            expected
+        );
+        // チャンネルIDは問い合わせない
+        assert!(repo.requested.lock().unwrap().is_empty());
+
+        assert_eq!(service.resolve("@googledevelopers").await?, expected);
+        assert_eq!(
+            service
+                .resolve("https://www.youtube.com/@googledevelopers/videos")
+                .await?,
+            expected
+        );
+        assert!(matches!(
+            service.resolve("@unknown").await,
+            Err(AppError::NotFound(_))
+        ));
+        assert!(service.resolve("https://example.com/").await.is_err());
+        assert_eq!(repo.requested.lock().unwrap().len(), 3);
+        Ok(())
+    }
+}
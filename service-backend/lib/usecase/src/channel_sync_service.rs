@@ -0,0 +1,64 @@
+use domains::entities::channel_sync_state::ChannelSyncStateEntity;
+use domains::entities::video::VideoEntity;
+use domains::repositories::external_video_repository::ExternalVideoRepository;
+use domains::repositories::internal_sync_state_repository::InternalSyncStateRepository;
+use domains::value_objects::channel_id::ChannelId;
+use errors::AppResult;
+use futures::channel::mpsc::Sender;
+use std::sync::Arc;
+
+/// 前回の取得状況を保存し、チャンネルの動画を差分のみ取得する
+pub struct ChannelSyncService<E: ExternalVideoRepository> {
+    external_video_repository: Arc<E>,
+    sync_state_repository: Arc<dyn InternalSyncStateRepository + Send + Sync>,
+}
+
+impl<E: ExternalVideoRepository> ChannelSyncService<E> {
+    pub fn new(
+        external_video_repository: Arc<E>,
+        sync_state_repository: Arc<dyn InternalSyncStateRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            external_video_repository,
+            sync_state_repository,
+        }
+    }
+
+    /// 新しい動画と変更のあった動画を取得できたまとまりごとに `sink` へ送り、取得後の取得状況を返す
+    ///
+    /// 取得状況は保存しない。動画を登録し終えてから `save` で保存する。
+    ///
+    /// * `count` - 少なくとも確認する直近の動画の数。`None` の場合は全件を確認する
+    /// * `full` - 保存済みの取得状況を使わずに取得し直す
+    pub async fn sync(
+        &self,
+        channel_id: &ChannelId,
+        count: Option<u32>,
+        full: bool,
+        sink: Sender<Vec<VideoEntity>>,
+    ) -> AppResult<ChannelSyncStateEntity> {
+        let state = if full {
+            None
+        } else {
+            self.sync_state_repository
+                .get_sync_state(channel_id)
+                .await?
+        };
+        let state = state.unwrap_or_else(|| ChannelSyncStateEntity::new(channel_id.clone()));
+
+        let state = self
+            .external_video_repository
+            .fetch_updated_videos_by_channel_id(channel_id, &state, count, sink)
+            .await?;
+
+        if state.page_token.is_some() {
+            tracing::warn!("Fetching videos of {} is suspended", channel_id);
+        }
+        Ok(state)
+    }
+
+    /// 取得状況を保存する。取得した動画を登録し終えてから呼ぶ
+    pub async fn save(&self, state: &ChannelSyncStateEntity) -> AppResult<()> {
+        self.sync_state_repository.save_sync_state(state).await
+    }
+}
@@ -1,4 +1,17 @@
+pub mod broadcast_revisit_service;
+pub mod channel_registry_service;
+pub mod channel_resolve_service;
+pub mod channel_sync_service;
+pub mod parse_report_service;
+pub mod setlist_parser_service;
+pub mod song_performance_service;
 pub mod timestamp_indexing_service;
+pub mod timestamp_merge_service;
+pub mod timestamp_parser_rule;
 pub mod timestamp_parser_service;
+pub mod timestamp_reindex_service;
+pub mod timestamp_segment_service;
 pub mod video_fetch_service;
 pub mod video_indexing_service;
+pub mod video_reconcile_service;
+pub mod video_timestamp_service;
@@ -0,0 +1,127 @@
+use crate::timestamp_parser_service::TimeStampParserService;
+use domains::entities::video::VideoEntity;
+use domains::entities::video_timestamp::VideoTimestampEntity;
+use domains::value_objects::confidence::Confidence;
+use domains::value_objects::video_id::VideoId;
+use errors::AppResult;
+use std::collections::HashSet;
+use std::fmt;
+
+/// 誤検出の疑いとみなす確からしさの既定値
+const DEFAULT_SUSPECT_THRESHOLD: u8 = 50;
+
+/// 1動画分の解析結果の集計
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseReport {
+    pub video_id: VideoId,
+    /// 抽出したタイムスタンプ数
+    pub count: usize,
+    /// 直前より前の時刻に戻っているもの
+    pub out_of_order: usize,
+    /// 同じ時刻が既に現れているもの
+    pub duplicates: usize,
+    /// 確からしさが閾値を下回るもの
+    pub suspected: usize,
+}
+
+impl fmt::Display for ParseReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}",
+            self.video_id, self.count, self.out_of_order, self.duplicates, self.suspected
+        )
+    }
+}
+
+/// 保存済みの動画を解析し直し、抽出の品質を動画ごとに集計する
+///
+/// 外部APIは呼ばず、概要欄のみを対象とする。
+pub struct ParseReportService {
+    parser: TimeStampParserService,
+    threshold: Confidence,
+}
+
+impl ParseReportService {
+    pub fn new(parser: TimeStampParserService) -> Self {
+        Self {
+            parser,
+            threshold: Confidence(DEFAULT_SUSPECT_THRESHOLD),
+        }
+    }
+
+    pub fn with_threshold(mut self, threshold: Confidence) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn report(&self, video: &VideoEntity) -> AppResult<ParseReport> {
+        let tss = self
+            .parser
+            .parse_video(video)?
+            .into_iter()
+            .filter(|t| t.video_id == video.id)
+            .collect::<Vec<_>>();
+        Ok(self.summarize(video.id.clone(), &tss))
+    }
+
+    /// 記載順のタイムスタンプを集計する
+    fn summarize(&self, video_id: VideoId, tss: &[VideoTimestampEntity]) -> ParseReport {
+        let mut seen = HashSet::new();
+        let mut latest = None;
+        let mut report = ParseReport {
+            video_id,
+            count: tss.len(),
+            out_of_order: 0,
+            duplicates: 0,
+            suspected: 0,
+        };
+
+        for t in tss {
+            let time = t.timestamp.elapsed_time;
+            if latest.is_some_and(|l| time < l) {
+                report.out_of_order += 1;
+            }
+            if !seen.insert(time) {
+                report.duplicates += 1;
+            }
+            if t.confidence.is_some_and(|c| c < self.threshold) {
+                report.suspected += 1;
+            }
+            latest = latest.max(Some(time));
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use domains::entities::channel::ChannelEntity;
+    use domains::value_objects::channel_name::ChannelName;
+    use domains::value_objects::video_description::VideoDescription;
+    use domains::value_objects::video_title::VideoTitle;
+
+    #[test]
+    fn report_video() -> anyhow::Result<()> {
+        let c = ChannelEntity::with_random_id(ChannelName::new("channel")?);
+        let v = VideoEntity::with_random_id(VideoTitle::new("title")?, c)
+            .with_description(VideoDescription::new(
+                "00:10 Opening\n05:00 Song A\n01:00 Song B\n05:00 Song A again\n06:00 ★☆★☆",
+            )?)
+            .construct()?;
+
+        let report = ParseReportService::new(TimeStampParserService::new()).report(&v)?;
+        assert_eq!(
+            report,
+            ParseReport {
+                video_id: v.id.clone(),
+                count: 5,
+                out_of_order: 1,
+                duplicates: 1,
+                suspected: 1,
+            }
+        );
+        Ok(())
+    }
+}
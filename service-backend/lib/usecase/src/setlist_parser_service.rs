@@ -0,0 +1,241 @@
+use domains::entities::video_timestamp::VideoTimestampEntity;
+use domains::value_objects::setlist_entry::SetlistEntry;
+use domains::value_objects::song_artist::SongArtist;
+use domains::value_objects::song_tag::SongTag;
+use domains::value_objects::song_title::SongTitle;
+use std::collections::HashSet;
+
+/// 曲名とアーティストの区切り。上から順に優先する
+///
+/// 半角の `/` や `by` は曲名にも含まれる（`AC/DC`、`Stand by Me`）ため区切りにしない。
+const SEPARATORS: &[&str] = &["／", " / "];
+
+/// 曲の中でのみ使う区切り
+///
+/// ダッシュは `雑談 - 近況報告` のような曲以外の説明にも使われるため、
+/// セットリストの見出しの下か、タグの付いた説明文でのみ区切りとする。
+const DASHES: &[&str] = &[" - ", " – ", " — "];
+
+/// セットリストの見出しとみなす語。小文字で比べる
+const SETLIST_HEADINGS: &[&str] = &[
+    "セトリ",
+    "セットリスト",
+    "setlist",
+    "set list",
+    "歌枠",
+    "歌パート",
+    "karaoke",
+    "カラオケ",
+    "songs",
+];
+
+/// タグを囲む括弧
+const BRACKETS: &[(char, char)] = &[('(', ')'), ('（', '）'), ('[', ']'), ('【', '】')];
+
+/// タグの正規名と表記揺れ
+const TAG_ALIASES: &[(&str, &[&str])] = &[
+    ("cover", &["cover", "covered", "カバー", "歌ってみた"]),
+    ("original", &["original", "オリジナル", "オリ曲"]),
+    ("acoustic", &["acoustic", "アコースティック", "アコギ"]),
+];
+
+/// セットリスト形式のタイムスタンプ説明文から曲名・アーティスト・タグを取り出す
+///
+/// `曲名 / アーティスト` や `Song - Artist (cover)` のような形式を想定する。
+/// 区切りもタグも無い説明文は曲とみなさない。ダッシュはセットリストの見出しの下かタグ付きの場合のみ区切る。
+#[derive(Default)]
+pub struct SetlistParserService {}
+
+impl SetlistParserService {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// 曲情報を付与する。既に付与済みのものはそのまま
+    pub fn annotate(&self, entities: Vec<VideoTimestampEntity>) -> Vec<VideoTimestampEntity> {
+        let setlists = entities
+            .iter()
+            .filter(|e| Self::is_setlist_heading(&e.timestamp.description.display()))
+            .filter_map(|e| e.id().ok())
+            .collect::<HashSet<_>>();
+        entities
+            .into_iter()
+            .map(|e| {
+                if e.song.is_some() {
+                    return e;
+                }
+                let in_setlist = e.parent_id.as_ref().is_some_and(|p| setlists.contains(p));
+                let song = self.parse_in(&e.timestamp.description.display(), in_setlist);
+                e.with_song(song)
+            })
+            .collect()
+    }
+
+    /// 見出しの無い説明文として曲情報を取り出す
+    pub fn parse(&self, description: &str) -> Option<SetlistEntry> {
+        self.parse_in(description, false)
+    }
+
+    /// `in_setlist` はセットリストの見出しの下にあるか
+    pub fn parse_in(&self, description: &str, in_setlist: bool) -> Option<SetlistEntry> {
+        let (rest, tags) = Self::extract_tags(description.trim());
+        let rest = rest.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let dashes = match in_setlist || !tags.is_empty() {
+            true => DASHES,
+            false => &[],
+        };
+        let (title, artist) = match SEPARATORS
+            .iter()
+            .chain(dashes)
+            .find_map(|sep| rest.split_once(sep))
+        {
+            Some((title, artist)) => (title, SongArtist::new(artist).ok()),
+            None => (rest.as_str(), None),
+        };
+        let title = SongTitle::new(title).ok()?;
+
+        if artist.is_none() && tags.is_empty() {
+            return None;
+        }
+        Some(SetlistEntry::new(title, artist, tags))
+    }
+
+    fn is_setlist_heading(description: &str) -> bool {
+        let d = description.to_lowercase();
+        SETLIST_HEADINGS.iter().any(|h| d.contains(h))
+    }
+
+    /// 括弧で囲まれたタグを取り除き、残りの文字列とタグを返す
+    fn extract_tags(s: &str) -> (String, Vec<SongTag>) {
+        let mut rest = String::new();
+        let mut tags: Vec<SongTag> = Vec::new();
+        let mut remain = s;
+
+        while let Some((pos, open, close)) = BRACKETS
+            .iter()
+            .filter_map(|(o, c)| remain.find(*o).map(|p| (p, *o, *c)))
+            .min_by_key(|(p, ..)| *p)
+        {
+            let inner_start = pos + open.len_utf8();
+            let Some(len) = remain[inner_start..].find(close) else {
+                break;
+            };
+            let end = inner_start + len + close.len_utf8();
+
+            match Self::known_tags(&remain[inner_start..inner_start + len]) {
+                Some(found) => {
+                    rest.push_str(&remain[..pos]);
+                    for t in found {
+                        if !tags.contains(&t) {
+                            tags.push(t);
+                        }
+                    }
+                }
+                None => rest.push_str(&remain[..end]),
+            }
+            remain = &remain[end..];
+        }
+        rest.push_str(remain);
+
+        (rest, tags)
+    }
+
+    /// 括弧内がすべて既知のタグであればタグの一覧を返す
+    fn known_tags(inner: &str) -> Option<Vec<SongTag>> {
+        let words = inner
+            .split(|c: char| c.is_whitespace() || matches!(c, ',' | '、' | '/' | '／'))
+            .filter(|w| !w.is_empty())
+            .collect::<Vec<_>>();
+        if words.is_empty() {
+            return None;
+        }
+
+        words
+            .into_iter()
+            .map(|w| {
+                let w = w.to_lowercase();
+                TAG_ALIASES
+                    .iter()
+                    .find(|(_, aliases)| aliases.contains(&w.as_str()))
+                    .and_then(|(name, _)| SongTag::new(name).ok())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use domains::value_objects::timestamp::TimeStamp;
+    use domains::value_objects::timestamp_description::TimeStampDescription;
+    use domains::value_objects::video_id::VideoId;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("曲名 / アーティスト", "曲名", Some("アーティスト"), &[])]
+    #[case("曲名／アーティスト", "曲名", Some("アーティスト"), &[])]
+    #[case("Song - Artist (cover)", "Song", Some("Artist"), &["cover"])]
+    #[case("Song - Artist [Acoustic Cover]", "Song", Some("Artist"), &["acoustic", "cover"])]
+    #[case("Stand by Me (cover)", "Stand by Me", None, &["cover"])]
+    #[case("Fate/Zero / Artist", "Fate/Zero", Some("Artist"), &[])]
+    #[case("Back In Black - AC/DC (cover)", "Back In Black", Some("AC/DC"), &["cover"])]
+    #[case("【歌ってみた】曲名 / アーティスト", "曲名", Some("アーティスト"), &["cover"])]
+    #[case("Song (Live ver.) / Artist", "Song (Live ver.)", Some("Artist"), &[])]
+    #[case("Song (original)", "Song", None, &["original"])]
+    fn parse_setlist(
+        #[case] description: &str,
+        #[case] title: &str,
+        #[case] artist: Option<&str>,
+        #[case] tags: &[&str],
+    ) {
+        let e = SetlistParserService::new().parse(description).unwrap();
+        assert_eq!(e.title, title);
+        assert_eq!(e.artist.as_ref().map(|a| a.as_str()), artist);
+        assert_eq!(e.tags.iter().map(|t| t.as_str()).collect::<Vec<_>>(), tags);
+    }
+
+    #[rstest]
+    #[case("オープニング")]
+    #[case("Ending (雑談)")]
+    #[case(" / Artist")]
+    #[case("Stand by Me")]
+    #[case("Fate/Zero")]
+    #[case::chat_with_dash("雑談 - 近況報告")]
+    #[case::bgm_with_dash("Opening - BGM")]
+    #[case::en_dash("Talk – Q&A")]
+    fn parse_not_setlist(#[case] description: &str) {
+        assert_eq!(SetlistParserService::new().parse(description), None);
+    }
+
+    #[test]
+    fn annotate_entities() -> anyhow::Result<()> {
+        let id = VideoId::new("abcdefghijk")?;
+        let ts = |s: &str| {
+            VideoTimestampEntity::new(
+                id.clone(),
+                TimeStamp::new(0, TimeStampDescription::new(s).unwrap()).unwrap(),
+            )
+        };
+
+        // ダッシュはセットリストの見出しの下でのみ区切る
+        let setlist = ts("歌枠パート");
+        let chat = ts("雑談パート");
+        let tss = SetlistParserService::new().annotate(vec![
+            setlist.clone(),
+            ts("Song - Artist").with_parent_id(Some(setlist.id()?)),
+            ts("雑談"),
+            chat.clone(),
+            ts("Opening - BGM").with_parent_id(Some(chat.id()?)),
+            ts("Opening - BGM"),
+        ]);
+        assert_eq!(
+            tss[1].song.as_ref().and_then(|s| s.artist.clone()),
+            Some(SongArtist::new("Artist")?)
+        );
+        assert_eq!(tss[2].song, None);
+        assert_eq!(tss[4].song, None);
+        assert_eq!(tss[5].song, None);
+        Ok(())
+    }
+}
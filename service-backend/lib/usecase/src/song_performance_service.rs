@@ -0,0 +1,149 @@
+use domains::entities::video_timestamp::VideoTimestampEntity;
+use domains::repositories::internal_timestamp_search_repository::InternalVideoTimeStampSearchRepository;
+use domains::value_objects::limit::Limit;
+use domains::value_objects::song_key::SongKey;
+use errors::AppResult;
+use std::sync::Arc;
+
+/// 1曲あたりに取得する歌唱回数の上限
+const PERFORMANCES_LIMIT: usize = 1000;
+
+/// 同じ曲が歌われたタイムスタンプを動画をまたいで集める
+///
+/// タイムスタンプのインデックスに保存済みのキーでまとめるため、外部APIは呼ばない。
+#[derive(Clone)]
+pub struct SongPerformanceService {
+    search_repository: Arc<dyn InternalVideoTimeStampSearchRepository + Send + Sync>,
+}
+
+impl SongPerformanceService {
+    pub fn new(
+        search_repository: Arc<dyn InternalVideoTimeStampSearchRepository + Send + Sync>,
+    ) -> Self {
+        Self { search_repository }
+    }
+
+    /// 曲名（正規化前後どちらでもよい）から歌唱回を配信開始日時の古い順に返す
+    pub async fn performances(
+        &self,
+        title: &str,
+    ) -> AppResult<(SongKey, Vec<VideoTimestampEntity>)> {
+        let key = SongKey::new(title)?;
+        let mut v = self
+            .search_repository
+            .find_timestamps_by_song_key(&key, Limit::new(PERFORMANCES_LIMIT)?)
+            .await?;
+
+        // 配信開始日時が無いもの（プレミア公開でない動画など）は公開日時で代用し、どちらも無ければ末尾
+        v.sort_by_key(|e| {
+            let at = e
+                .video_details
+                .as_ref()
+                .and_then(|d| d.actual_start_at.or(d.published_at));
+            (at.is_none(), at, *e.timestamp.elapsed_time)
+        });
+
+        tracing::debug!("song {} performances count {}", key, v.len());
+        Ok((key, v))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use domains::repositories::internal_timestamp_search_repository::{
+        VideoTimestampSearchQuery, VideoTimestampSearchResult,
+    };
+    use domains::value_objects::timestamp::TimeStamp;
+    use domains::value_objects::timestamp_description::TimeStampDescription;
+    use domains::value_objects::video_detail::VideoDetail;
+    use domains::value_objects::video_id::VideoId;
+
+    /// 保存済みのタイムスタンプから条件に合うものを返す。検索クエリの条件は見ない
+    struct StubRepository {
+        items: Vec<VideoTimestampEntity>,
+    }
+
+    #[async_trait::async_trait]
+    impl InternalVideoTimeStampSearchRepository for StubRepository {
+        async fn search_timestamps_by_query(
+            &self,
+            query: VideoTimestampSearchQuery,
+        ) -> AppResult<VideoTimestampSearchResult> {
+            Ok(VideoTimestampSearchResult {
+                items: self.items.clone(),
+                page: query.page,
+                per_page: query.per_page,
+                total_pages: 1,
+                total_hits: self.items.len(),
+            })
+        }
+
+        async fn find_timestamps_by_song_key(
+            &self,
+            key: &SongKey,
+            limit: Limit,
+        ) -> AppResult<Vec<VideoTimestampEntity>> {
+            Ok(self
+                .items
+                .iter()
+                .filter(|e| SongKey::new(&e.timestamp.description).ok().as_ref() == Some(key))
+                .take(*limit)
+                .cloned()
+                .collect())
+        }
+
+        async fn find_timestamps_by_video_id(
+            &self,
+            video_id: &VideoId,
+            limit: Limit,
+        ) -> AppResult<Vec<VideoTimestampEntity>> {
+            let mut v = self
+                .items
+                .iter()
+                .filter(|e| &e.video_id == video_id)
+                .cloned()
+                .collect::<Vec<_>>();
+            v.sort_by_key(|e| e.timestamp.elapsed_time);
+            v.truncate(*limit);
+            Ok(v)
+        }
+    }
+
+    fn performance(id: &str, actual_start_at: Option<i64>) -> VideoTimestampEntity {
+        VideoTimestampEntity::with_details(
+            VideoId::new(id).unwrap(),
+            TimeStamp::new(60, TimeStampDescription::new("Song Title").unwrap()).unwrap(),
+            Some(VideoDetail {
+                video_title: None,
+                video_tags: None,
+                thumbnail_url: None,
+                published_at: None,
+                actual_start_at: actual_start_at
+                    .and_then(|t| DateTime::<Utc>::from_timestamp(t, 0)),
+                duration: None,
+                channel_id: None,
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn performances_sorted_by_actual_start_at() -> anyhow::Result<()> {
+        let repo = StubRepository {
+            items: vec![
+                performance("bbbbbbbbbbb", Some(2_000)),
+                performance("ccccccccccc", None),
+                performance("aaaaaaaaaaa", Some(1_000)),
+            ],
+        };
+        let service = SongPerformanceService::new(Arc::new(repo));
+        assert!(service.performances("Other Song").await?.1.is_empty());
+
+        let (key, v) = service.performances("ＳＯＮＧ　ＴＩＴＬＥ").await?;
+        assert_eq!(key.as_str(), "songtitle");
+        let ids = v.iter().map(|e| e.video_id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["aaaaaaaaaaa", "bbbbbbbbbbb", "ccccccccccc"]);
+        Ok(())
+    }
+}
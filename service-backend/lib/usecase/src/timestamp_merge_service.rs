@@ -0,0 +1,193 @@
+use domains::entities::video_timestamp::VideoTimestampEntity;
+use domains::value_objects::timestamp_description::TimeStampDescription;
+use domains::value_objects::timestamp_id::TimestampId;
+use std::collections::{HashMap, HashSet};
+
+/// 同一とみなす経過時間の差（秒）
+const DEFAULT_TOLERANCE_SECONDS: u64 = 3;
+/// 同一とみなす説明文の類似度（0.0 ~ 1.0）
+const DEFAULT_SIMILARITY_THRESHOLD: f64 = 0.8;
+
+/// 概要欄・コメント等、複数の取得元から得たタイムスタンプの重複をまとめる
+///
+/// 同じ動画で経過時間の差が許容範囲内、かつ説明文が類似しているものを1件にまとめ、
+/// 取得元はすべて残す。先に現れたタイムスタンプを代表として採用する。
+/// まとめられた側を親にしていたタイムスタンプは、代表を親とするよう付け替える。
+pub struct TimeStampMergeService {
+    tolerance: u64,
+    threshold: f64,
+}
+
+impl Default for TimeStampMergeService {
+    fn default() -> Self {
+        Self::new(DEFAULT_TOLERANCE_SECONDS, DEFAULT_SIMILARITY_THRESHOLD)
+    }
+}
+
+impl TimeStampMergeService {
+    pub fn new(tolerance: u64, threshold: f64) -> Self {
+        Self {
+            tolerance,
+            threshold,
+        }
+    }
+
+    pub fn merge(&self, entities: Vec<VideoTimestampEntity>) -> Vec<VideoTimestampEntity> {
+        let mut result: Vec<VideoTimestampEntity> = Vec::with_capacity(entities.len());
+        let mut replaced: HashMap<TimestampId, TimestampId> = HashMap::new();
+
+        for e in entities {
+            let found = result.iter_mut().find(|m| {
+                m.video_id == e.video_id
+                    && m.timestamp.elapsed_time.abs_diff(*e.timestamp.elapsed_time)
+                        <= self.tolerance
+                    && self.is_similar(&m.timestamp.description, &e.timestamp.description)
+            });
+
+            match found {
+                Some(m) => {
+                    m.merge_sources(&e.sources);
+                    if m.parent_id.is_none() {
+                        m.parent_id = e.parent_id.clone();
+                    }
+                    m.confidence = m.confidence.max(e.confidence);
+                    // 範囲で書かれた終了時間は、後で前後から導出した終了時間に上書きされないよう引き継ぐ
+                    m.timestamp.end_time = m.timestamp.end_time.or(e.timestamp.end_time);
+                    if let (Ok(from), Ok(to)) = (e.id(), m.id())
+                        && from != to
+                    {
+                        replaced.insert(from, to);
+                    }
+                }
+                None => result.push(e),
+            }
+        }
+
+        for e in result.iter_mut() {
+            if let Some(to) = e.parent_id.as_ref().and_then(|p| replaced.get(p)) {
+                e.parent_id = Some(to.clone());
+            }
+        }
+
+        tracing::debug!("merged timestamps count {}", result.len());
+        result
+    }
+
+    fn is_similar(&self, a: &TimeStampDescription, b: &TimeStampDescription) -> bool {
+        let (a, b) = (a.folded(), b.folded());
+        if a == b {
+            return true;
+        }
+        if a.is_empty() || b.is_empty() {
+            return false;
+        }
+        dice_coefficient(&a, &b) >= self.threshold
+    }
+}
+
+/// 文字bigramによるDice係数
+fn dice_coefficient(a: &str, b: &str) -> f64 {
+    fn bigrams(s: &str) -> HashSet<(char, char)> {
+        let c: Vec<char> = s.chars().collect();
+        c.windows(2).map(|w| (w[0], w[1])).collect()
+    }
+
+    let (a, b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let common = a.intersection(&b).count();
+    2.0 * common as f64 / (a.len() + b.len()) as f64
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use domains::value_objects::comment_author::CommentAuthor;
+    use domains::value_objects::comment_id::CommentId;
+    use domains::value_objects::elapsed_time::ElapsedTime;
+    use domains::value_objects::timestamp::TimeStamp;
+    use domains::value_objects::timestamp_source::TimeStampSource;
+    use domains::value_objects::video_id::VideoId;
+
+    fn entity(video_id: &str, sec: u64, d: &str, source: TimeStampSource) -> VideoTimestampEntity {
+        VideoTimestampEntity::new(
+            VideoId::new(video_id).unwrap(),
+            TimeStamp::new(sec, TimeStampDescription::new(d).unwrap()).unwrap(),
+        )
+        .with_source(source)
+    }
+
+    fn comment() -> TimeStampSource {
+        TimeStampSource::Comment {
+            comment_id: CommentId::new("comment-1").unwrap(),
+            author: CommentAuthor::new("@author").unwrap(),
+        }
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case::punctuation(10, "Opening talk!", 1)]
+    #[case::within_tolerance(12, "opening talk", 1)]
+    #[case::typo(11, "Opening talks", 1)]
+    #[case::out_of_tolerance(20, "Opening talk", 2)]
+    #[case::different_text(10, "Ending", 2)]
+    fn merge_timestamps(#[case] sec: u64, #[case] d: &str, #[case] expected: usize) {
+        let v = vec![
+            entity(
+                "aqz-KE-bpKQ",
+                10,
+                "Opening Talk",
+                TimeStampSource::Description,
+            ),
+            // コメントの方のみ範囲で書かれている
+            {
+                let mut e = entity("aqz-KE-bpKQ", sec, d, comment());
+                e.timestamp.end_time = Some(ElapsedTime(930));
+                e
+            },
+        ];
+
+        let merged = TimeStampMergeService::default().merge(v);
+        assert_eq!(merged.len(), expected);
+        if expected == 1 {
+            assert_eq!(merged[0].timestamp.description, "Opening Talk");
+            assert_eq!(merged[0].timestamp.end_time, Some(ElapsedTime(930)));
+            assert_eq!(
+                merged[0].sources,
+                vec![TimeStampSource::Description, comment()]
+            );
+        }
+    }
+
+    #[test]
+    fn merge_keeps_other_videos() {
+        let v = vec![
+            entity("aqz-KE-bpKQ", 10, "Opening", TimeStampSource::Description),
+            entity("bqz-KE-bpKQ", 10, "Opening", comment()),
+            entity("aqz-KE-bpKQ", 10, "Opening", TimeStampSource::Description),
+        ];
+
+        let merged = TimeStampMergeService::default().merge(v);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].sources, vec![TimeStampSource::Description]);
+    }
+
+    #[test]
+    fn merge_replaces_parent() -> anyhow::Result<()> {
+        let section = entity(
+            "aqz-KE-bpKQ",
+            3600,
+            "Karaoke corner",
+            TimeStampSource::Description,
+        );
+        let duplicated = entity("aqz-KE-bpKQ", 3601, "Karaoke corner!", comment());
+        let child =
+            entity("aqz-KE-bpKQ", 3720, "Song", comment()).with_parent_id(Some(duplicated.id()?));
+
+        let v = TimeStampMergeService::default().merge(vec![section.clone(), duplicated, child]);
+        assert_eq!(v.len(), 2);
+        assert_eq!(v[1].parent_id, Some(section.id()?));
+        Ok(())
+    }
+}
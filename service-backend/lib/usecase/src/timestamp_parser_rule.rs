@@ -0,0 +1,266 @@
+use domains::value_objects::channel_id::ChannelId;
+use errors::{AppError, AppResult};
+use fancy_regex::Regex;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// `h:mm:ss` / `mm:ss`。全角数字・全角コロンも含む
+const COLON_NOTATION: &str = r"[0-9０-９]{0,2}[:：]*[0-9０-９]{1,2}[:：][0-9０-９]{1,2}";
+/// `1h02m03s` / `1時間2分3秒` / `12分34秒`。誤検出を避けるため分と秒は必須とする
+const UNIT_NOTATION: &str =
+    r"(?:[0-9０-９]{1,2}(?:h|時間|時))?[0-9０-９]{1,2}(?:m|分)[0-9０-９]{1,2}(?:s|秒)";
+/// 行頭の番号・箇条書き記号。`01.` / `1)` / `・` など
+const LIST_MARKER: &str = r"^(?:[0-9０-９]{1,3}[.)）]?|[・*▶►●♪]+)$";
+
+/// タイムスタンプ抽出のルール
+///
+/// チャンネルごとに表記の癖が異なるため、設定ファイルで上書きできるようにする。
+#[derive(Debug, Clone)]
+pub struct TimeStampParserRule {
+    /// 行内の時刻。前後が数字・コロンに続くものは時刻とみなさない
+    pub(crate) time_token: Regex,
+    /// 時刻の前に置かれた番号・箇条書き記号
+    pub(crate) list_marker: Regex,
+    /// 行から取り除く装飾
+    pub(crate) strip: Vec<Regex>,
+    /// 説明文の最小文字数。これより短いものは捨てる
+    pub(crate) min_description_length: usize,
+    /// 無視する行
+    pub(crate) ignore_lines: Vec<Regex>,
+}
+
+impl Default for TimeStampParserRule {
+    fn default() -> Self {
+        Self::try_from(TimeStampParserRuleConfig::default()).unwrap()
+    }
+}
+
+impl TimeStampParserRule {
+    pub(crate) fn is_ignored(&self, line: &str) -> bool {
+        self.ignore_lines
+            .iter()
+            .any(|r| r.is_match(line).unwrap_or(false))
+    }
+
+    pub(crate) fn strip<'a>(&self, line: &'a str) -> Cow<'a, str> {
+        let mut line = Cow::Borrowed(line);
+        for r in self.strip.iter() {
+            if let Cow::Owned(s) = r.replace_all(&line, "") {
+                line = Cow::Owned(s);
+            }
+        }
+        line
+    }
+}
+
+/// 設定ファイル上のルール。省略した項目は既定値（チャンネル別の場合は `[default]`）を使う
+///
+/// ```toml
+/// [default]
+/// min_description_length = 2
+///
+/// [channels.UC_x5XG1OV2P6uZZ5FSM9Ttw]
+/// strip_patterns = ['^start\s*', '🎤']
+/// ignore_lines = ['^-+$']
+///
+/// # チャンネル登録の parser_profile で指定する
+/// [profiles.karaoke]
+/// list_marker = '^(?:[0-9]{1,3}\.|🎤)$'
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimeStampParserRuleConfig {
+    /// 時刻表記の正規表現。一致した文字列は `ElapsedTime::from_notation` で解釈する
+    pub time_patterns: Option<Vec<String>>,
+    /// 時刻の前に置かれた番号・箇条書き記号の正規表現
+    pub list_marker: Option<String>,
+    /// 行から取り除く装飾の正規表現
+    pub strip_patterns: Option<Vec<String>>,
+    pub min_description_length: Option<usize>,
+    /// 無視する行の正規表現
+    pub ignore_lines: Option<Vec<String>>,
+}
+
+impl TimeStampParserRuleConfig {
+    /// 省略した項目を `base` で補う
+    fn or(self, base: &TimeStampParserRuleConfig) -> Self {
+        Self {
+            time_patterns: self.time_patterns.or(base.time_patterns.clone()),
+            list_marker: self.list_marker.or(base.list_marker.clone()),
+            strip_patterns: self.strip_patterns.or(base.strip_patterns.clone()),
+            min_description_length: self.min_description_length.or(base.min_description_length),
+            ignore_lines: self.ignore_lines.or(base.ignore_lines.clone()),
+        }
+    }
+}
+
+impl TryFrom<TimeStampParserRuleConfig> for TimeStampParserRule {
+    type Error = AppError;
+    fn try_from(config: TimeStampParserRuleConfig) -> AppResult<Self> {
+        let time_patterns = config
+            .time_patterns
+            .unwrap_or(vec![COLON_NOTATION.to_string(), UNIT_NOTATION.to_string()]);
+        if time_patterns.is_empty() {
+            return Err(AppError::InvalidInput(
+                "time_patterns must not be empty".to_string(),
+            ));
+        }
+        let time_token = regex(&format!(
+            r"(?<![0-9０-９:：])(?:{})(?![0-9０-９:：])",
+            time_patterns.join("|")
+        ))?;
+
+        Ok(Self {
+            time_token,
+            list_marker: regex(config.list_marker.as_deref().unwrap_or(LIST_MARKER))?,
+            strip: regexes(config.strip_patterns)?,
+            min_description_length: config.min_description_length.unwrap_or(1),
+            ignore_lines: regexes(config.ignore_lines)?,
+        })
+    }
+}
+
+fn regex(pattern: &str) -> AppResult<Regex> {
+    Regex::new(pattern)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid pattern {} : {}", pattern, e)))
+}
+
+fn regexes(patterns: Option<Vec<String>>) -> AppResult<Vec<Regex>> {
+    patterns
+        .unwrap_or_default()
+        .iter()
+        .map(|p| regex(p))
+        .collect()
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TimeStampParserRulesFile {
+    #[serde(default)]
+    default: TimeStampParserRuleConfig,
+    #[serde(default)]
+    channels: HashMap<String, TimeStampParserRuleConfig>,
+    #[serde(default)]
+    profiles: HashMap<String, TimeStampParserRuleConfig>,
+}
+
+/// チャンネルごとのタイムスタンプ抽出ルール
+#[derive(Debug, Clone, Default)]
+pub struct TimeStampParserRules {
+    default: TimeStampParserRule,
+    channels: HashMap<ChannelId, TimeStampParserRule>,
+    profiles: HashMap<String, TimeStampParserRule>,
+}
+
+impl TimeStampParserRules {
+    /// TOML 形式の設定ファイルを読み込む
+    pub fn load(path: impl AsRef<Path>) -> AppResult<Self> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path).map_err(|e| {
+            AppError::InvalidInput(format!("Unable to read {} : {}", path.display(), e))
+        })?;
+        Self::from_toml(&s)
+    }
+
+    pub fn from_toml(s: &str) -> AppResult<Self> {
+        let file: TimeStampParserRulesFile =
+            toml::from_str(s).map_err(|e| AppError::InvalidInput(e.to_string()))?;
+
+        let channels = file
+            .channels
+            .into_iter()
+            .map(|(id, config)| {
+                let rule = TimeStampParserRule::try_from(config.or(&file.default))?;
+                Ok((ChannelId::new(&id)?, rule))
+            })
+            .collect::<AppResult<HashMap<_, _>>>()?;
+
+        let profiles = file
+            .profiles
+            .into_iter()
+            .map(|(name, config)| {
+                Ok((
+                    name,
+                    TimeStampParserRule::try_from(config.or(&file.default))?,
+                ))
+            })
+            .collect::<AppResult<HashMap<_, _>>>()?;
+
+        Ok(Self {
+            default: TimeStampParserRule::try_from(file.default)?,
+            channels,
+            profiles,
+        })
+    }
+
+    /// チャンネルにプロファイルのルールを割り当てる
+    /// `[channels.<id>]` でチャンネル個別のルールがある場合はそちらを優先する
+    pub fn assign_profile(&mut self, channel_id: &ChannelId, profile: &str) -> AppResult<()> {
+        let rule = self.profiles.get(profile).ok_or_else(|| {
+            AppError::InvalidInput(format!("Unknown parser profile : {}", profile))
+        })?;
+        self.channels
+            .entry(channel_id.clone())
+            .or_insert_with(|| rule.clone());
+        Ok(())
+    }
+
+    /// チャンネルのルール。設定が無ければ既定のルール
+    pub fn rule(&self, channel_id: &ChannelId) -> &TimeStampParserRule {
+        self.channels.get(channel_id).unwrap_or(&self.default)
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn load_channel_rules() -> anyhow::Result<()> {
+        let rules = TimeStampParserRules::from_toml(
+            r#"
+[default]
+min_description_length = 2
+
+[channels.UC_x5XG1OV2P6uZZ5FSM9Ttw]
+strip_patterns = ['^start\s*']
+"#,
+        )?;
+
+        let channel = ChannelId::new("UC_x5XG1OV2P6uZZ5FSM9Ttw")?;
+        let rule = rules.rule(&channel);
+        assert_eq!(rule.min_description_length, 2);
+        assert_eq!(rule.strip("start 00:00 Opening"), "00:00 Opening");
+
+        let other = rules.rule(&ChannelId::new("UC_1234567890abcdefghijk")?);
+        assert_eq!(other.min_description_length, 2);
+        assert!(other.strip.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn assign_profile() -> anyhow::Result<()> {
+        let mut rules = TimeStampParserRules::from_toml(
+            r#"
+[profiles.karaoke]
+min_description_length = 3
+"#,
+        )?;
+
+        let channel = ChannelId::new("UC_x5XG1OV2P6uZZ5FSM9Ttw")?;
+        assert_eq!(rules.rule(&channel).min_description_length, 1);
+        rules.assign_profile(&channel, "karaoke")?;
+        assert_eq!(rules.rule(&channel).min_description_length, 3);
+        assert!(rules.assign_profile(&channel, "unknown").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reject_invalid_rules() {
+        assert!(TimeStampParserRules::from_toml("[default]\nunknown = 1").is_err());
+        assert!(TimeStampParserRules::from_toml("[default]\nignore_lines = ['(']").is_err());
+        assert!(TimeStampParserRules::from_toml("[channels.invalid]").is_err());
+    }
+}
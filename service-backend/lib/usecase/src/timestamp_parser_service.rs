@@ -1,53 +1,473 @@
-use domains::entities::{video::VideoEntity, video_timestamp::VideoTimestampEntity};
+use crate::timestamp_parser_rule::{TimeStampParserRule, TimeStampParserRules};
+use domains::entities::{
+    video::VideoEntity, video_comment::VideoCommentEntity, video_timestamp::VideoTimestampEntity,
+};
 use domains::value_objects::{
-    elapsed_time::ElapsedTime, timestamp::TimeStamp, timestamp_description::TimeStampDescription,
-    video_description::VideoDescription,
+    confidence::Confidence, elapsed_time::ElapsedTime, timestamp::TimeStamp,
+    timestamp_description::TimeStampDescription, timestamp_id::TimestampId,
+    timestamp_source::TimeStampSource, video_id::VideoId,
 };
 use errors::AppResult;
 use fancy_regex::Regex;
+use std::sync::LazyLock;
+
+/// 範囲指定の区切り。`00:10 - 05:20` / `00:10〜05:20` など
+static RANGE_SEPARATOR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*[-~〜～–—]\s*$").unwrap());
+/// 直前より前の時刻に戻った場合の減点
+const OUT_OF_ORDER_PENALTY: u8 = 40;
+/// 直前と同じ時刻の場合の減点
+const SAME_TIME_PENALTY: u8 = 20;
+/// 直前から間が空きすぎている場合の減点
+const LONG_GAP_PENALTY: u8 = 20;
+/// 間が空きすぎているとみなす秒数
+const LONG_GAP_SECONDS: u64 = 3 * 60 * 60;
+/// 説明文が1文字しかない場合の減点
+const SHORT_DESCRIPTION_PENALTY: u8 = 20;
+/// 説明文の大半が記号の場合の減点
+const NOISY_DESCRIPTION_PENALTY: u8 = 30;
+/// 説明文に文字・数字が無い場合の減点
+const NO_TEXT_PENALTY: u8 = 60;
+
+/// 時刻と説明文の間に置かれる区切り記号
+const SEPARATORS: &str = "-–—~〜～:：|｜・";
+/// 見出しを表す行頭の記号
+const HEADING_MARKERS: &str = "■□▼▽◆◇★☆";
+/// 入れ子を表す罫線
+const TREE_MARKERS: &str = "└├┗┣│┃";
+
+/// YouTube の動画リンク。`youtu.be/<id>?t=754` や `watch?v=<id>&t=12m34s` など
+static YOUTUBE_LINK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"https?://(?:www\.|m\.)?(?:youtu\.be/|youtube\.com/(?:watch\?\S*?v=|live/|shorts/))([A-Za-z0-9_-]{11})\S*").unwrap()
+});
+/// リンク中の時刻パラメータ
+static TIME_PARAM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[?&#]t=([0-9A-Za-z]+)").unwrap());
 
 #[derive(Default)]
-pub struct TimeStampParserService {}
+pub struct TimeStampParserService {
+    rules: TimeStampParserRules,
+}
 
 impl TimeStampParserService {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
     }
 
+    /// チャンネルごとの抽出ルールを使う
+    pub fn with_rules(mut self, rules: TimeStampParserRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// 動画のチャンネルに合ったルールで説明文からタイムスタンプを抽出する
     pub fn parse_video(&self, video: &VideoEntity) -> AppResult<Vec<VideoTimestampEntity>> {
+        Self::parse_text(
+            self.rules.rule(&video.channel.id),
+            video,
+            &video.description,
+        )
+    }
+
+    /// 動画に付いたコメントからタイムスタンプを抽出する
+    /// 対象動画以外へのコメントは無視する
+    pub fn parse_comments(
+        &self,
+        video: &VideoEntity,
+        comments: &[VideoCommentEntity],
+    ) -> AppResult<Vec<VideoTimestampEntity>> {
         let mut result = vec![];
 
-        let tss = Self::parse(&video.description)?;
-        for ts in tss {
-            let v = VideoTimestampEntity::new(video.id.clone(), ts);
-            result.push(v);
+        for c in comments.iter().filter(|c| c.video_id == video.id) {
+            let source = TimeStampSource::Comment {
+                comment_id: c.id.clone(),
+                author: c.author.clone(),
+            };
+            for v in Self::parse_text(self.rules.rule(&video.channel.id), video, &c.text)? {
+                result.push(v.with_source(source.clone()));
+            }
         }
 
         Ok(result)
     }
 
-    fn parse(description: &VideoDescription) -> AppResult<Vec<TimeStamp>> {
-        let re = Regex::new(r"([0-9]{0,2}:*[0-9]{1,2}:[0-9]{1,2})\s+([\s\S]*?)(?=\n{2}|[0-9]{0,2}:*[0-9]{1,2}:[0-9]{1,2}|$)").unwrap();
-        let ret: Vec<TimeStamp> = re
-            .captures_iter(description)
-            .filter(|caps| caps.is_ok())
-            .filter_map(|caps| {
-                let caps = caps.ok()?;
-                let time = caps.get(1)?.as_str().trim();
-                let text = caps.get(2)?.as_str().trim();
-                let time = ElapsedTime::from_hhmmss(time).ok()?;
-                let d = TimeStampDescription::new(text).ok()?;
-                Some(TimeStamp::new(time, d).unwrap())
+    /// 時刻表記と時刻付きリンクの両方からタイムスタンプを抽出する
+    /// 別の動画へのリンクは、リンク先の動画のタイムスタンプとする
+    fn parse_text(
+        rule: &TimeStampParserRule,
+        video: &VideoEntity,
+        text: &str,
+    ) -> AppResult<Vec<VideoTimestampEntity>> {
+        // リンク内の `t=12m34s` を時刻表記として拾わないよう、リンクを除いてから時刻表記を解析する
+        // 時刻付きリンクの行はそれ自体が1件なので、前の説明文の続きにならないよう空行にする
+        let mut links = vec![];
+        let mut lines = vec![];
+        for line in text.lines() {
+            let found = Self::parse_link_line(line)?;
+            if found.is_empty() {
+                lines.push(YOUTUBE_LINK.replace_all(line, "").into_owned());
+            } else {
+                links.extend(found);
+                lines.push(String::new());
+            }
+        }
+        let text = lines.join("\n");
+
+        let outline = Self::parse_outline(rule, &text)?;
+        let ids = outline
+            .iter()
+            .map(|(ts, _)| TimestampId::new(&video.id, ts))
+            .collect::<AppResult<Vec<_>>>()?;
+
+        let scores = Self::confidences(outline.iter().map(|(ts, _)| ts));
+
+        let result = outline
+            .into_iter()
+            .zip(scores)
+            .map(|((ts, parent), score)| {
+                VideoTimestampEntity::new(video.id.clone(), ts)
+                    .with_parent_id(parent.map(|p| ids[p].clone()))
+                    .with_confidence(Some(score))
             })
+            .chain(links.into_iter().map(|(id, ts)| {
+                let score = Self::confidence(None, None, &ts);
+                VideoTimestampEntity::new(id, ts).with_confidence(Some(score))
+            }))
+            .filter(|e| e.video_id != video.id || Self::within_video(video, &e.timestamp))
             .collect();
+        Ok(result)
+    }
+
+    /// 記載順のタイムスタンプそれぞれの確からしさ
+    fn confidences<'a>(timestamps: impl Iterator<Item = &'a TimeStamp>) -> Vec<Confidence> {
+        let mut latest: Option<ElapsedTime> = None;
+        let mut prev: Option<ElapsedTime> = None;
+        let mut ret = vec![];
+        for ts in timestamps {
+            ret.push(Self::confidence(prev, latest, ts));
+            prev = Some(ts.elapsed_time);
+            latest = latest.max(Some(ts.elapsed_time));
+        }
+        ret
+    }
+
+    /// 時刻の並び（直前・それまでの最大）と説明文から確からしさを求める
+    fn confidence(
+        prev: Option<ElapsedTime>,
+        latest: Option<ElapsedTime>,
+        ts: &TimeStamp,
+    ) -> Confidence {
+        let mut penalty = 0u8;
+        let t = ts.elapsed_time.value();
+
+        if latest.is_some_and(|l| t < l.value()) {
+            penalty = penalty.saturating_add(OUT_OF_ORDER_PENALTY);
+        }
+        if let Some(prev) = prev {
+            if t == prev.value() {
+                penalty = penalty.saturating_add(SAME_TIME_PENALTY);
+            } else if t > prev.value() + LONG_GAP_SECONDS {
+                penalty = penalty.saturating_add(LONG_GAP_PENALTY);
+            }
+        }
+
+        let chars = ts
+            .description
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<Vec<_>>();
+        let text = chars.iter().filter(|c| c.is_alphanumeric()).count();
+        if text == 0 {
+            penalty = penalty.saturating_add(NO_TEXT_PENALTY);
+        } else if text * 2 < chars.len() {
+            penalty = penalty.saturating_add(NOISY_DESCRIPTION_PENALTY);
+        }
+        if chars.len() <= 1 {
+            penalty = penalty.saturating_add(SHORT_DESCRIPTION_PENALTY);
+        }
+
+        Confidence(Confidence::MAX.saturating_sub(penalty))
+    }
+
+    /// 動画の長さが分かる場合、動画の終端を超えるタイムスタンプは誤検出として除外する
+    fn within_video(video: &VideoEntity, ts: &TimeStamp) -> bool {
+        match video.duration {
+            Some(d) if ts.elapsed_time.value() > d.value() => {
+                tracing::debug!(
+                    "Skip timestamp past the end of video {} : {}",
+                    video.id,
+                    ts.elapsed_time
+                );
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// 時刻付きリンクを含む行から、リンク先の動画IDとタイムスタンプを抽出する
+    /// 説明文はリンクを除いた行の残りとする
+    fn parse_link_line(line: &str) -> AppResult<Vec<(VideoId, TimeStamp)>> {
+        let rest = YOUTUBE_LINK.replace_all(line, "");
+        let rest = rest.trim_matches(|c: char| c.is_whitespace() || "-–—:|・".contains(c));
+        if rest.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut ret = vec![];
+        for caps in YOUTUBE_LINK.captures_iter(line).filter_map(|c| c.ok()) {
+            let (Some(url), Some(id)) = (caps.get(0), caps.get(1)) else {
+                continue;
+            };
+            let Some(t) = TIME_PARAM
+                .captures(url.as_str())
+                .ok()
+                .flatten()
+                .and_then(|c| c.get(1))
+            else {
+                continue;
+            };
+            let (Ok(id), Ok(time)) = (
+                VideoId::new(id.as_str()),
+                Self::parse_time_param(t.as_str()),
+            ) else {
+                continue;
+            };
+            ret.push((id, TimeStamp::new(time, TimeStampDescription::new(rest)?)?));
+        }
         Ok(ret)
     }
+
+    /// `t=754` / `t=754s` / `t=12m34s` / `t=1h2m3s`
+    fn parse_time_param(t: &str) -> AppResult<ElapsedTime> {
+        let seconds = t.strip_suffix('s').unwrap_or(t);
+        match seconds.parse::<u64>() {
+            Ok(seconds) => ElapsedTime::new(seconds),
+            Err(_) => ElapsedTime::from_notation(t),
+        }
+    }
+
+    /// 行単位でタイムスタンプを抽出し、見出しにあたる親タイムスタンプの位置とともに返す
+    ///
+    /// - `00:10 タイトル` : 時刻が先頭の行。1行に複数の時刻があればそれぞれを1件とする
+    /// - `タイトル 00:10` : 時刻が末尾の行
+    /// - `00:10 - 05:20 タイトル` : 範囲指定。終了時間も設定する
+    ///
+    /// 時刻が先頭の行に続く時刻の無い行は、空行までを説明文の続きとする。
+    /// インデントが深い行、罫線（`└` など）で始まる行は直前の浅い行の子とする。
+    /// 見出し記号（`■` など）は取り除くだけで、同じインデントの後続の行は子にしない。
+    fn parse_outline(
+        rule: &TimeStampParserRule,
+        description: &str,
+    ) -> AppResult<Vec<(TimeStamp, Option<usize>)>> {
+        let mut ret = vec![];
+        let mut current: Option<PendingTimeStamp> = None;
+
+        for raw in description.lines() {
+            if rule.is_ignored(raw) {
+                continue;
+            }
+            let raw = rule.strip(raw);
+            if raw.trim().is_empty() {
+                Self::close(rule, &mut current, &mut ret)?;
+                continue;
+            }
+
+            let (level, line) = Self::outline_level(&raw);
+            let tokens = Self::time_tokens(rule, line);
+            let (Some(first), Some(last)) = (tokens.first(), tokens.last()) else {
+                if let Some(p) = current.as_mut() {
+                    if !p.description.is_empty() {
+                        p.description.push('\n');
+                    }
+                    p.description.push_str(raw.trim());
+                }
+                continue;
+            };
+            Self::close(rule, &mut current, &mut ret)?;
+
+            let prefix = trim_separators(&line[..first.start]);
+            let prefix = if rule.list_marker.is_match(prefix).unwrap_or(false) {
+                ""
+            } else {
+                prefix
+            };
+            let suffix = trim_separators(&line[last.end..]);
+
+            // 時刻が末尾の行
+            if tokens.len() == 1 && suffix.is_empty() && !prefix.is_empty() {
+                current = Some(PendingTimeStamp::new(first, level, prefix.to_string()));
+                Self::close(rule, &mut current, &mut ret)?;
+                continue;
+            }
+
+            for (i, t) in tokens.iter().enumerate() {
+                let next = tokens.get(i + 1).map(|n| n.start).unwrap_or(line.len());
+                let text = trim_separators(&line[t.end..next]);
+                let text = if i == 0 && !prefix.is_empty() {
+                    format!("{} {}", prefix, text).trim().to_string()
+                } else {
+                    text.to_string()
+                };
+                Self::close(rule, &mut current, &mut ret)?;
+                current = Some(PendingTimeStamp::new(t, level, text));
+            }
+        }
+        Self::close(rule, &mut current, &mut ret)?;
+
+        // 自分より浅い直前の項目を親とする
+        let mut stack: Vec<(usize, usize)> = vec![];
+        let mut outline = Vec::with_capacity(ret.len());
+        for (i, (ts, level)) in ret.into_iter().enumerate() {
+            while stack.last().is_some_and(|(l, _)| *l >= level) {
+                stack.pop();
+            }
+            outline.push((ts, stack.last().map(|(_, p)| *p)));
+            stack.push((level, i));
+        }
+
+        Ok(outline)
+    }
+
+    /// 行の階層の深さと、見出し記号・罫線を除いた行を返す。値が小さいほど外側
+    fn outline_level(line: &str) -> (usize, &str) {
+        let body = line.trim_start();
+        let indent: usize = line[..line.len() - body.len()]
+            .chars()
+            .map(|c| match c {
+                '\t' => 4,
+                '\u{3000}' => 2,
+                _ => 1,
+            })
+            .sum();
+
+        let tree = body.trim_start_matches(|c: char| TREE_MARKERS.contains(c));
+        let indent = if tree.len() != body.len() {
+            indent + 2
+        } else {
+            indent
+        };
+
+        let heading = tree.trim_start_matches(|c: char| HEADING_MARKERS.contains(c));
+        (indent, heading.trim())
+    }
+
+    /// 1行に含まれる時刻を抽出する。`00:10 - 05:20` のような範囲は1つにまとめる
+    fn time_tokens(rule: &TimeStampParserRule, line: &str) -> Vec<TimeToken> {
+        let found: Vec<TimeToken> = rule
+            .time_token
+            .find_iter(line)
+            .filter_map(|m| m.ok())
+            .filter_map(|m| {
+                Some(TimeToken {
+                    start: m.start(),
+                    end: m.end(),
+                    time: ElapsedTime::from_notation(m.as_str()).ok()?,
+                    end_time: None,
+                })
+            })
+            .collect();
+
+        let mut tokens: Vec<TimeToken> = Vec::with_capacity(found.len());
+        for t in found {
+            if let Some(prev) = tokens.last_mut()
+                && prev.end_time.is_none()
+                && RANGE_SEPARATOR
+                    .is_match(&line[prev.end..t.start])
+                    .unwrap_or(false)
+            {
+                prev.end = t.end;
+                prev.end_time = Some(t.time);
+                continue;
+            }
+            tokens.push(t);
+        }
+        tokens
+    }
+
+    /// 説明文が空、またはルールの最小文字数に満たないものは捨てる
+    fn close(
+        rule: &TimeStampParserRule,
+        current: &mut Option<PendingTimeStamp>,
+        ret: &mut Vec<(TimeStamp, usize)>,
+    ) -> AppResult<()> {
+        let Some(p) = current.take() else {
+            return Ok(());
+        };
+        if p.description.is_empty() || p.description.chars().count() < rule.min_description_length {
+            return Ok(());
+        }
+
+        let mut ts = TimeStamp::new(p.time, TimeStampDescription::new(&p.description)?)?;
+        if let Some(end) = p.end_time.filter(|e| *e > p.time) {
+            ts = ts.with_end_time(end);
+        }
+        ret.push((ts, p.level));
+        Ok(())
+    }
+}
+
+/// 行内の時刻の位置。範囲指定の場合は終了時刻を持つ
+struct TimeToken {
+    start: usize,
+    end: usize,
+    time: ElapsedTime,
+    end_time: Option<ElapsedTime>,
+}
+
+/// 説明文の続きを待っているタイムスタンプ
+struct PendingTimeStamp {
+    time: ElapsedTime,
+    end_time: Option<ElapsedTime>,
+    level: usize,
+    description: String,
+}
+
+impl PendingTimeStamp {
+    fn new(token: &TimeToken, level: usize, description: String) -> Self {
+        Self {
+            time: token.time,
+            end_time: token.end_time,
+            level,
+            description,
+        }
+    }
+}
+
+/// 時刻と説明文の間の区切り記号・空白を取り除く
+fn trim_separators(s: &str) -> &str {
+    s.trim_matches(|c: char| c.is_whitespace() || SEPARATORS.contains(c))
 }
 
 #[cfg(test)]
 mod unit_tests {
     use super::*;
+    use domains::entities::channel::ChannelEntity;
+    use domains::entities::video::VideoEntityBuilder;
+    use domains::value_objects::channel_name::ChannelName;
+    use domains::value_objects::comment_author::CommentAuthor;
+    use domains::value_objects::comment_id::CommentId;
+    use domains::value_objects::comment_text::CommentText;
     use domains::value_objects::video_description::VideoDescription;
+    use domains::value_objects::video_duration::VideoDuration;
+    use domains::value_objects::video_title::VideoTitle;
+    use std::fs;
+    use std::path::Path;
+
+    /// 既定のルールで動画の説明文を解析する
+    fn parse_description(description: &str) -> anyhow::Result<Vec<VideoTimestampEntity>> {
+        let c = ChannelEntity::with_random_id(ChannelName::new("channel")?);
+        let v = VideoEntityBuilder::new(VideoId::new("abcdefghijk")?, VideoTitle::new("title")?, c)
+            .with_description(VideoDescription::new(description)?)
+            .construct()?;
+        Ok(TimeStampParserService::new().parse_video(&v)?)
+    }
+
+    fn parse(description: &str) -> anyhow::Result<Vec<TimeStamp>> {
+        Ok(parse_description(description)?
+            .into_iter()
+            .map(|t| t.timestamp)
+            .collect())
+    }
 
     #[rstest::rstest]
     #[test]
@@ -64,10 +484,306 @@ mod unit_tests {
     )]
     #[case::single_line_multi_item("01:10 test Description. 01:12 test Description.", 2)]
     fn valid_parse(#[case] description: &str, #[case] expected_num: usize) {
-        let d = VideoDescription::new(description).unwrap();
-
-        let v = TimeStampParserService::parse(&d);
+        let v = parse(description);
         assert!(v.is_ok());
         assert_eq!(expected_num, v.unwrap().len());
     }
+
+    #[rstest::rstest]
+    #[test]
+    #[case::full_width_colon("１２：３４ 曲名", 754)]
+    #[case::full_width_hhmmss("１：０２：０３ 曲名", 3723)]
+    #[case::japanese_hms("1時間2分3秒 曲名", 3723)]
+    #[case::japanese_ms("12分34秒 曲名", 754)]
+    #[case::unit_hms("1h02m03s 曲名", 3723)]
+    #[case::unit_ms("2m03s 曲名", 123)]
+    fn parse_time_notation(#[case] description: &str, #[case] expected: u64) {
+        let v = parse(description).unwrap();
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].elapsed_time, expected);
+        assert_eq!(v[0].description, "曲名");
+    }
+
+    #[test]
+    fn parse_mixed_notation() {
+        let v = parse("00:10 開始\n１２分３４秒 曲名\n1h02m03s 終了").unwrap();
+        let times = v.iter().map(|t| *t.elapsed_time).collect::<Vec<_>>();
+        assert_eq!(times, vec![10, 754, 3723]);
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case::short_link("https://youtu.be/abcdefghijk?t=754 曲名", 754)]
+    #[case::short_link_seconds("曲名 https://youtu.be/abcdefghijk?t=754s", 754)]
+    #[case::watch_link("https://www.youtube.com/watch?v=abcdefghijk&t=12m34s 曲名", 754)]
+    #[case::watch_link_param_first("曲名 - https://youtube.com/watch?t=1h2m3s&v=abcdefghijk", 3723)]
+    #[case::live_link("https://www.youtube.com/live/abcdefghijk?si=xyz&t=754 曲名", 754)]
+    fn parse_link(#[case] text: &str, #[case] expected: u64) {
+        let links = TimeStampParserService::parse_link_line(text).unwrap();
+        assert_eq!(links.len(), 1);
+        let (id, ts) = &links[0];
+        assert_eq!(id.as_str(), "abcdefghijk");
+        assert_eq!(ts.elapsed_time, expected);
+        assert_eq!(ts.description, "曲名");
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case::without_time("https://youtu.be/abcdefghijk 曲名")]
+    #[case::without_description("https://youtu.be/abcdefghijk?t=754")]
+    #[case::other_site("https://example.com/abcdefghijk?t=754 曲名")]
+    fn parse_link_ignored(#[case] text: &str) {
+        assert!(
+            TimeStampParserService::parse_link_line(text)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn parse_video_links() -> anyhow::Result<()> {
+        let c = ChannelEntity::with_random_id(ChannelName::new("channel")?);
+        let v = VideoEntity::with_random_id(VideoTitle::new("title")?, c).construct()?;
+        let description = format!(
+            "00:10 開始\nhttps://youtu.be/{}?t=754 曲名\n前回 https://youtu.be/zyxwvutsrqp?t=120 続き",
+            v.id
+        );
+        let v = VideoEntity {
+            description: VideoDescription::new(&description)?,
+            ..v
+        };
+
+        let tss = TimeStampParserService::new().parse_video(&v)?;
+        let tss = tss
+            .iter()
+            .map(|t| (t.video_id.as_str(), *t.timestamp.elapsed_time))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tss,
+            vec![
+                (v.id.as_str(), 10),
+                (v.id.as_str(), 754),
+                ("zyxwvutsrqp", 120)
+            ]
+        );
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case::range("00:10 - 05:20 Opening", 10, Some(320), "Opening")]
+    #[case::range_without_space("00:10〜05:20 Opening", 10, Some(320), "Opening")]
+    #[case::reversed_range("05:20 - 00:10 Opening", 320, None, "Opening")]
+    #[case::trailing_time("Opening talk 00:10", 10, None, "Opening talk")]
+    #[case::trailing_time_separator("Opening talk - 00:10", 10, None, "Opening talk")]
+    #[case::trailing_range("Opening 00:10 - 05:20", 10, Some(320), "Opening")]
+    #[case::list_marker("01. 00:10 Opening", 10, None, "Opening")]
+    #[case::time_only_line("00:10\nOpening", 10, None, "Opening")]
+    fn parse_line_forms(
+        #[case] description: &str,
+        #[case] start: u64,
+        #[case] end: Option<u64>,
+        #[case] text: &str,
+    ) {
+        let v = parse(description).unwrap();
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].elapsed_time, start);
+        assert_eq!(v[0].end_time.map(u64::from), end);
+        assert_eq!(v[0].description, text);
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case::indent(
+        "■Karaoke corner 1:00:00\n  1:02:00 Song A\n  1:06:00 Song B\n■Game: X 1:30:00\n└ 1:31:00 Chapter 1\n1:59:00 Ending",
+        &[(3600, None), (3720, Some(3600)), (3960, Some(3600)), (5400, None), (5460, Some(5400)), (7140, None)]
+    )]
+    #[case::heading_without_indent(
+        "■Opening 00:00\n00:10 Talk\n05:00 Song A",
+        &[(0, None), (10, None), (300, None)]
+    )]
+    #[case::nested_heading(
+        "■Part 1 00:00\n  ■Songs 01:00\n    02:00 Song A\n  10:00 Talk",
+        &[(0, None), (60, Some(0)), (120, Some(60)), (600, Some(0))]
+    )]
+    fn parse_outline(#[case] description: &str, #[case] expected: &[(u64, Option<u64>)]) {
+        let v = parse_description(description).unwrap();
+        // 親は ID の代わりに親の経過時間で表す
+        let parents = v
+            .iter()
+            .map(|t| {
+                let parent = t.parent_id.as_ref().map(|p| {
+                    v.iter()
+                        .find(|c| c.id().ok().as_ref() == Some(p))
+                        .map(|c| *c.timestamp.elapsed_time)
+                        .unwrap()
+                });
+                (*t.timestamp.elapsed_time, parent)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(parents, expected);
+    }
+
+    #[test]
+    fn parse_video_with_channel_rules() -> anyhow::Result<()> {
+        let c = ChannelEntity::with_random_id(ChannelName::new("channel")?);
+        let rules = TimeStampParserRules::from_toml(&format!(
+            r#"
+[channels."{}"]
+strip_patterns = ['🎤', '^\s*start\s*']
+min_description_length = 2
+ignore_lines = ['^-+$']
+"#,
+            c.id
+        ))?;
+        let v = VideoEntity::with_random_id(VideoTitle::new("title")?, c)
+            .with_description(VideoDescription::new(
+                "start 00:00 Opening\n🎤 05:00 Song A\n-----\n06:00 a\n07:00 Ending",
+            )?)
+            .construct()?;
+
+        let tss = TimeStampParserService::new()
+            .with_rules(rules)
+            .parse_video(&v)?;
+        let tss = tss
+            .iter()
+            .map(|t| (*t.timestamp.elapsed_time, t.timestamp.description.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(tss, vec![(0, "Opening"), (300, "Song A"), (420, "Ending")]);
+
+        // ルールの無いチャンネルは既定のルール
+        let other = ChannelEntity::with_random_id(ChannelName::new("other")?);
+        let v = VideoEntity {
+            channel: other,
+            ..v
+        };
+        assert_eq!(TimeStampParserService::new().parse_video(&v)?.len(), 4);
+        Ok(())
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case::in_order("00:10 Opening\n05:00 Song A\n10:00 Song B", &[100, 100, 100])]
+    #[case::out_of_order("00:10 Opening\n05:00 Song A\n01:00 Song B", &[100, 100, 60])]
+    #[case::same_time("00:10 Opening\n00:10 Song A", &[100, 80])]
+    #[case::long_gap("00:10 Opening\n4:00:00 Song A", &[100, 80])]
+    #[case::noisy("00:10 Opening\n05:00 ★☆★☆a", &[100, 70])]
+    #[case::single_char("00:10 Opening\n05:00 a", &[100, 80])]
+    fn confidence_scores(#[case] description: &str, #[case] expected: &[u8]) {
+        let scores = parse_description(description)
+            .unwrap()
+            .iter()
+            .filter_map(|t| t.confidence.map(|c| c.value()))
+            .collect::<Vec<_>>();
+        assert_eq!(scores, expected);
+    }
+
+    /// testdata/timestamp_parser の各 `.txt` を解析し、同名の `.golden` と比較する
+    /// `UPDATE_GOLDEN=1` を付けて実行すると `.golden` を更新する
+    #[test]
+    fn golden_corpus() -> anyhow::Result<()> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/timestamp_parser");
+        let mut inputs = fs::read_dir(&dir)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "txt"))
+            .collect::<Vec<_>>();
+        inputs.sort();
+        assert!(!inputs.is_empty());
+
+        let c = ChannelEntity::with_random_id(ChannelName::new("channel")?);
+        for input in inputs {
+            let v = VideoEntityBuilder::new(
+                VideoId::new("goldenvideo")?,
+                VideoTitle::new("title")?,
+                c.clone(),
+            )
+            .with_description(VideoDescription::new(&fs::read_to_string(&input)?)?)
+            .construct()?;
+
+            let tss = TimeStampParserService::new().parse_video(&v)?;
+            // 親は ID の代わりに親の経過時間で表す
+            let parent = |t: &VideoTimestampEntity| {
+                t.parent_id
+                    .as_ref()
+                    .and_then(|p| tss.iter().find(|c| c.id().ok().as_ref() == Some(p)))
+                    .map(|p| p.timestamp.elapsed_time.to_string())
+                    .unwrap_or("-".to_string())
+            };
+            let actual = tss
+                .iter()
+                .map(|t| {
+                    format!(
+                        "{}\t{}\t{}\t{}\t{}\n",
+                        t.video_id,
+                        t.timestamp.elapsed_time,
+                        t.timestamp
+                            .end_time
+                            .map(|e| e.to_string())
+                            .unwrap_or("-".to_string()),
+                        parent(t),
+                        t.timestamp.description.replace('\n', "\\n")
+                    )
+                })
+                .collect::<String>();
+
+            let golden = input.with_extension("golden");
+            if std::env::var("UPDATE_GOLDEN").is_ok() {
+                fs::write(&golden, &actual)?;
+            }
+            assert_eq!(fs::read_to_string(&golden)?, actual, "{}", input.display());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn parse_video_rejects_past_end() -> anyhow::Result<()> {
+        let c = ChannelEntity::with_random_id(ChannelName::new("channel")?);
+        let v = VideoEntity::with_random_id(VideoTitle::new("title")?, c)
+            .with_description(VideoDescription::new(
+                "00:10 opening\n09:59 ending\n1:00:00 typo",
+            )?)
+            .with_duration(VideoDuration::new(600)?)
+            .construct()?;
+
+        let tss = TimeStampParserService::new().parse_video(&v)?;
+        assert_eq!(tss.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_comments_records_source() -> anyhow::Result<()> {
+        let c = ChannelEntity::with_random_id(ChannelName::new("channel")?);
+        let v = VideoEntity::with_random_id(VideoTitle::new("title")?, c.clone()).construct()?;
+        let other = VideoEntity::with_random_id(VideoTitle::new("other")?, c).construct()?;
+
+        let comments = vec![
+            VideoCommentEntity::new(
+                CommentId::new("comment-1")?,
+                v.id.clone(),
+                CommentAuthor::new("@author")?,
+                CommentText::new("00:10 opening\n12:34 song")?,
+            ),
+            VideoCommentEntity::new(
+                CommentId::new("comment-2")?,
+                other.id.clone(),
+                CommentAuthor::new("@author")?,
+                CommentText::new("00:10 other video")?,
+            ),
+        ];
+
+        let tss = TimeStampParserService::new().parse_comments(&v, &comments)?;
+        assert_eq!(tss.len(), 2);
+        for ts in tss {
+            assert_eq!(ts.video_id, v.id);
+            assert_eq!(
+                ts.sources,
+                vec![TimeStampSource::Comment {
+                    comment_id: CommentId::new("comment-1")?,
+                    author: CommentAuthor::new("@author")?,
+                }]
+            );
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,181 @@
+use domains::entities::video::VideoEntity;
+use domains::entities::video_timestamp::VideoTimestampEntity;
+use domains::repositories::internal_timestamp_repository::InternalVideoTimeStampRepository;
+use domains::value_objects::timestamp_id::TimestampId;
+use errors::AppResult;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// 保存済みのタイムスタンプを現在の形式で保存し直す
+///
+/// IDの作り方や検索用の項目が変わった後に実行する。
+/// 古いIDのドキュメントは動画ごとに削除し、見出しのIDも新しいIDに付け替える。
+pub struct TimeStampReindexService<T>
+where
+    T: InternalVideoTimeStampRepository,
+{
+    timestamp_repository: Arc<T>,
+}
+
+impl<T> TimeStampReindexService<T>
+where
+    T: InternalVideoTimeStampRepository,
+{
+    pub fn new(timestamp_repository: Arc<T>) -> Self {
+        Self {
+            timestamp_repository,
+        }
+    }
+
+    /// 動画のタイムスタンプを保存し直し、その件数を返す
+    pub async fn reindex(&self, video: &VideoEntity) -> AppResult<usize> {
+        let tss = self
+            .timestamp_repository
+            .get_video_timestamp_entities_by_video_id(&video.id)
+            .await?;
+        if tss.is_empty() {
+            return Ok(0);
+        }
+        let tss = relink_parents(tss)?;
+
+        self.timestamp_repository
+            .delete_video_timestamp_entity_by_video_id(&video.id)
+            .await?;
+        self.timestamp_repository
+            .add_video_timestamp_entities(video, &tss)
+            .await?;
+        Ok(tss.len())
+    }
+}
+
+/// 見出しのIDを新しいIDに付け替える
+///
+/// 新しいIDに一致しないものは、以前の形式のIDで一致するタイムスタンプに対応付ける。
+/// それも無い場合は、IDに含まれる経過時間が同じタイムスタンプのうち、登録順で直前のものに対応付ける。
+/// 入れ子の見出しは先頭の項目と経過時間が同じことが多いため、最も内側の見出しを選ぶ。
+/// 対応するものが無い場合は見出しの下から外す。
+fn relink_parents(tss: Vec<VideoTimestampEntity>) -> AppResult<Vec<VideoTimestampEntity>> {
+    let ids = tss
+        .iter()
+        .map(|t| t.id())
+        .collect::<AppResult<Vec<TimestampId>>>()?;
+    let known = ids.iter().cloned().collect::<HashSet<_>>();
+    let legacy = tss
+        .iter()
+        .zip(ids.iter())
+        .flat_map(|(t, id)| {
+            TimestampId::legacy(&t.video_id, &t.timestamp)
+                .into_iter()
+                .map(move |l| (l, id.clone()))
+        })
+        .collect::<HashMap<_, _>>();
+    let elapsed = tss
+        .iter()
+        .map(|t| t.timestamp.elapsed_time.0)
+        .collect::<Vec<_>>();
+
+    Ok(tss
+        .into_iter()
+        .enumerate()
+        .map(|(i, t)| {
+            let parent = t.parent_id.clone().and_then(|p| {
+                if known.contains(&p) {
+                    return Some(p);
+                }
+                if let Some(id) = legacy.get(&p) {
+                    return Some(id.clone());
+                }
+                let e = elapsed_time_of(&p)?;
+                (0..i)
+                    .rev()
+                    .find(|&j| elapsed[j] == e)
+                    .map(|j| ids[j].clone())
+            });
+            t.with_parent_id(parent)
+        })
+        .collect())
+}
+
+/// `<動画ID>-<経過時間>-<ハッシュ>` の経過時間。動画IDは `-` を含みうるため後ろから読む
+fn elapsed_time_of(id: &TimestampId) -> Option<u64> {
+    id.rsplit('-').nth(1)?.parse().ok()
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use domains::value_objects::elapsed_time::ElapsedTime;
+    use domains::value_objects::timestamp::TimeStamp;
+    use domains::value_objects::timestamp_description::TimeStampDescription;
+    use domains::value_objects::video_id::VideoId;
+
+    fn ts(seconds: u64, description: &str) -> VideoTimestampEntity {
+        VideoTimestampEntity::new(
+            VideoId::new("aqz-KE-bpKQ").unwrap(),
+            TimeStamp::new(
+                ElapsedTime::new(seconds).unwrap(),
+                TimeStampDescription::new(description).unwrap(),
+            )
+            .unwrap(),
+        )
+    }
+
+    /// 説明文をそのままハッシュしていた頃のID
+    fn old_id(t: &VideoTimestampEntity) -> TimestampId {
+        TimestampId::legacy(&t.video_id, &t.timestamp).remove(0)
+    }
+
+    /// 以前の形式でも一致しないID
+    fn unknown_id(seconds: u64) -> TimestampId {
+        format!("aqz-KE-bpKQ-{}-0123456789abcdef", seconds)
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn relink_nested_outline() -> anyhow::Result<()> {
+        // 3段の見出し。句読点の違いで新しいIDは古いIDと異なる
+        let part = ts(60, "Part 1!");
+        let chapter = ts(60, "Chapter A.").with_parent_id(Some(old_id(&part)));
+        let song = ts(90, "Song, 1").with_parent_id(Some(old_id(&chapter)));
+        let orphan = ts(120, "Other").with_parent_id(Some(unknown_id(999)));
+
+        let v = relink_parents(vec![part.clone(), chapter.clone(), song, orphan])?;
+        let parents = v.iter().map(|t| t.parent_id.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            parents,
+            vec![None, Some(part.id()?), Some(chapter.id()?), None]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn relink_nested_outline_by_elapsed_time() -> anyhow::Result<()> {
+        // 見出しと先頭の項目の経過時間が同じ場合は、直前の最も内側の見出しに対応付ける
+        let part = ts(60, "Part 1");
+        let chapter = ts(60, "Chapter A").with_parent_id(Some(unknown_id(60)));
+        let song = ts(90, "Song 1").with_parent_id(Some(unknown_id(60)));
+        let next = ts(300, "Part 2");
+        let child = ts(330, "Song 2").with_parent_id(Some(unknown_id(300)));
+
+        let v = relink_parents(vec![
+            part.clone(),
+            chapter.clone(),
+            song,
+            next.clone(),
+            child,
+        ])?;
+        let parents = v.iter().map(|t| t.parent_id.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            parents,
+            vec![
+                None,
+                Some(part.id()?),
+                Some(chapter.id()?),
+                None,
+                Some(next.id()?),
+            ]
+        );
+        Ok(())
+    }
+}
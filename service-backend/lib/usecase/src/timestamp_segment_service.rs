@@ -0,0 +1,168 @@
+use domains::entities::{video::VideoEntity, video_timestamp::VideoTimestampEntity};
+use domains::value_objects::elapsed_time::ElapsedTime;
+use domains::value_objects::timestamp_id::TimestampId;
+
+/// タイムスタンプの終了時間を導出する
+///
+/// 終了時間は同じ動画内で次に始まるタイムスタンプの開始時間とする。
+/// 見出しのタイムスタンプは、配下のタイムスタンプを飛ばして次に始まるものまでとする。
+/// 最後のタイムスタンプは動画の長さが分かる場合のみ、それを終了時間とする。
+/// 既に終了時間が設定されているものは変更しない。
+#[derive(Default)]
+pub struct TimeStampSegmentService {}
+
+impl TimeStampSegmentService {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn assign_end_times(
+        &self,
+        video: &VideoEntity,
+        mut tss: Vec<VideoTimestampEntity>,
+    ) -> Vec<VideoTimestampEntity> {
+        tss.sort_by_key(|t| t.timestamp.elapsed_time);
+        let ids = tss.iter().map(|t| t.id().ok()).collect::<Vec<_>>();
+
+        for i in 0..tss.len() {
+            if tss[i].timestamp.end_time.is_some() {
+                continue;
+            }
+            let start = tss[i].timestamp.elapsed_time;
+            let next = tss[i + 1..]
+                .iter()
+                .filter(|t| t.video_id == tss[i].video_id)
+                .filter(|t| !Self::is_descendant(&tss, &ids, t, ids[i].as_ref()))
+                .map(|t| t.timestamp.elapsed_time)
+                .find(|e| *e > start);
+
+            let end = match next {
+                Some(e) => Some(e),
+                None if tss[i].video_id == video.id => Self::video_length(video),
+                None => None,
+            };
+
+            if let Some(end) = end.filter(|e| *e > start) {
+                tss[i].timestamp.end_time = Some(end);
+            }
+        }
+
+        tss
+    }
+
+    /// `t` が `ancestor` の配下にあるか
+    fn is_descendant(
+        tss: &[VideoTimestampEntity],
+        ids: &[Option<TimestampId>],
+        t: &VideoTimestampEntity,
+        ancestor: Option<&TimestampId>,
+    ) -> bool {
+        let Some(ancestor) = ancestor else {
+            return false;
+        };
+        let mut parent = t.parent_id.as_ref();
+        // 親の参照が循環していても止まるよう、辿る回数を件数までに制限する
+        for _ in 0..tss.len() {
+            let Some(p) = parent else {
+                return false;
+            };
+            if p == ancestor {
+                return true;
+            }
+            parent = ids
+                .iter()
+                .position(|id| id.as_ref() == Some(p))
+                .and_then(|j| tss[j].parent_id.as_ref());
+        }
+        false
+    }
+
+    /// 動画の長さ（秒）
+    fn video_length(video: &VideoEntity) -> Option<ElapsedTime> {
+        video.duration.map(|d| ElapsedTime(d.value()))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use domains::entities::channel::ChannelEntity;
+    use domains::value_objects::channel_name::ChannelName;
+    use domains::value_objects::timestamp::TimeStamp;
+    use domains::value_objects::timestamp_description::TimeStampDescription;
+    use domains::value_objects::video_duration::VideoDuration;
+    use domains::value_objects::video_title::VideoTitle;
+
+    #[test]
+    fn assign_end_times() -> anyhow::Result<()> {
+        let c = ChannelEntity::with_random_id(ChannelName::new("channel")?);
+        let v = VideoEntity::with_random_id(VideoTitle::new("title")?, c)
+            .with_duration(VideoDuration::new(1200)?)
+            .construct()?;
+
+        let ts = |sec: u64| {
+            VideoTimestampEntity::new(
+                v.id.clone(),
+                TimeStamp::new(sec, TimeStampDescription::new("song").unwrap()).unwrap(),
+            )
+        };
+        let explicit = ts(100);
+        let explicit = VideoTimestampEntity {
+            timestamp: explicit.timestamp.with_end_time(ElapsedTime(150)),
+            ..explicit
+        };
+
+        let tss = TimeStampSegmentService::new()
+            .assign_end_times(&v, vec![ts(723), ts(0), explicit, ts(723), ts(1000)]);
+
+        let ends: Vec<(u64, Option<u64>)> = tss
+            .iter()
+            .map(|t| {
+                (
+                    *t.timestamp.elapsed_time,
+                    t.timestamp.end_time.map(u64::from),
+                )
+            })
+            .collect();
+        assert_eq!(
+            ends,
+            vec![
+                (0, Some(100)),
+                (100, Some(150)),
+                (723, Some(1000)),
+                (723, Some(1000)),
+                (1000, Some(1200)),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn assign_end_times_skips_children() -> anyhow::Result<()> {
+        let c = ChannelEntity::with_random_id(ChannelName::new("channel")?);
+        let v = VideoEntity::with_random_id(VideoTitle::new("title")?, c).construct()?;
+
+        let ts = |sec: u64, d: &str| {
+            VideoTimestampEntity::new(
+                v.id.clone(),
+                TimeStamp::new(sec, TimeStampDescription::new(d).unwrap()).unwrap(),
+            )
+        };
+        let section = ts(3600, "Karaoke corner");
+        let parent = Some(section.id()?);
+        let tss = vec![
+            section,
+            ts(3720, "Song A").with_parent_id(parent.clone()),
+            ts(3990, "Song B").with_parent_id(parent),
+            ts(5400, "Game"),
+        ];
+
+        let tss = TimeStampSegmentService::new().assign_end_times(&v, tss);
+        let ends = tss
+            .iter()
+            .map(|t| t.timestamp.end_time.map(u64::from))
+            .collect::<Vec<_>>();
+        assert_eq!(ends, vec![Some(5400), Some(3990), Some(5400), None]);
+        Ok(())
+    }
+}
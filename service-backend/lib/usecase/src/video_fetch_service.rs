@@ -1,7 +1,11 @@
 use domains::entities::video::VideoEntity;
+use domains::entities::video_comment::VideoCommentEntity;
 use domains::repositories::external_video_repository::ExternalVideoRepository;
 use domains::value_objects::channel_id::ChannelId;
+use domains::value_objects::playlist_id::PlaylistId;
+use domains::value_objects::video_id::VideoId;
 use errors::AppResult;
+use futures::channel::mpsc::Sender;
 use std::sync::Arc;
 
 pub struct VideoFetchService<E: ExternalVideoRepository> {
@@ -41,4 +45,42 @@ impl<E: ExternalVideoRepository> VideoFetchService<E> {
         tracing::info!("Downloaded videos count {}", v.len());
         Ok(v)
     }
+
+    pub async fn fetch_videos_by_playlist_id(
+        &self,
+        playlist_id: &PlaylistId,
+    ) -> AppResult<Vec<VideoEntity>> {
+        let v = self
+            .external_video_repository
+            .fetch_videos_by_playlist_id(playlist_id)
+            .await?;
+
+        tracing::info!("Downloaded videos count {} : {}", v.len(), playlist_id);
+        Ok(v)
+    }
+
+    /// チャンネルのすべての動画を、取得できたまとまりごとに `sink` へ送る
+    pub async fn stream_all_videos_by_channel_id(
+        &self,
+        channel_id: &ChannelId,
+        sink: Sender<Vec<VideoEntity>>,
+    ) -> AppResult<()> {
+        self.external_video_repository
+            .stream_all_videos_by_channel_id(channel_id, sink)
+            .await
+    }
+
+    pub async fn fetch_comments_by_video_id(
+        &self,
+        video_id: &VideoId,
+        max_results: u32,
+    ) -> AppResult<Vec<VideoCommentEntity>> {
+        let c = self
+            .external_video_repository
+            .fetch_comments_by_video_id(video_id, max_results)
+            .await?;
+
+        tracing::debug!("Downloaded comments count {} : {}", c.len(), video_id);
+        Ok(c)
+    }
 }
@@ -1,6 +1,9 @@
 use domains::entities::video::VideoEntity;
 use domains::repositories::internal_video_repository::InternalVideoRepository;
+use domains::value_objects::playlist_id::PlaylistId;
+use domains::value_objects::video_id::VideoId;
 use errors::AppResult;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 pub struct VideoIndexingService<I: InternalVideoRepository> {
     pub repo: Arc<I>,
@@ -11,9 +14,231 @@ impl<I: InternalVideoRepository> VideoIndexingService<I> {
         Self { repo }
     }
 
-    pub async fn add_or_update_video_entities(&self, entities: &[VideoEntity]) -> AppResult<()> {
+    /// 保存済みのものから変更のあった動画のみを書き込み、書き込んだ件数を返す
+    /// 保存済みの再生リストへの所属は引き継ぐ
+    pub async fn add_or_update_video_entities(&self, entities: &[VideoEntity]) -> AppResult<usize> {
         tracing::info!("add_or_update_video_entities");
-        self.repo.update_video_entities(entities).await?;
+        let stored = self.stored(entities).await?;
+        let changed = entities
+            .iter()
+            .filter_map(|v| Self::changed(v, stored.get(&v.id)))
+            .collect::<Vec<_>>();
+
+        tracing::info!(
+            "Write {} of {} video entities",
+            changed.len(),
+            entities.len()
+        );
+        if !changed.is_empty() {
+            self.repo.update_video_entities(&changed).await?;
+        }
+        Ok(changed.len())
+    }
+
+    /// 保存済みの再生リストへの所属を引き継いだ動画
+    /// 再生リスト以外から取得した動画にも、所属を写してからタイムスタンプを登録するために使う
+    pub async fn merge_stored_playlists(
+        &self,
+        entities: &[VideoEntity],
+    ) -> AppResult<Vec<VideoEntity>> {
+        let stored = self.stored(entities).await?;
+        Ok(entities
+            .iter()
+            .map(|v| Self::merge(v, stored.get(&v.id)))
+            .collect())
+    }
+
+    /// 再生リストへの所属を、取得し直した動画のみに置き換える
+    ///
+    /// 保存済みの動画のうち、entities で再生リストに所属していないものから所属を外し、外した動画を返す。
+    /// 所属の引き継ぎで外れた所属が戻らないよう、登録の前に呼ぶ
+    pub async fn detach_playlist(
+        &self,
+        playlist_id: &PlaylistId,
+        entities: &[VideoEntity],
+    ) -> AppResult<Vec<VideoEntity>> {
+        let members = entities
+            .iter()
+            .filter(|v| v.playlists.contains(playlist_id))
+            .map(|v| &v.id)
+            .collect::<HashSet<_>>();
+        let detached = self
+            .repo
+            .get_video_entities_by_playlist_id(playlist_id)
+            .await?
+            .into_iter()
+            .filter(|v| !members.contains(&v.id))
+            .map(|mut v| {
+                v.remove_playlist(playlist_id);
+                v
+            })
+            .collect::<Vec<_>>();
+
+        tracing::info!(
+            "Detach {} videos from playlist {}",
+            detached.len(),
+            playlist_id
+        );
+        if !detached.is_empty() {
+            self.repo.update_video_entities(&detached).await?;
+        }
+        Ok(detached)
+    }
+
+    /// 保存済みの動画をまとめて取得する
+    async fn stored(&self, entities: &[VideoEntity]) -> AppResult<HashMap<VideoId, VideoEntity>> {
+        let ids = entities.iter().map(|v| v.id.clone()).collect::<Vec<_>>();
+        Ok(self
+            .repo
+            .get_video_entities_by_ids(&ids)
+            .await?
+            .into_iter()
+            .map(|v| (v.id.clone(), v))
+            .collect())
+    }
+
+    fn merge(video: &VideoEntity, stored: Option<&VideoEntity>) -> VideoEntity {
+        let mut merged = video.clone();
+        if let Some(s) = stored {
+            s.playlists.iter().for_each(|p| merged.add_playlist(p));
+        }
+        merged
+    }
+
+    /// 保存済みのものと ETag・公開状態・再生リストへの所属のいずれかが異なれば、書き込む動画を返す
+    /// ETag の無いものは常に変更ありとみなす
+    fn changed(video: &VideoEntity, stored: Option<&VideoEntity>) -> Option<VideoEntity> {
+        let merged = Self::merge(video, stored);
+        let unchanged = video.etag.is_some()
+            && stored.is_some_and(|s| {
+                s.etag == video.etag
+                    && s.availability == video.availability
+                    && s.playlists == merged.playlists
+            });
+        (!unchanged).then_some(merged)
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use domains::entities::channel::ChannelEntity;
+    use domains::value_objects::channel_name::ChannelName;
+    use domains::value_objects::video_availability::VideoAvailability;
+    use domains::value_objects::video_etag::VideoEtag;
+    use domains::value_objects::video_title::VideoTitle;
+    use test_utils::repositories::InMemoryVideoRepository;
+
+    fn video(id: &str, etag: Option<&str>) -> VideoEntity {
+        let c = ChannelEntity::with_random_id(ChannelName::new("channel").unwrap());
+        let mut v = VideoEntity::build(
+            VideoId::new(id).unwrap(),
+            VideoTitle::new("title").unwrap(),
+            c,
+        );
+        if let Some(e) = etag {
+            v = v.with_etag(VideoEtag::new(e).unwrap());
+        }
+        v.construct().unwrap()
+    }
+
+    #[tokio::test]
+    async fn write_only_changed_videos() -> anyhow::Result<()> {
+        let repo = Arc::new(InMemoryVideoRepository::default());
+        let service = VideoIndexingService::new(repo.clone());
+
+        let first = vec![
+            video("aaaaaaaaaaa", Some("1")),
+            video("bbbbbbbbbbb", Some("1")),
+        ];
+        assert_eq!(service.add_or_update_video_entities(&first).await?, 2);
+
+        let second = vec![
+            video("aaaaaaaaaaa", Some("1")),
+            video("bbbbbbbbbbb", Some("2")),
+            video("ccccccccccc", None),
+        ];
+        assert_eq!(service.add_or_update_video_entities(&second).await?, 2);
+        let etags = repo
+            .get_all_video_entities()
+            .await?
+            .into_iter()
+            .map(|v| (v.id.to_string(), v.etag.map(|e| e.to_string())))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            etags,
+            vec![
+                ("aaaaaaaaaaa".to_string(), Some("1".to_string())),
+                ("bbbbbbbbbbb".to_string(), Some("2".to_string())),
+                ("ccccccccccc".to_string(), None),
+            ]
+        );
+
+        // 公開状態のみが変わった場合も書き込む
+        let unavailable = VideoEntity {
+            availability: VideoAvailability::Unavailable,
+            ..video("aaaaaaaaaaa", Some("1"))
+        };
+        assert_eq!(
+            service.add_or_update_video_entities(&[unavailable]).await?,
+            1
+        );
+
+        // 再生リストから取り込んだ動画は所属を書き込み、チャンネルから取得し直しても所属を引き継ぐ
+        let playlist = PlaylistId::new("PL0123456789abcdef")?;
+        let mut in_playlist = video("bbbbbbbbbbb", Some("2"));
+        in_playlist.add_playlist(&playlist);
+        assert_eq!(
+            service.add_or_update_video_entities(&[in_playlist]).await?,
+            1
+        );
+        let plain = video("bbbbbbbbbbb", Some("2"));
+        assert_eq!(
+            service
+                .add_or_update_video_entities(std::slice::from_ref(&plain))
+                .await?,
+            0
+        );
+        let merged = service.merge_stored_playlists(&[plain]).await?;
+        assert_eq!(merged[0].playlists, vec![playlist]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn detach_removed_playlist_members() -> anyhow::Result<()> {
+        let repo = Arc::new(InMemoryVideoRepository::default());
+        let service = VideoIndexingService::new(repo.clone());
+        let playlist = PlaylistId::new("PL0123456789abcdef")?;
+        let other = PlaylistId::new("PLfedcba9876543210")?;
+
+        let in_playlists = |id: &str, playlists: &[&PlaylistId]| {
+            let mut v = video(id, Some("1"));
+            playlists.iter().for_each(|p| v.add_playlist(p));
+            v
+        };
+        let stored = vec![
+            in_playlists("aaaaaaaaaaa", &[&playlist]),
+            in_playlists("bbbbbbbbbbb", &[&playlist, &other]),
+            in_playlists("ccccccccccc", &[&other]),
+        ];
+        service.add_or_update_video_entities(&stored).await?;
+
+        // 取得し直した再生リストには aaaaaaaaaaa のみが残り、bbbbbbbbbbb はチャンネルから取得した
+        let fetched = vec![
+            in_playlists("aaaaaaaaaaa", &[&playlist]),
+            video("bbbbbbbbbbb", Some("1")),
+        ];
+        let detached = service.detach_playlist(&playlist, &fetched).await?;
+        assert_eq!(detached.len(), 1);
+        assert_eq!(detached[0].id.as_str(), "bbbbbbbbbbb");
+
+        let merged = service.merge_stored_playlists(&fetched).await?;
+        assert_eq!(merged[0].playlists, vec![playlist.clone()]);
+        assert_eq!(merged[1].playlists, vec![other.clone()]);
+        let c = repo
+            .get_video_entity_by_id(&VideoId::new("ccccccccccc")?)
+            .await?;
+        assert_eq!(c.map(|v| v.playlists), Some(vec![other]));
         Ok(())
     }
 }
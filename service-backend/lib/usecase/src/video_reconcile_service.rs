@@ -0,0 +1,89 @@
+use domains::entities::video::VideoEntity;
+use domains::repositories::external_video_repository::ExternalVideoRepository;
+use domains::repositories::internal_timestamp_repository::InternalVideoTimeStampRepository;
+use domains::repositories::internal_video_repository::InternalVideoRepository;
+use domains::value_objects::video_availability::VideoAvailability;
+use domains::value_objects::video_id::VideoId;
+use errors::AppResult;
+use std::sync::Arc;
+
+/// 保存済みの動画を取得元と照合し、公開状態の変わった動画とそのタイムスタンプを更新する
+///
+/// 削除・非公開になった動画は消さずに視聴できないものとして残し、検索から除く。
+/// 再び公開された場合は元に戻す。
+pub struct VideoReconcileService<E, V, T>
+where
+    E: ExternalVideoRepository,
+    V: InternalVideoRepository,
+    T: InternalVideoTimeStampRepository,
+{
+    external_video_repository: Arc<E>,
+    video_repository: Arc<V>,
+    timestamp_repository: Arc<T>,
+}
+
+impl<E, V, T> VideoReconcileService<E, V, T>
+where
+    E: ExternalVideoRepository,
+    V: InternalVideoRepository,
+    T: InternalVideoTimeStampRepository,
+{
+    pub fn new(
+        external_video_repository: Arc<E>,
+        video_repository: Arc<V>,
+        timestamp_repository: Arc<T>,
+    ) -> Self {
+        Self {
+            external_video_repository,
+            video_repository,
+            timestamp_repository,
+        }
+    }
+
+    /// 公開状態の変わった動画を更新し、更新後の動画を返す
+    pub async fn reconcile(&self, videos: &[VideoEntity]) -> AppResult<Vec<VideoEntity>> {
+        let ids = videos
+            .iter()
+            .map(|v| v.id.clone())
+            .collect::<Vec<VideoId>>();
+        let availabilities = self
+            .external_video_repository
+            .fetch_video_availabilities(&ids)
+            .await?;
+
+        let changed = videos
+            .iter()
+            .filter_map(|v| {
+                let a = availabilities
+                    .get(&v.id)
+                    .copied()
+                    .unwrap_or(VideoAvailability::Unavailable);
+                (a != v.availability).then(|| VideoEntity {
+                    availability: a,
+                    ..v.clone()
+                })
+            })
+            .collect::<Vec<_>>();
+        if changed.is_empty() {
+            return Ok(changed);
+        }
+
+        self.video_repository
+            .update_video_entities(&changed)
+            .await?;
+        // タイムスタンプは動画の公開状態を写して持つため、書き直す
+        for v in changed.iter() {
+            let tss = self
+                .timestamp_repository
+                .get_video_timestamp_entities_by_video_id(&v.id)
+                .await?;
+            if !tss.is_empty() {
+                self.timestamp_repository
+                    .add_video_timestamp_entities(v, &tss)
+                    .await?;
+            }
+            tracing::info!("Video {} is {:?} now", v.id, v.availability);
+        }
+        Ok(changed)
+    }
+}
@@ -0,0 +1,126 @@
+use domains::entities::video_timestamp::VideoTimestampEntity;
+use domains::repositories::internal_timestamp_search_repository::InternalVideoTimeStampSearchRepository;
+use domains::value_objects::limit::Limit;
+use domains::value_objects::video_id::VideoId;
+use errors::AppResult;
+use std::sync::Arc;
+
+/// 1動画あたりに取得するタイムスタンプの上限
+const TIMESTAMPS_LIMIT: usize = 1000;
+
+/// 見出しと配下のタイムスタンプ
+#[derive(Debug, Clone)]
+pub struct TimeStampNode {
+    pub timestamp: VideoTimestampEntity,
+    pub children: Vec<TimeStampNode>,
+}
+
+/// 動画ごとのタイムスタンプ一覧
+#[derive(Clone)]
+pub struct VideoTimeStampService {
+    search_repository: Arc<dyn InternalVideoTimeStampSearchRepository + Send + Sync>,
+}
+
+impl VideoTimeStampService {
+    pub fn new(
+        search_repository: Arc<dyn InternalVideoTimeStampSearchRepository + Send + Sync>,
+    ) -> Self {
+        Self { search_repository }
+    }
+
+    /// 経過時間順の一覧
+    pub async fn timestamps(&self, video_id: &VideoId) -> AppResult<Vec<VideoTimestampEntity>> {
+        let mut v = self
+            .search_repository
+            .find_timestamps_by_video_id(video_id, Limit::new(TIMESTAMPS_LIMIT)?)
+            .await?;
+        v.sort_by_key(|e| e.timestamp.elapsed_time);
+        Ok(v)
+    }
+
+    /// 見出しごとにまとめた一覧
+    pub async fn timestamp_tree(&self, video_id: &VideoId) -> AppResult<Vec<TimeStampNode>> {
+        Ok(build_tree(self.timestamps(video_id).await?))
+    }
+}
+
+/// 経過時間順のタイムスタンプを親子関係で木にする
+///
+/// 親が見つからない、または親が自分より後ろにあるものは最上位に置く。
+pub fn build_tree(entities: Vec<VideoTimestampEntity>) -> Vec<TimeStampNode> {
+    let ids = entities.iter().map(|e| e.id().ok()).collect::<Vec<_>>();
+
+    let mut children: Vec<Vec<usize>> = vec![vec![]; entities.len()];
+    let mut roots = vec![];
+    for (i, e) in entities.iter().enumerate() {
+        let parent = e
+            .parent_id
+            .as_ref()
+            .and_then(|p| ids[..i].iter().position(|id| id.as_ref() == Some(p)));
+        match parent {
+            Some(p) => children[p].push(i),
+            None => roots.push(i),
+        }
+    }
+
+    fn node(
+        i: usize,
+        entities: &mut [Option<VideoTimestampEntity>],
+        children: &[Vec<usize>],
+    ) -> Option<TimeStampNode> {
+        let timestamp = entities[i].take()?;
+        let children = children[i]
+            .iter()
+            .filter_map(|c| node(*c, entities, children))
+            .collect();
+        Some(TimeStampNode {
+            timestamp,
+            children,
+        })
+    }
+
+    let mut entities = entities.into_iter().map(Some).collect::<Vec<_>>();
+    roots
+        .into_iter()
+        .filter_map(|i| node(i, &mut entities, &children))
+        .collect()
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use domains::value_objects::timestamp::TimeStamp;
+    use domains::value_objects::timestamp_description::TimeStampDescription;
+
+    fn entity(sec: u64, d: &str) -> VideoTimestampEntity {
+        VideoTimestampEntity::new(
+            VideoId::new("abcdefghijk").unwrap(),
+            TimeStamp::new(sec, TimeStampDescription::new(d).unwrap()).unwrap(),
+        )
+    }
+
+    #[test]
+    fn build_timestamp_tree() -> anyhow::Result<()> {
+        let section = entity(3600, "Karaoke corner");
+        let song = entity(3720, "Song A").with_parent_id(Some(section.id()?));
+        let verse = entity(3750, "Verse").with_parent_id(Some(song.id()?));
+        let orphan = entity(4000, "Orphan").with_parent_id(Some(entity(1, "missing").id()?));
+
+        let tree = build_tree(vec![entity(0, "Opening"), section, song, verse, orphan]);
+
+        let roots = tree
+            .iter()
+            .map(|n| n.timestamp.timestamp.description.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(roots, vec!["Opening", "Karaoke corner", "Orphan"]);
+        assert_eq!(tree[1].children.len(), 1);
+        assert_eq!(
+            tree[1].children[0].children[0]
+                .timestamp
+                .timestamp
+                .elapsed_time,
+            3750
+        );
+        Ok(())
+    }
+}
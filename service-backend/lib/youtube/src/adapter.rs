@@ -1,3 +1,4 @@
 pub mod channel;
+pub mod comment;
 pub mod thumbnail;
 pub mod video;
@@ -0,0 +1,86 @@
+use domains::entities::video_comment::VideoCommentEntity;
+use domains::value_objects::comment_author::CommentAuthor;
+use domains::value_objects::comment_id::CommentId;
+use domains::value_objects::comment_text::CommentText;
+use domains::value_objects::video_id::VideoId;
+use errors::AppError::{self, DomainParseError};
+use google_youtube3::api::CommentThread;
+
+/// Converter from YouTube CommentThread to VideoCommentEntity
+pub struct CommentThreadConverter(pub CommentThread);
+
+impl TryInto<VideoCommentEntity> for CommentThreadConverter {
+    type Error = AppError;
+
+    fn try_into(self) -> Result<VideoCommentEntity, Self::Error> {
+        let inner = self.0;
+        let snippet = inner.snippet.ok_or(DomainParseError(
+            "CommentThread snippet is missing".to_string(),
+        ))?;
+        let video_id = snippet
+            .video_id
+            .ok_or(DomainParseError("Video id is missing".to_string()))?;
+        let comment = snippet
+            .top_level_comment
+            .ok_or(DomainParseError("Top level comment is missing".to_string()))?;
+        let id = comment
+            .id
+            .ok_or(DomainParseError("Comment id is missing".to_string()))?;
+        let comment = comment
+            .snippet
+            .ok_or(DomainParseError("Comment snippet is missing".to_string()))?;
+        let author = comment
+            .author_display_name
+            .ok_or(DomainParseError("Comment author is missing".to_string()))?;
+        // textOriginal は投稿時の生テキスト。無い場合は表示用テキストを使う
+        let text = comment
+            .text_original
+            .or(comment.text_display)
+            .ok_or(DomainParseError("Comment text is missing".to_string()))?;
+
+        Ok(VideoCommentEntity::new(
+            CommentId::new(&id)?,
+            VideoId::new(&video_id)?,
+            CommentAuthor::new(&author)?,
+            CommentText::new(&text)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use google_youtube3::api::{Comment, CommentSnippet, CommentThreadSnippet};
+
+    #[test]
+    fn comment_thread_converter_test() {
+        let thread = CommentThread {
+            snippet: Some(CommentThreadSnippet {
+                video_id: Some("aqz-KE-bpKQ".to_string()),
+                top_level_comment: Some(Comment {
+                    id: Some("Ugw-comment-id".to_string()),
+                    snippet: Some(CommentSnippet {
+                        author_display_name: Some("@author".to_string()),
+                        text_original: Some("01:23 opening".to_string()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let r: Result<VideoCommentEntity, AppError> = CommentThreadConverter(thread).try_into();
+        assert!(r.is_ok());
+        let c = r.unwrap();
+        assert_eq!(c.id, "Ugw-comment-id");
+        assert_eq!(c.video_id, "aqz-KE-bpKQ");
+        assert_eq!(c.author, "@author");
+        assert_eq!(c.text, "01:23 opening");
+
+        let r: Result<VideoCommentEntity, AppError> =
+            CommentThreadConverter(CommentThread::default()).try_into();
+        assert!(r.is_err());
+    }
+}
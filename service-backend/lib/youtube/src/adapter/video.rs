@@ -1,15 +1,19 @@
 use crate::adapter::thumbnail::ThumbnailsToThumbnailConverter;
 use domains::entities::channel::ChannelEntity;
 use domains::entities::video::VideoEntity;
+use domains::value_objects::broadcast_state::BroadcastState;
 use domains::value_objects::channel_id::ChannelId;
 use domains::value_objects::channel_name::ChannelName;
 use domains::value_objects::thumbnail::Thumbnail;
+use domains::value_objects::video_availability::VideoAvailability;
 use domains::value_objects::video_description::VideoDescription;
+use domains::value_objects::video_duration::VideoDuration;
+use domains::value_objects::video_etag::VideoEtag;
 use domains::value_objects::video_id::VideoId;
 use domains::value_objects::video_tag::VideoTag;
 use domains::value_objects::video_title::VideoTitle;
 use errors::AppError::{self, DomainParseError};
-use google_youtube3::api::Video;
+use google_youtube3::api::{Video, VideoStatus};
 
 /// Converter from YouTube Video to VideoEntity
 pub struct VideoEntityConverter(pub Video);
@@ -54,12 +58,27 @@ impl TryInto<VideoEntity> for VideoEntityConverter {
             .published_at
             .ok_or(DomainParseError("Published date is missing".to_string()))?;
 
-        let a = if let Some(ls) = inner.live_streaming_details {
-            ls.actual_start_time
-        } else {
-            None
+        let live = inner.live_streaming_details.is_some();
+        let (a, e) = match inner.live_streaming_details {
+            Some(ls) => (ls.actual_start_time, ls.actual_end_time),
+            None => (None, None),
         };
 
+        // 配信予定・配信中の動画は P0D が返るため、長さ不明として扱う
+        let duration = inner
+            .content_details
+            .and_then(|c| c.duration)
+            .and_then(|d| VideoDuration::from_iso8601(&d).ok())
+            .filter(|d| d.value() > 0);
+
+        let state = broadcast_state(
+            snippet.live_broadcast_content.as_deref(),
+            live,
+            duration,
+            &title,
+            &description,
+        );
+
         let mut v = VideoEntity::build(
             VideoId::new(&id)?,
             VideoTitle::new(&title)?,
@@ -80,6 +99,173 @@ impl TryInto<VideoEntity> for VideoEntityConverter {
             v = v.with_actual_start_time(a);
         }
 
-        v.construct()
+        if let Some(e) = e {
+            v = v.with_actual_end_time(e);
+        }
+
+        if let Some(d) = duration {
+            v = v.with_duration(d);
+        }
+
+        if let Some(e) = inner.etag.as_deref().and_then(|e| VideoEtag::new(e).ok()) {
+            v = v.with_etag(e);
+        }
+
+        if let Some(s) = inner.status.as_ref() {
+            v = v.with_availability(availability(s));
+        }
+
+        v.with_broadcast_state(state).construct()
+    }
+}
+
+/// ショート動画とみなす長さの上限（秒）
+const SHORT_MAX_SECONDS: u64 = 180;
+
+/// 配信の状態を判定する
+///
+/// API はプレミア公開とショート動画を区別しないため、次のように推定する。
+/// - プレミア公開は、配信予定・配信中でも長さが決まっているもの。公開後は配信のアーカイブと区別できない
+/// - ショート動画は、配信ではなく、タイトルか説明に #shorts を含み（大文字小文字は区別しない）、長さが上限以下のもの。
+///   短いだけの通常の動画もあるため、長さだけでは判定しない
+pub fn broadcast_state(
+    live_broadcast_content: Option<&str>,
+    live: bool,
+    duration: Option<VideoDuration>,
+    title: &str,
+    description: &str,
+) -> BroadcastState {
+    let seconds = duration.map(|d| d.value());
+    match live_broadcast_content {
+        Some("upcoming" | "live") if seconds.is_some() => BroadcastState::Premiere,
+        Some("upcoming") => BroadcastState::Upcoming,
+        Some("live") => BroadcastState::Live,
+        _ if live => BroadcastState::Archived,
+        _ => match seconds {
+            Some(s)
+                if s <= SHORT_MAX_SECONDS
+                    && (has_shorts_tag(title) || has_shorts_tag(description)) =>
+            {
+                BroadcastState::Short
+            }
+            _ => BroadcastState::Upload,
+        },
+    }
+}
+
+fn has_shorts_tag(s: &str) -> bool {
+    s.to_lowercase().contains("#shorts")
+}
+
+/// 動画の状態から公開状態を判定する
+/// 非公開のほか、アップロードに失敗・拒否された動画も視聴できないものとする
+pub fn availability(status: &VideoStatus) -> VideoAvailability {
+    if matches!(
+        status.upload_status.as_deref(),
+        Some("deleted" | "failed" | "rejected")
+    ) {
+        return VideoAvailability::Unavailable;
+    }
+    match status.privacy_status.as_deref() {
+        Some("unlisted") => VideoAvailability::Unlisted,
+        Some("private") => VideoAvailability::Unavailable,
+        _ => VideoAvailability::Public,
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use google_youtube3::api::{VideoContentDetails, VideoSnippet};
+
+    fn video(duration: &str) -> Video {
+        Video {
+            id: Some("aqz-KE-bpKQ".to_string()),
+            etag: Some("etag".to_string()),
+            snippet: Some(VideoSnippet {
+                title: Some("title".to_string()),
+                description: Some("description".to_string()),
+                thumbnails: Some(Default::default()),
+                channel_id: Some("UC_x5XG1OV2P6uZZ5FSM9Ttw".to_string()),
+                channel_title: Some("channel".to_string()),
+                published_at: Some(chrono::Utc::now()),
+                ..Default::default()
+            }),
+            content_details: Some(VideoContentDetails {
+                duration: Some(duration.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[rstest::rstest]
+    #[test]
+    #[case("PT1H2M3S", Some(3723))]
+    #[case("P0D", None)]
+    #[case("invalid", None)]
+    fn video_duration_conversion(#[case] duration: &str, #[case] expected: Option<u64>) {
+        let r: Result<VideoEntity, AppError> = VideoEntityConverter(video(duration)).try_into();
+        assert!(r.is_ok());
+        let v = r.unwrap();
+        assert_eq!(v.duration.map(u64::from), expected);
+        assert_eq!(v.etag.as_ref().map(|e| e.as_str()), Some("etag"));
+    }
+
+    #[rstest::rstest]
+    #[case(Some("public"), Some("processed"), VideoAvailability::Public)]
+    #[case(Some("unlisted"), Some("processed"), VideoAvailability::Unlisted)]
+    #[case(Some("private"), Some("processed"), VideoAvailability::Unavailable)]
+    #[case(Some("public"), Some("rejected"), VideoAvailability::Unavailable)]
+    #[case(None, None, VideoAvailability::Public)]
+    fn video_availability(
+        #[case] privacy: Option<&str>,
+        #[case] upload: Option<&str>,
+        #[case] expected: VideoAvailability,
+    ) {
+        let status = VideoStatus {
+            privacy_status: privacy.map(str::to_string),
+            upload_status: upload.map(str::to_string),
+            ..Default::default()
+        };
+        assert_eq!(availability(&status), expected);
+    }
+
+    #[rstest::rstest]
+    #[case(Some("upcoming"), true, None, "", "", BroadcastState::Upcoming)]
+    #[case(Some("upcoming"), true, Some(300), "", "", BroadcastState::Premiere)]
+    #[case(Some("live"), true, None, "", "", BroadcastState::Live)]
+    #[case(Some("none"), true, Some(3600), "", "", BroadcastState::Archived)]
+    #[case(Some("none"), false, Some(3600), "", "", BroadcastState::Upload)]
+    #[case(Some("none"), false, Some(45), "", "", BroadcastState::Upload)]
+    #[case(Some("none"), false, Some(45), "", "#shorts", BroadcastState::Short)]
+    #[case(Some("none"), false, Some(150), "", "#shorts", BroadcastState::Short)]
+    #[case(Some("none"), false, Some(150), "", "#Shorts", BroadcastState::Short)]
+    #[case(
+        Some("none"),
+        false,
+        Some(150),
+        "cover #SHORTS",
+        "",
+        BroadcastState::Short
+    )]
+    #[case(Some("none"), false, Some(150), "", "", BroadcastState::Upload)]
+    #[case(Some("none"), false, Some(300), "", "#shorts", BroadcastState::Upload)]
+    fn video_broadcast_state(
+        #[case] content: Option<&str>,
+        #[case] live: bool,
+        #[case] duration: Option<u64>,
+        #[case] title: &str,
+        #[case] description: &str,
+        #[case] expected: BroadcastState,
+    ) {
+        let state = broadcast_state(
+            content,
+            live,
+            duration.map(VideoDuration),
+            title,
+            description,
+        );
+        assert_eq!(state, expected);
     }
 }
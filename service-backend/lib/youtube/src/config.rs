@@ -1,14 +1,127 @@
-use once_cell::sync::Lazy;
+use errors::{AppError, AppResult};
 use serde::Deserialize;
 
+/// YouTube Data API の認証方法
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMode {
+    /// API キー。公開データの読み取りのみ
+    ApiKey,
+    /// サービスアカウントの鍵
+    ServiceAccount,
+    /// ブラウザでの OAuth 同意を経て、トークンを保存して使う
+    #[default]
+    InstalledFlow,
+}
+
+/// `TSSEARCH_` で始まる環境変数から読み込む YouTube クライアントの設定
 #[derive(Debug, Deserialize)]
 pub struct YouTubeClient {
-    pub google_client_secret_path: String,
-    pub persistent_token_path: String,
+    #[serde(default)]
+    pub auth_mode: AuthMode,
+    /// `api_key` の場合に使う
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// `service_account` の場合に使う
+    #[serde(default)]
+    pub service_account_key_path: Option<String>,
+    /// `installed_flow` の場合に使う
+    #[serde(default)]
+    pub google_client_secret_path: Option<String>,
+    /// `installed_flow` の場合に使う。取得したトークンの保存先
+    #[serde(default)]
+    pub persistent_token_path: Option<String>,
+    /// API の接続先。テスト用のサーバーなどに向ける場合に指定する
+    #[serde(default)]
+    pub api_base_url: Option<String>,
+    /// 1日あたりの YouTube Data API の利用枠（ユニット）
+    #[serde(default = "default_daily_quota_budget")]
+    pub daily_quota_budget: u64,
+    /// 当日の利用量の記録先。指定しない場合は実行ごとに数え直す
+    #[serde(default)]
+    pub quota_usage_path: Option<String>,
+    /// 並行して取得するビデオ詳細のまとまりの数
+    #[serde(default = "default_max_concurrency")]
+    pub max_concurrency: usize,
+    /// 1秒あたりのリクエスト数の上限。並行するリクエスト全体で共有する
+    #[serde(default = "default_requests_per_second")]
+    pub requests_per_second: usize,
 }
 
-pub static YOUTUBE_CLIENT: Lazy<YouTubeClient> = Lazy::new(|| {
-    envy::prefixed("TSSEARCH_")
-        .from_env::<YouTubeClient>()
-        .unwrap()
-});
+fn default_daily_quota_budget() -> u64 {
+    10_000
+}
+
+fn default_max_concurrency() -> usize {
+    4
+}
+
+fn default_requests_per_second() -> usize {
+    10
+}
+
+impl YouTubeClient {
+    /// API キーで `base_url` の API に接続する設定
+    pub fn with_api_key(api_key: &str, base_url: &str) -> Self {
+        Self {
+            auth_mode: AuthMode::ApiKey,
+            api_key: Some(api_key.to_string()),
+            service_account_key_path: None,
+            google_client_secret_path: None,
+            persistent_token_path: None,
+            api_base_url: Some(base_url.to_string()),
+            daily_quota_budget: default_daily_quota_budget(),
+            quota_usage_path: None,
+            max_concurrency: default_max_concurrency(),
+            requests_per_second: default_requests_per_second(),
+        }
+    }
+
+    pub fn from_env() -> AppResult<Self> {
+        envy::prefixed("TSSEARCH_")
+            .from_env::<YouTubeClient>()
+            .map_err(|e| AppError::InvalidInput(format!("Invalid YouTube client config : {}", e)))
+    }
+
+    /// 認証方法に必要な設定。空文字は未設定とみなす
+    pub fn required<'a>(value: &'a Option<String>, name: &str) -> AppResult<&'a str> {
+        value.as_deref().filter(|v| !v.is_empty()).ok_or_else(|| {
+            AppError::InvalidInput(format!(
+                "TSSEARCH_{} is required for the auth mode",
+                name.to_uppercase()
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    fn config(vars: &[(&str, &str)]) -> Result<YouTubeClient, envy::Error> {
+        envy::prefixed("TSSEARCH_").from_iter(
+            vars.iter()
+                .map(|(k, v)| (format!("TSSEARCH_{}", k), v.to_string())),
+        )
+    }
+
+    #[test]
+    fn load_auth_mode() -> anyhow::Result<()> {
+        let c = config(&[("AUTH_MODE", "api_key"), ("API_KEY", "key")])?;
+        assert_eq!(c.auth_mode, AuthMode::ApiKey);
+        assert_eq!(YouTubeClient::required(&c.api_key, "api_key")?, "key");
+        assert!(
+            YouTubeClient::required(&c.service_account_key_path, "service_account_key_path")
+                .is_err()
+        );
+
+        let c = config(&[])?;
+        assert_eq!(c.auth_mode, AuthMode::InstalledFlow);
+        assert_eq!(c.daily_quota_budget, 10_000);
+        assert_eq!(c.max_concurrency, 4);
+        assert_eq!(c.requests_per_second, 10);
+
+        assert!(config(&[("AUTH_MODE", "password")]).is_err());
+        Ok(())
+    }
+}
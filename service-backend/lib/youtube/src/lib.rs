@@ -1,3 +1,5 @@
 mod adapter;
 pub mod config;
+pub mod quota;
 pub mod repositories;
+pub mod retry;
@@ -0,0 +1,411 @@
+use chrono::{FixedOffset, NaiveDate, Utc};
+use errors::{AppError, AppResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 利用枠は太平洋時間の0時に戻るため、日付はこの時差で数える（夏時間は考慮しない）
+const QUOTA_RESET_OFFSET_SECS: i32 = -8 * 3600;
+
+/// 利用枠を消費する YouTube Data API のエンドポイント
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QuotaEndpoint {
+    Channels,
+    PlaylistItems,
+    Videos,
+    CommentThreads,
+}
+
+impl QuotaEndpoint {
+    /// 1リクエストあたりの消費ユニット
+    pub fn cost(&self) -> u64 {
+        match self {
+            QuotaEndpoint::Channels
+            | QuotaEndpoint::PlaylistItems
+            | QuotaEndpoint::Videos
+            | QuotaEndpoint::CommentThreads => 1,
+        }
+    }
+}
+
+impl fmt::Display for QuotaEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            QuotaEndpoint::Channels => "channels.list",
+            QuotaEndpoint::PlaylistItems => "playlistItems.list",
+            QuotaEndpoint::Videos => "videos.list",
+            QuotaEndpoint::CommentThreads => "commentThreads.list",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// エンドポイントごとの利用量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuotaUsage {
+    pub requests: u64,
+    pub units: u64,
+}
+
+/// 利用量の記録ファイル
+#[derive(Debug, Serialize, Deserialize)]
+struct QuotaLedger {
+    date: NaiveDate,
+    units: u64,
+}
+
+/// YouTube Data API の利用量を数え、1日の利用枠を超えるリクエストを止める
+#[derive(Debug)]
+pub struct QuotaTracker {
+    budget: u64,
+    state: Mutex<QuotaState>,
+    ledger: Option<PathBuf>,
+}
+
+/// 当日の利用量
+#[derive(Debug)]
+struct QuotaState {
+    date: NaiveDate,
+    /// 今回の実行より前と、同時に動く他の実行で当日消費した量
+    spent_before: u64,
+    usage: BTreeMap<QuotaEndpoint, QuotaUsage>,
+    /// 今回の実行の消費量のうち、記録ファイルに書き出した量
+    saved: u64,
+}
+
+impl QuotaState {
+    fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            spent_before: 0,
+            usage: BTreeMap::new(),
+            saved: 0,
+        }
+    }
+
+    /// 日付が変わっていれば利用枠が戻るため、数え直す
+    fn roll(&mut self, date: NaiveDate) {
+        if self.date != date {
+            *self = Self::new(date);
+        }
+    }
+
+    fn units(&self) -> u64 {
+        self.usage.values().map(|u| u.units).sum()
+    }
+}
+
+impl QuotaTracker {
+    pub fn new(budget: u64) -> Self {
+        Self {
+            budget,
+            state: Mutex::new(QuotaState::new(today())),
+            ledger: None,
+        }
+    }
+
+    /// 記録ファイルから当日の利用量を引き継ぐ。日付が変わっていれば数え直す
+    /// 記録ファイルが読めない場合は、利用枠を数え直さないようエラーにする
+    pub fn with_ledger(mut self, path: impl AsRef<Path>) -> AppResult<Self> {
+        let path = path.as_ref();
+        {
+            let _lock = lock_ledger(path)?;
+            let state = self.state.get_mut().unwrap();
+            state.spent_before = read_ledger(path, state.date)?;
+        }
+        self.ledger = Some(path.to_path_buf());
+        Ok(self)
+    }
+
+    /// リクエストの前に利用枠を消費する。利用枠を超える場合は消費せずにエラーを返す
+    pub fn spend(&self, endpoint: QuotaEndpoint) -> AppResult<()> {
+        self.spend_on(endpoint, today())
+    }
+
+    fn spend_on(&self, endpoint: QuotaEndpoint, date: NaiveDate) -> AppResult<()> {
+        let mut state = self.state.lock().unwrap();
+        state.roll(date);
+        let spent = state.spent_before + state.units();
+        if spent + endpoint.cost() > self.budget {
+            return Err(AppError::QuotaExceeded(format!(
+                "{} : {} of {} units spent",
+                endpoint, spent, self.budget
+            )));
+        }
+
+        let u = state.usage.entry(endpoint).or_default();
+        u.requests += 1;
+        u.units += endpoint.cost();
+        Ok(())
+    }
+
+    pub fn report(&self) -> QuotaReport {
+        let state = self.state.lock().unwrap();
+        QuotaReport {
+            budget: self.budget,
+            spent_before: state.spent_before,
+            usage: state.usage.clone(),
+        }
+    }
+
+    /// 当日の利用量を記録ファイルに書き出す
+    ///
+    /// 他の実行も同じファイルに書き出すため、ロックを取ってから読み直した記録に前回から増えた分のみを足す。
+    /// 書きかけのファイルを読まれないよう、一時ファイルに書いてから置き換える。
+    pub fn save(&self) -> AppResult<()> {
+        self.save_on(today())
+    }
+
+    fn save_on(&self, date: NaiveDate) -> AppResult<()> {
+        let Some(path) = self.ledger.as_ref() else {
+            return Ok(());
+        };
+        let mut state = self.state.lock().unwrap();
+        state.roll(date);
+        let units = state.units();
+
+        let _lock = lock_ledger(path)?;
+        let total = read_ledger(path, state.date)? + (units - state.saved);
+        write_ledger(
+            path,
+            &QuotaLedger {
+                date: state.date,
+                units: total,
+            },
+        )?;
+        state.saved = units;
+        state.spent_before = total.saturating_sub(units);
+        Ok(())
+    }
+}
+
+/// 記録ファイルにある date の利用量。ファイルが無い場合や別の日の記録は 0
+fn read_ledger(path: &Path, date: NaiveDate) -> AppResult<u64> {
+    let s = match std::fs::read_to_string(path) {
+        Ok(s) => s,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(ledger_error("read", path, e)),
+    };
+    let ledger =
+        serde_json::from_str::<QuotaLedger>(&s).map_err(|e| ledger_error("parse", path, e))?;
+    Ok(match ledger.date == date {
+        true => ledger.units,
+        false => 0,
+    })
+}
+
+/// 同じディレクトリの一時ファイルに書き出し、記録ファイルと置き換える
+fn write_ledger(path: &Path, ledger: &QuotaLedger) -> AppResult<()> {
+    let s = serde_json::to_string(ledger)
+        .map_err(|e| AppError::InternalServerError(anyhow::anyhow!(e)))?;
+    let tmp = sidecar(path, &format!("tmp.{}", std::process::id()));
+    std::fs::write(&tmp, s).map_err(|e| ledger_error("write", &tmp, e))?;
+    std::fs::rename(&tmp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        ledger_error("write", path, e)
+    })
+}
+
+/// 記録ファイルの読み書きの間、他の実行を待たせる。返したファイルを閉じるとロックが外れる
+///
+/// 記録ファイル自体は置き換えるため、隣に置いた `.lock` ファイルをロックする。
+fn lock_ledger(path: &Path) -> AppResult<File> {
+    let lock = sidecar(path, "lock");
+    let file = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock)
+        .map_err(|e| ledger_error("open", &lock, e))?;
+    file.lock().map_err(|e| ledger_error("lock", &lock, e))?;
+    Ok(file)
+}
+
+/// 記録ファイルの名前に拡張子を足したパス
+fn sidecar(path: &Path, extension: &str) -> PathBuf {
+    let mut s = path.as_os_str().to_owned();
+    s.push(".");
+    s.push(extension);
+    PathBuf::from(s)
+}
+
+fn ledger_error(action: &str, path: &Path, e: impl fmt::Display) -> AppError {
+    AppError::InvalidInput(format!(
+        "Unable to {} quota ledger {} : {}",
+        action,
+        path.display(),
+        e
+    ))
+}
+
+fn today() -> NaiveDate {
+    Utc::now()
+        .with_timezone(&FixedOffset::east_opt(QUOTA_RESET_OFFSET_SECS).unwrap())
+        .date_naive()
+}
+
+/// 実行を通した利用量
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuotaReport {
+    pub budget: u64,
+    pub spent_before: u64,
+    pub usage: BTreeMap<QuotaEndpoint, QuotaUsage>,
+}
+
+impl QuotaReport {
+    /// 当日の消費量
+    pub fn total(&self) -> u64 {
+        self.spent_before + self.usage.values().map(|u| u.units).sum::<u64>()
+    }
+}
+
+impl fmt::Display for QuotaReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "quota {} / {} units", self.total(), self.budget)?;
+        if self.spent_before > 0 {
+            write!(f, " (previous runs {})", self.spent_before)?;
+        }
+        for (endpoint, u) in self.usage.iter() {
+            write!(
+                f,
+                "\n  {}\t{} requests\t{} units",
+                endpoint, u.requests, u.units
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn stop_at_budget() {
+        let tracker = QuotaTracker::new(3);
+        assert!(tracker.spend(QuotaEndpoint::Channels).is_ok());
+        assert!(tracker.spend(QuotaEndpoint::Videos).is_ok());
+        assert!(tracker.spend(QuotaEndpoint::Videos).is_ok());
+        assert!(matches!(
+            tracker.spend(QuotaEndpoint::PlaylistItems),
+            Err(AppError::QuotaExceeded(_))
+        ));
+
+        let report = tracker.report();
+        assert_eq!(report.total(), 3);
+        assert_eq!(
+            report.usage.get(&QuotaEndpoint::Videos),
+            Some(&QuotaUsage {
+                requests: 2,
+                units: 2
+            })
+        );
+        assert!(!report.usage.contains_key(&QuotaEndpoint::PlaylistItems));
+    }
+
+    #[test]
+    fn carry_over_usage_of_the_day() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("quota_{}.json", std::process::id()));
+
+        let tracker = QuotaTracker::new(10).with_ledger(&path)?;
+        for _ in 0..4 {
+            tracker.spend(QuotaEndpoint::Videos)?;
+        }
+        tracker.save()?;
+
+        let tracker = QuotaTracker::new(10).with_ledger(&path)?;
+        assert_eq!(tracker.report().spent_before, 4);
+        assert_eq!(tracker.report().total(), 4);
+
+        // 前日の記録は引き継がない
+        std::fs::write(&path, r#"{"date":"2000-01-01","units":8}"#)?;
+        let tracker = QuotaTracker::new(10).with_ledger(&path)?;
+        assert_eq!(tracker.report().spent_before, 0);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn add_usage_to_ledger_of_other_runs() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("quota_shared_{}.json", std::process::id()));
+
+        let first = QuotaTracker::new(10).with_ledger(&path)?;
+        let second = QuotaTracker::new(10).with_ledger(&path)?;
+        for _ in 0..3 {
+            first.spend(QuotaEndpoint::Videos)?;
+        }
+        second.spend(QuotaEndpoint::Videos)?;
+        first.save()?;
+        second.save()?;
+        first.spend(QuotaEndpoint::Videos)?;
+        first.save()?;
+
+        let tracker = QuotaTracker::new(10).with_ledger(&path)?;
+        assert_eq!(tracker.report().spent_before, 5);
+        // 書き出すと他の実行の消費量も数える
+        assert_eq!(first.report().total(), 5);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn reset_usage_on_next_day() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("quota_reset_{}.json", std::process::id()));
+        let day = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let next = day.succ_opt().unwrap();
+
+        let tracker = QuotaTracker::new(2).with_ledger(&path)?;
+        tracker.spend_on(QuotaEndpoint::Videos, day)?;
+        tracker.spend_on(QuotaEndpoint::Videos, day)?;
+        tracker.save_on(day)?;
+        assert!(tracker.spend_on(QuotaEndpoint::Videos, day).is_err());
+
+        tracker.spend_on(QuotaEndpoint::Videos, next)?;
+        assert_eq!(tracker.report().total(), 1);
+        tracker.save_on(next)?;
+        assert_eq!(read_ledger(&path, next)?, 1);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn reject_broken_ledger() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("quota_broken_{}.json", std::process::id()));
+
+        // 書きかけのファイルを 0 として読むと、当日の利用枠を数え直してしまう
+        std::fs::write(&path, r#"{"date":"20"#)?;
+        assert!(QuotaTracker::new(10).with_ledger(&path).is_err());
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn keep_usage_of_concurrent_saves() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("quota_race_{}.json", std::process::id()));
+
+        let trackers = (0..4)
+            .map(|_| QuotaTracker::new(1000).with_ledger(&path))
+            .collect::<AppResult<Vec<_>>>()?;
+        std::thread::scope(|s| {
+            for t in trackers.iter() {
+                s.spawn(|| {
+                    for _ in 0..10 {
+                        t.spend(QuotaEndpoint::Videos).unwrap();
+                        t.save().unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(read_ledger(&path, today())?, 40);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -1,63 +1,150 @@
-use crate::adapter::video::VideoEntityConverter;
-use crate::config::YOUTUBE_CLIENT;
+use crate::adapter::comment::CommentThreadConverter;
+use crate::adapter::video::{VideoEntityConverter, availability};
+use crate::config::{AuthMode, YouTubeClient};
+use crate::quota::{QuotaEndpoint, QuotaTracker};
+use crate::retry::{RetryAfterRecorder, RetryPolicy};
+use domains::entities::channel_sync_state::ChannelSyncStateEntity;
 use domains::entities::video::VideoEntity;
+use domains::entities::video_comment::VideoCommentEntity;
+use domains::repositories::external_channel_repository::ExternalChannelRepository;
 use domains::repositories::external_video_repository::ExternalVideoRepository;
 use domains::value_objects::channel_id::ChannelId;
+use domains::value_objects::channel_reference::ChannelReference;
+use domains::value_objects::playlist_id::PlaylistId;
+use domains::value_objects::video_availability::VideoAvailability;
+use domains::value_objects::video_id::VideoId;
 use errors::{AppError, AppResult};
-use google_youtube3::api::{Channel as YouTubeChannel, Video as YouTubeVideo};
-use google_youtube3::hyper::StatusCode;
+use futures::channel::mpsc::Sender;
+use futures::{SinkExt, Stream, TryStreamExt, stream};
+use google_youtube3::api::{
+    Channel as YouTubeChannel, CommentThread as YouTubeCommentThread, Video as YouTubeVideo,
+};
 use google_youtube3::{
     Result as YouTubeResult, YouTube, common, hyper_rustls, hyper_util, yup_oauth2,
 };
 use hyper_rustls::HttpsConnector;
 use hyper_util::client::legacy::connect::HttpConnector;
+use leaky_bucket::RateLimiter;
+use std::collections::HashMap;
 use std::path::Path;
+use std::pin::pin;
+use std::time::{Duration, Instant};
 
 pub struct YoutubeVideoRepository {
     api_client: YouTubeApi,
 }
 
-pub async fn create_youtube_video_repository() -> YoutubeVideoRepository {
+/// 環境変数の設定で YouTube のリポジトリを作る
+pub async fn create_youtube_video_repository() -> AppResult<YoutubeVideoRepository> {
+    let config = YouTubeClient::from_env()?;
+    create_youtube_video_repository_with(&config).await
+}
+
+pub async fn create_youtube_video_repository_with(
+    config: &YouTubeClient,
+) -> AppResult<YoutubeVideoRepository> {
+    let client = hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
+        .build(
+            hyper_rustls::HttpsConnectorBuilder::new()
+                .with_native_roots()
+                .map_err(|e| AppError::InternalServerError(anyhow::anyhow!(e)))?
+                .https_or_http()
+                .enable_http1()
+                .build(),
+        );
+
+    tracing::info!("Create youtube client : {:?}", config.auth_mode);
+    let (mut hub, api_key) = match config.auth_mode {
+        AuthMode::ApiKey => {
+            let key = YouTubeClient::required(&config.api_key, "api_key")?;
+            (YouTube::new(client, common::NoToken), Some(key.to_string()))
+        }
+        AuthMode::ServiceAccount => {
+            let path = YouTubeClient::required(
+                &config.service_account_key_path,
+                "service_account_key_path",
+            )?;
+            let key = yup_oauth2::read_service_account_key(path)
+                .await
+                .map_err(|e| auth_error("Unable to read service account key", e))?;
+            let auth = yup_oauth2::ServiceAccountAuthenticator::builder(key)
+                .build()
+                .await
+                .map_err(|e| auth_error("Unable to build service account authenticator", e))?;
+            (YouTube::new(client, auth), None)
+        }
+        AuthMode::InstalledFlow => (YouTube::new(client, installed_flow(config).await?), None),
+    };
+    if let Some(url) = config.api_base_url.as_deref().filter(|u| !u.is_empty()) {
+        // パスはこの後ろに連結されるため、末尾を `/` に揃える
+        let url = format!("{}/", url.trim_end_matches('/'));
+        tracing::info!("YouTube API base url : {}", url);
+        hub.base_url(url.clone());
+        hub.root_url(url);
+    }
+
+    let mut quota = QuotaTracker::new(config.daily_quota_budget);
+    if let Some(path) = config.quota_usage_path.as_ref().filter(|p| !p.is_empty()) {
+        quota = quota.with_ledger(path)?;
+    }
+    let rps = config.requests_per_second.max(1);
+    let limiter = RateLimiter::builder()
+        .interval(Duration::from_secs(1))
+        .initial(rps)
+        .refill(rps)
+        .max(rps)
+        .build();
+    let api_impl = YouTubeApi {
+        hub,
+        api_key,
+        quota,
+        retry: RetryPolicy::default(),
+        limiter,
+        max_concurrency: config.max_concurrency.max(1),
+    };
+    Ok(YoutubeVideoRepository::new(api_impl))
+}
+
+/// 保存済みのトークンを使い、無ければブラウザでの同意を経て取得する
+async fn installed_flow(
+    config: &YouTubeClient,
+) -> AppResult<yup_oauth2::authenticator::Authenticator<HttpsConnector<HttpConnector>>> {
+    let secret_path = YouTubeClient::required(
+        &config.google_client_secret_path,
+        "google_client_secret_path",
+    )?;
+    let token_path =
+        YouTubeClient::required(&config.persistent_token_path, "persistent_token_path")?;
+
+    // 保存先が読み取り専用の場合があるため、一時ディレクトリに写して使う
     tracing::info!("Read secret token.");
-    let src = Path::new(&YOUTUBE_CLIENT.persistent_token_path);
+    let src = Path::new(token_path);
     let dst = Path::new("/tmp/secret_token.json");
-
     if src.exists() {
-        std::fs::copy(src, dst).expect("Failed to copy secret token file.");
+        std::fs::copy(src, dst).map_err(|e| auth_error("Failed to copy secret token file", e))?;
         tracing::info!("Token copied to temporary directory.");
     } else {
         tracing::warn!("Missing token file.");
     }
 
     tracing::info!("Read application secret");
-    let secret = yup_oauth2::read_application_secret(&YOUTUBE_CLIENT.google_client_secret_path)
+    let secret = yup_oauth2::read_application_secret(secret_path)
         .await
-        .expect("TSSEARCH_GOOGLE_CLIENT_SECRET_PATH FILE NOT FOUND");
+        .map_err(|e| auth_error("Unable to read application secret", e))?;
 
     tracing::info!("Build authenticator.");
-    let auth = yup_oauth2::InstalledFlowAuthenticator::builder(
+    yup_oauth2::InstalledFlowAuthenticator::builder(
         secret,
         yup_oauth2::InstalledFlowReturnMethod::HTTPRedirect,
     )
     .persist_tokens_to_disk(dst)
     .build()
     .await
-    .expect("InstalledFlowAuthenticator: builder");
-
-    let client = hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
-        .build(
-            hyper_rustls::HttpsConnectorBuilder::new()
-                .with_native_roots()
-                .unwrap()
-                .https_or_http()
-                .enable_http1()
-                .build(),
-        );
+    .map_err(|e| auth_error("Unable to build installed flow authenticator", e))
+}
 
-    tracing::info!("Create youtube client");
-    let hub = YouTube::new(client, auth);
-    let api_impl = YouTubeApi { hub };
-    YoutubeVideoRepository::new(api_impl)
+fn auth_error(message: &str, e: impl std::fmt::Display) -> AppError {
+    AppError::UpstreamUnauthorized(format!("{} : {}", message, e))
 }
 
 #[async_trait::async_trait]
@@ -74,45 +161,33 @@ impl ExternalVideoRepository for YoutubeVideoRepository {
         channel_id: &ChannelId,
     ) -> AppResult<Vec<VideoEntity>> {
         tracing::debug!("fetch_all_videos_by_channel_id");
+        let uploads = self.fetch_uploads_playlist_id(channel_id).await?;
+        self.video_batches(&uploads).try_concat().await
+    }
 
-        let mut videos: Vec<VideoEntity> = Vec::new();
-
-        let c = self.api_client.fetch_channel(channel_id).await?;
-        if c.is_none() {
-            AppError::InvalidInput(format!("Channel not found: {}", channel_id));
-        }
-
-        let uploads = c
-            .unwrap_or_default()
-            .content_details
-            .unwrap_or_default()
-            .related_playlists
-            .unwrap_or_default()
-            .uploads
-            .unwrap_or_default();
-
-        let (mut ids, mut next) = self
-            .api_client
-            .fetch_playlist_video_ids(&uploads, 50, None)
-            .await?;
-        let v = self.api_client.fetch_videos(ids).await?;
-        videos.extend(
-            v.into_iter()
-                .map(|v| VideoEntityConverter(v).try_into().unwrap()),
-        );
-
-        while next.is_some() {
-            (ids, next) = self
-                .api_client
-                .fetch_playlist_video_ids(&uploads, 50, next)
-                .await?;
-            let v = self.api_client.fetch_videos(ids).await?;
-            videos.extend(
-                v.into_iter()
-                    .map(|v| VideoEntityConverter(v).try_into().unwrap()),
-            );
+    /// YouTube APIを使用して、指定されたチャンネルIDのすべてのビデオを取得し、
+    /// 再生リストの1ページ分ずつ `sink` へ送ります。
+    ///
+    /// # Arguments
+    /// * `channel_id` - チャンネルID
+    /// * `sink` - ビデオ情報のまとまりの送り先
+    ///
+    /// # Returns
+    /// * `Result<(), String>` - 送り終えたかエラーメッセージ
+    async fn stream_all_videos_by_channel_id(
+        &self,
+        channel_id: &ChannelId,
+        mut sink: Sender<Vec<VideoEntity>>,
+    ) -> AppResult<()> {
+        tracing::debug!("stream_all_videos_by_channel_id");
+        let uploads = self.fetch_uploads_playlist_id(channel_id).await?;
+        let mut batches = pin!(self.video_batches(&uploads));
+        while let Some(v) = batches.try_next().await? {
+            sink.send(v)
+                .await
+                .map_err(|e| AppError::InternalServerError(anyhow::anyhow!(e)))?;
         }
-        Ok(videos)
+        Ok(())
     }
 
     /// YouTube APIを使用して、指定されたチャンネルIDの最新のビデオを取得します。
@@ -128,28 +203,234 @@ impl ExternalVideoRepository for YoutubeVideoRepository {
         max_results: u32,
     ) -> AppResult<Vec<VideoEntity>> {
         tracing::debug!("fetching recent video from api");
-        let c = self.api_client.fetch_channel(channel_id).await?;
-        if c.is_none() {
-            AppError::InvalidInput(format!("Channel not found: {}", channel_id));
-        }
-
-        let uploads = c
-            .unwrap_or_default()
-            .content_details
-            .unwrap_or_default()
-            .related_playlists
-            .unwrap_or_default()
-            .uploads
-            .unwrap_or_default();
+        let uploads = self.fetch_uploads_playlist_id(channel_id).await?;
         let (ids, _) = self
             .api_client
             .fetch_playlist_video_ids(&uploads, max_results, None)
             .await?;
-        let v = self.api_client.fetch_videos(ids).await?;
+        self.fetch_video_entities(ids).await
+    }
+
+    /// YouTube APIを使用して、前回の取得以降に追加・変更されたビデオのみを取得し、
+    /// 再生リストの1ページ分ずつ `sink` へ送ります。ビデオの変更は ETag で判定します。
+    ///
+    /// `count` を指定した場合は直近から、少なくとも `count` 件かつ前回取得済みの公開日時に達するまで確認します。
+    /// 確認し終えた後のページを先取りしないよう、1ページずつ取得します。
+    /// 指定しない場合は全件を並行して確認し、利用枠を使い切るか一時的に利用できないときは、
+    /// 失敗したページを記録して次回そこから再開します。その他のエラーはそのまま返します。
+    ///
+    /// * `channel_id` - チャンネルID
+    /// * `state` - 前回の取得状況
+    /// * `count` - 少なくとも確認する直近のビデオの数
+    /// * `sink` - ビデオ情報のまとまりの送り先
+    ///
+    /// # Returns
+    /// * `Result<ChannelSyncStateEntity, String>` - 更新後の取得状況、またはエラーメッセージ
+    async fn fetch_updated_videos_by_channel_id(
+        &self,
+        channel_id: &ChannelId,
+        state: &ChannelSyncStateEntity,
+        count: Option<u32>,
+        mut sink: Sender<Vec<VideoEntity>>,
+    ) -> AppResult<ChannelSyncStateEntity> {
+        tracing::debug!("fetching updated videos from api : {}", channel_id);
+        let uploads = self.fetch_uploads_playlist_id(channel_id).await?;
+        let max_results = count.map_or(50, |c| c.clamp(1, 50));
+        let concurrency = match count {
+            Some(_) => 1,
+            None => self.api_client.max_concurrency,
+        };
+
+        // 最後に受け取ったページの次のページ。失敗した場合はここから再開する
+        let mut next = state.page_token.clone().filter(|_| count.is_none());
+        let mut state = state.clone();
+        let mut checked = 0;
+        // 初回は直近の `count` 件のみを取得する
+        let first = state.last_published_at.is_none();
+
+        let mut pages = pin!(self.video_pages(&uploads, max_results, next.clone(), concurrency));
+        loop {
+            let (v, n) = match pages.try_next().await {
+                Ok(Some(page)) => page,
+                Ok(None) => break,
+                // 利用枠を使い切った場合と、再試行しても応答の無い場合のみ中断して次回再開する
+                // 認証の失敗などは続けても回復しないため、そのまま返す
+                Err(e @ (AppError::QuotaExceeded(_) | AppError::UpstreamUnavailable(_)))
+                    if count.is_none() =>
+                {
+                    tracing::warn!("Suspend fetching videos of {} : {}", channel_id, e);
+                    state.page_token = next;
+                    return Ok(state);
+                }
+                Err(e) => return Err(e),
+            };
+
+            checked += v.len() as u32;
+            // 取得済みの範囲に達したかは、このページを記録する前の状況で判定する
+            let reached = v.iter().any(|v| state.is_known(v.published_at));
+            let changed = v
+                .into_iter()
+                .filter(|v| state.is_changed(v))
+                .collect::<Vec<_>>();
+            changed.iter().for_each(|v| state.record(v));
+            if !changed.is_empty() {
+                sink.send(changed)
+                    .await
+                    .map_err(|e| AppError::InternalServerError(anyhow::anyhow!(e)))?;
+            }
+
+            next = n;
+            if next.is_none() {
+                break;
+            }
+            if let Some(c) = count
+                && checked >= c
+                && (reached || first)
+            {
+                break;
+            }
+        }
+
+        state.page_token = None;
+        Ok(state)
+    }
+
+    /// YouTube APIを使用して、指定されたビデオIDのトップレベルコメントを取得します。
+    /// commentThreads をページングしながら `max_results` 件まで取得します。
+    ///
+    /// * `video_id` - ビデオID
+    /// * `max_results` - 取得するコメントの最大数
+    ///
+    /// # Returns
+    /// * `Result<Vec<VideoCommentEntity>, String>` - コメントのリストまたはエラーメッセージ
+    async fn fetch_comments_by_video_id(
+        &self,
+        video_id: &VideoId,
+        max_results: u32,
+    ) -> AppResult<Vec<VideoCommentEntity>> {
+        tracing::debug!("fetching comment threads from api : {}", video_id);
+        let mut comments: Vec<VideoCommentEntity> = Vec::new();
+        let mut next: Option<String> = None;
+
+        loop {
+            let remaining = max_results.saturating_sub(comments.len() as u32);
+            if remaining == 0 {
+                break;
+            }
+
+            let (threads, n) = self
+                .api_client
+                .fetch_comment_threads(video_id, remaining.min(100), next)
+                .await?;
+            comments.extend(threads.into_iter().filter_map(|t| {
+                CommentThreadConverter(t)
+                    .try_into()
+                    .inspect_err(|e| tracing::warn!("Skip comment thread : {}", e))
+                    .ok()
+            }));
+
+            next = n;
+            if next.is_none() {
+                break;
+            }
+        }
+
+        Ok(comments)
+    }
+
+    /// YouTube APIを使用して、指定された再生リストのすべてのビデオを取得します。
+    /// 非公開などで取得できなかったビデオは含みません。
+    ///
+    /// * `playlist_id` - 再生リストID
+    ///
+    /// # Returns
+    /// * `Result<Vec<VideoEntity>, String>` - ビデオ情報のリストまたはエラーメッセージ
+    async fn fetch_videos_by_playlist_id(
+        &self,
+        playlist_id: &PlaylistId,
+    ) -> AppResult<Vec<VideoEntity>> {
+        tracing::debug!("fetching playlist videos from api : {}", playlist_id);
+        let mut videos: Vec<VideoEntity> = self.video_batches(playlist_id).try_concat().await?;
+        videos.iter_mut().for_each(|v| v.add_playlist(playlist_id));
+        Ok(videos)
+    }
+
+    /// YouTube APIを使用して、指定されたビデオIDのビデオを取得します。
+    /// videos.list で返らなかったビデオは含みません。
+    ///
+    /// * `video_ids` - ビデオID
+    ///
+    /// # Returns
+    /// * `Result<Vec<VideoEntity>, String>` - ビデオ情報のリストまたはエラーメッセージ
+    async fn fetch_videos_by_ids(&self, video_ids: &[VideoId]) -> AppResult<Vec<VideoEntity>> {
+        tracing::debug!("fetching videos from api : {}", video_ids.len());
+        let mut videos = Vec::new();
+        for ids in video_ids.chunks(50) {
+            let ids = ids.iter().map(|id| id.to_string()).collect();
+            videos.extend(self.fetch_video_entities(ids).await?);
+        }
+        Ok(videos)
+    }
+
+    /// YouTube APIを使用して、指定されたビデオIDの公開状態を取得します。
+    /// videos.list で返らなかったビデオは、削除・非公開などで視聴できないものとします。
+    ///
+    /// * `video_ids` - ビデオID
+    ///
+    /// # Returns
+    /// * `Result<HashMap<VideoId, VideoAvailability>, String>` - ビデオIDごとの公開状態またはエラーメッセージ
+    async fn fetch_video_availabilities(
+        &self,
+        video_ids: &[VideoId],
+    ) -> AppResult<HashMap<VideoId, VideoAvailability>> {
+        tracing::debug!("fetching video statuses from api : {}", video_ids.len());
+        let mut availabilities = video_ids
+            .iter()
+            .map(|id| (id.clone(), VideoAvailability::Unavailable))
+            .collect::<HashMap<_, _>>();
 
-        Ok(v.into_iter()
-            .map(|v| VideoEntityConverter(v).try_into().unwrap())
-            .collect())
+        for ids in video_ids.chunks(50) {
+            let ids = ids.iter().map(|id| id.to_string()).collect();
+            for v in self.api_client.fetch_video_statuses(ids).await? {
+                let Some(id) = v.id.as_deref().and_then(|id| VideoId::new(id).ok()) else {
+                    continue;
+                };
+                let a = v.status.as_ref().map(availability).unwrap_or_default();
+                availabilities.insert(id, a);
+            }
+        }
+        Ok(availabilities)
+    }
+}
+
+#[async_trait::async_trait]
+impl ExternalChannelRepository for YoutubeVideoRepository {
+    /// ハンドル・ユーザー名は channels.list で、動画は videos.list で投稿したチャンネルを引く
+    async fn fetch_channel_id(&self, reference: &ChannelReference) -> AppResult<ChannelId> {
+        let id = match reference {
+            ChannelReference::Id(id) => return Ok(id.clone()),
+            ChannelReference::Handle(handle) => self
+                .api_client
+                .fetch_channel_by_handle(handle)
+                .await?
+                .and_then(|c| c.id),
+            ChannelReference::Username(name) => self
+                .api_client
+                .fetch_channel_by_username(name)
+                .await?
+                .and_then(|c| c.id),
+            ChannelReference::Video(video_id) => self
+                .api_client
+                .fetch_videos(vec![video_id.to_string()])
+                .await?
+                .into_iter()
+                .next()
+                .and_then(|v| v.snippet)
+                .and_then(|s| s.channel_id),
+        };
+        let id = id.ok_or_else(|| AppError::NotFound(format!("Channel of {}", reference)))?;
+        tracing::info!("Resolved {} to {}", reference, id);
+        ChannelId::new(&id)
     }
 }
 
@@ -157,62 +438,154 @@ impl YoutubeVideoRepository {
     pub fn new(api_client: YouTubeApi) -> Self {
         YoutubeVideoRepository { api_client }
     }
+
+    /// API の利用量
+    pub fn quota(&self) -> &QuotaTracker {
+        &self.api_client.quota
+    }
+
+    /// チャンネルのアップロード動画の再生リストID
+    async fn fetch_uploads_playlist_id(&self, channel_id: &ChannelId) -> AppResult<String> {
+        let c = self
+            .api_client
+            .fetch_channel(channel_id)
+            .await?
+            .ok_or(AppError::InvalidInput(format!(
+                "Channel not found: {}",
+                channel_id
+            )))?;
+
+        c.content_details
+            .and_then(|d| d.related_playlists)
+            .and_then(|p| p.uploads)
+            .filter(|u| !u.is_empty())
+            .ok_or(AppError::InvalidInput(format!(
+                "Uploads playlist not found: {}",
+                channel_id
+            )))
+    }
+
+    /// ビデオIDのビデオ
+    async fn fetch_video_entities(&self, ids: Vec<String>) -> AppResult<Vec<VideoEntity>> {
+        self.api_client
+            .fetch_videos(ids)
+            .await?
+            .into_iter()
+            .map(|v| VideoEntityConverter(v).try_into())
+            .collect()
+    }
+
+    /// 再生リストのビデオを1ページ分ずつ、再生リストの順に返す
+    fn video_batches<'a>(
+        &'a self,
+        playlist_id: &'a str,
+    ) -> impl Stream<Item = AppResult<Vec<VideoEntity>>> + 'a {
+        self.video_pages(playlist_id, 50, None, self.api_client.max_concurrency)
+            .map_ok(|(v, _)| v)
+    }
+
+    /// 再生リストのビデオを `page_token` のページから1ページ分ずつ、次のページのトークンとともに返す
+    ///
+    /// ページは順に辿るしかないため、次のページの取得とそれまでのページのビデオ詳細の取得を並行して進める。
+    /// 並行数は `concurrency` まで。
+    fn video_pages<'a>(
+        &'a self,
+        playlist_id: &'a str,
+        max_results: u32,
+        page_token: Option<String>,
+        concurrency: usize,
+    ) -> impl Stream<Item = AppResult<(Vec<VideoEntity>, Option<String>)>> + 'a {
+        // `None` は最後のページまで取得済み、`Some(token)` は次に取得するページ
+        let pages = stream::try_unfold(
+            Some(page_token),
+            move |next: Option<Option<String>>| async move {
+                let Some(token) = next else {
+                    return Ok(None);
+                };
+                let (ids, next) = self
+                    .api_client
+                    .fetch_playlist_video_ids(playlist_id, max_results, token)
+                    .await?;
+                Ok(Some(((ids, next.clone()), next.map(Some))))
+            },
+        );
+        pages
+            .map_ok(
+                move |(ids, next)| async move { Ok((self.fetch_video_entities(ids).await?, next)) },
+            )
+            .try_buffered(concurrency)
+    }
 }
 
 pub struct YouTubeApi {
     hub: YouTube<HttpsConnector<HttpConnector>>,
+    /// API キーで認証する場合のキー
+    api_key: Option<String>,
+    quota: QuotaTracker,
+    retry: RetryPolicy,
+    /// 並行するリクエスト全体で共有する流量の上限
+    limiter: RateLimiter,
+    max_concurrency: usize,
 }
 
 impl YouTubeApi {
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     ///
     /// YouTubeData APIへ、再送処理を含めて特定のリクエストを送るためのラッパ
+    /// 再送も含め、リクエストごとに利用枠を消費する
     ///
-    async fn try_req<R, Fut, F>(&self, req: F) -> YouTubeResult<R>
+    /// 一時的なエラーのみ、回数と経過時間の上限まで待ち時間を伸ばしながら再送する。
+    /// 利用枠・認証・存在しないリソースのエラーは再送せずに返す。
+    /// 送る前に流量の上限に収まるまで待つ。
+    ///
+    async fn try_req<R, Fut, F>(&self, endpoint: QuotaEndpoint, req: F) -> AppResult<R>
     where
-        F: Fn() -> Fut,
+        F: Fn(RetryAfterRecorder) -> Fut,
         Fut: Future<Output = YouTubeResult<(common::Response, R)>>,
     {
-        // 失敗時の待ち時間
-        let mut elapsed = 1;
-
-        // リクエストを送る
-        tokio::time::sleep(tokio::time::Duration::from_secs(elapsed)).await;
-        let ret = req().await;
-        let (mut res, mut v) = ret?;
+        let started = Instant::now();
+        let mut failures = 0;
 
-        // エラーが出てるなら再送処理
         loop {
-            match res.status() {
-                StatusCode::OK => break,
-                _ => {
-                    // 再試行のための待ち時間の倍増
-                    elapsed *= 2;
-                    tokio::time::sleep(tokio::time::Duration::from_secs(elapsed)).await;
-                }
+            self.quota.spend(endpoint)?;
+            self.limiter.acquire_one().await;
+            let recorder = RetryAfterRecorder::default();
+            let e = match req(recorder.clone()).await {
+                Ok((_, v)) => return Ok(v),
+                Err(e) => AppError::from(e),
             };
-            // 1分を超えたら再試行をやめて終了
-            if elapsed > 60 {
-                break;
+
+            failures += 1;
+            if !RetryPolicy::is_retryable(&e) || failures >= self.retry.max_attempts {
+                return Err(e);
+            }
+            let wait = self.retry.delay(failures, recorder.retry_after());
+            if started.elapsed() + wait > self.retry.max_elapsed {
+                return Err(e);
             }
-            // 指定時間待ったうえでリクエストを送信する
-            tracing::debug!("Request retry : wait time {elapsed} sec.");
-            (res, v) = req().await?;
-        }
 
-        Ok(v)
+            tracing::debug!("Request retry {} : wait time {:?} : {}", endpoint, wait, e);
+            tokio::time::sleep(wait).await;
+        }
     }
 
-    pub async fn fetch_channel(
-        &self,
-        channel_id: &ChannelId,
-    ) -> YouTubeResult<Option<YouTubeChannel>> {
+    pub async fn fetch_channel(&self, channel_id: &ChannelId) -> AppResult<Option<YouTubeChannel>> {
+        let key = self.api_key.as_deref();
         let res = self
-            .try_req(|| {
-                self.hub
+            .try_req(QuotaEndpoint::Channels, |mut dlg| async move {
+                let mut req = self
+                    .hub
                     .channels()
                     .list(&vec!["contentDetails".to_string()])
-                    .add_id(channel_id)
-                    .doit()
+                    .add_id(channel_id);
+                if let Some(key) = key {
+                    req = req.param("key", key);
+                }
+                req.delegate(&mut dlg).doit().await
             })
             .await?;
         let items = res.items.unwrap_or_default();
@@ -220,24 +593,70 @@ impl YouTubeApi {
         Ok(c)
     }
 
+    /// ハンドルのチャンネル。`@` は付けても付けなくてもよい
+    pub async fn fetch_channel_by_handle(&self, handle: &str) -> AppResult<Option<YouTubeChannel>> {
+        let key = self.api_key.as_deref();
+        let res = self
+            .try_req(QuotaEndpoint::Channels, |mut dlg| async move {
+                let mut req = self
+                    .hub
+                    .channels()
+                    .list(&vec!["id".to_string()])
+                    .for_handle(handle);
+                if let Some(key) = key {
+                    req = req.param("key", key);
+                }
+                req.delegate(&mut dlg).doit().await
+            })
+            .await?;
+        Ok(res.items.unwrap_or_default().into_iter().next())
+    }
+
+    /// 旧形式のユーザー名のチャンネル
+    pub async fn fetch_channel_by_username(
+        &self,
+        username: &str,
+    ) -> AppResult<Option<YouTubeChannel>> {
+        let key = self.api_key.as_deref();
+        let res = self
+            .try_req(QuotaEndpoint::Channels, |mut dlg| async move {
+                let mut req = self
+                    .hub
+                    .channels()
+                    .list(&vec!["id".to_string()])
+                    .for_username(username);
+                if let Some(key) = key {
+                    req = req.param("key", key);
+                }
+                req.delegate(&mut dlg).doit().await
+            })
+            .await?;
+        Ok(res.items.unwrap_or_default().into_iter().next())
+    }
+
     pub async fn fetch_playlist_video_ids(
         &self,
         playlist_id: &str,
         max_results: u32,
         next_page_token: Option<String>,
-    ) -> YouTubeResult<(Vec<String>, Option<String>)> {
+    ) -> AppResult<(Vec<String>, Option<String>)> {
+        let key = self.api_key.as_deref();
+        let token = next_page_token.as_deref();
         let res = self
-            .try_req(|| {
+            .try_req(QuotaEndpoint::PlaylistItems, |mut dlg| async move {
                 let mut req = self
                     .hub
                     .playlist_items()
                     .list(&vec!["snippet".to_string()])
                     .playlist_id(playlist_id)
                     .max_results(max_results);
-                if next_page_token.is_some() {
-                    req = req.page_token(&next_page_token.clone().unwrap());
+                if let Some(token) = token {
+                    req = req.page_token(token);
                 }
-                req.doit()
+                if let Some(key) = key {
+                    req = req.param("key", key);
+                }
+                req.delegate(&mut dlg).doit().await
             })
             .await?;
 
@@ -257,9 +676,46 @@ impl YouTubeApi {
         Ok((video_ids, res.next_page_token))
     }
 
-    pub async fn fetch_videos(&self, video_ids: Vec<String>) -> YouTubeResult<Vec<YouTubeVideo>> {
+    pub async fn fetch_comment_threads(
+        &self,
+        video_id: &VideoId,
+        max_results: u32,
+        next_page_token: Option<String>,
+    ) -> AppResult<(Vec<YouTubeCommentThread>, Option<String>)> {
+        let key = self.api_key.as_deref();
+        let token = next_page_token.as_deref();
+        let res = self
+            .try_req(QuotaEndpoint::CommentThreads, |mut dlg| async move {
+                let mut req = self
+                    .hub
+                    .comment_threads()
+                    .list(&vec!["snippet".to_string()])
+                    .video_id(video_id)
+                    .order("relevance")
+                    .text_format("plainText")
+                    .max_results(max_results);
+                if let Some(token) = token {
+                    req = req.page_token(token);
+                }
+                if let Some(key) = key {
+                    req = req.param("key", key);
+                }
+                req.delegate(&mut dlg).doit().await
+            })
+            .await?;
+
+        Ok((res.items.unwrap_or_default(), res.next_page_token))
+    }
+
+    pub async fn fetch_videos(&self, video_ids: Vec<String>) -> AppResult<Vec<YouTubeVideo>> {
+        // 空のリクエストでも利用枠を消費するため送らない
+        if video_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let ids = &video_ids;
+        let key = self.api_key.as_deref();
         let res = self
-            .try_req(|| {
+            .try_req(QuotaEndpoint::Videos, |mut dlg| async move {
                 let mut req = self
                     .hub
                     .videos()
@@ -267,14 +723,50 @@ impl YouTubeApi {
                         "snippet".to_string(),
                         "contentDetails".to_string(),
                         "liveStreamingDetails".to_string(),
+                        "status".to_string(),
                     ])
-                    .max_results(video_ids.len() as u32);
+                    .max_results(ids.len() as u32);
 
-                for v in video_ids.iter() {
+                for v in ids.iter() {
                     req = req.add_id(v);
                 }
 
-                req.doit()
+                if let Some(key) = key {
+                    req = req.param("key", key);
+                }
+                req.delegate(&mut dlg).doit().await
+            })
+            .await?;
+
+        Ok(res.items.unwrap_or_default())
+    }
+
+    /// 動画の公開状態のみを取得する。削除・非公開の動画は結果に含まれない
+    pub async fn fetch_video_statuses(
+        &self,
+        video_ids: Vec<String>,
+    ) -> AppResult<Vec<YouTubeVideo>> {
+        if video_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let ids = &video_ids;
+        let key = self.api_key.as_deref();
+        let res = self
+            .try_req(QuotaEndpoint::Videos, |mut dlg| async move {
+                let mut req = self
+                    .hub
+                    .videos()
+                    .list(&vec!["status".to_string()])
+                    .max_results(ids.len() as u32);
+
+                for v in ids.iter() {
+                    req = req.add_id(v);
+                }
+
+                if let Some(key) = key {
+                    req = req.param("key", key);
+                }
+                req.delegate(&mut dlg).doit().await
             })
             .await?;
 
@@ -282,4 +774,23 @@ impl YouTubeApi {
     }
 }
 #[cfg(test)]
-mod unit_tests {}
+mod unit_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reject_missing_credentials() {
+        for auth_mode in [
+            AuthMode::ApiKey,
+            AuthMode::ServiceAccount,
+            AuthMode::InstalledFlow,
+        ] {
+            let config = YouTubeClient {
+                auth_mode,
+                api_key: Some(String::new()),
+                ..YouTubeClient::with_api_key("", "")
+            };
+            let r = create_youtube_video_repository_with(&config).await;
+            assert!(matches!(r, Err(AppError::InvalidInput(_))), "{auth_mode:?}");
+        }
+    }
+}
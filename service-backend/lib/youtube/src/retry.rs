@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use errors::AppError;
+use google_youtube3::common::{Delegate, Response, Retry};
+use google_youtube3::hyper::header::RETRY_AFTER;
+use rand::Rng;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 失敗したリクエストの再試行の方針
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// 最初のリクエストを含めた試行回数の上限
+    pub max_attempts: u32,
+    /// 最初の再試行までの待ち時間
+    pub base_delay: Duration,
+    /// 1回あたりの待ち時間の上限
+    pub max_delay: Duration,
+    /// 最初のリクエストからの経過時間の上限
+    pub max_elapsed: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(32),
+            max_elapsed: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 時間をおけば成功しうるエラーか
+    pub fn is_retryable(e: &AppError) -> bool {
+        matches!(e, AppError::UpstreamUnavailable(_))
+    }
+
+    /// `failures` 回失敗した後の待ち時間
+    ///
+    /// `Retry-After` が返されていればそれに従い、無ければ指数的に伸ばした時間の後半で揺らす。
+    pub fn delay(&self, failures: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(d) = retry_after {
+            return d;
+        }
+        let exp = 2u32.saturating_pow(failures.saturating_sub(1));
+        let cap = self.base_delay.saturating_mul(exp).min(self.max_delay);
+        let half = cap / 2;
+        half + half.mul_f64(rand::rng().random_range(0.0..=1.0))
+    }
+}
+
+/// 失敗したレスポンスの `Retry-After` を記録する
+///
+/// 再試行は `YouTubeApi` 側で行うため、ライブラリには再試行させない。
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RetryAfterRecorder(Arc<Mutex<Option<Duration>>>);
+
+impl RetryAfterRecorder {
+    pub(crate) fn retry_after(&self) -> Option<Duration> {
+        *self.0.lock().unwrap()
+    }
+}
+
+impl Delegate for RetryAfterRecorder {
+    fn http_failure(&mut self, res: &Response, _: Option<&serde_json::Value>) -> Retry {
+        *self.0.lock().unwrap() = res
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| parse_retry_after(v, Utc::now()));
+        Retry::Abort
+    }
+}
+
+/// `Retry-After` は秒数か HTTP 日付
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    if let Ok(secs) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    Some((at.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use google_youtube3::Error as YouTubeError;
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn error(code: u16, reason: &str) -> AppError {
+        AppError::from(YouTubeError::BadRequest(json!({
+            "error": {
+                "code": code,
+                "message": "message",
+                "errors": [{"reason": reason, "domain": "youtube.quota"}]
+            }
+        })))
+    }
+
+    #[rstest]
+    #[case::quota(403, "quotaExceeded", "quota")]
+    #[case::daily_limit(403, "dailyLimitExceeded", "quota")]
+    #[case::rate_limit(403, "rateLimitExceeded", "transient")]
+    #[case::unauthorized(401, "authError", "auth")]
+    #[case::permissions(403, "insufficientPermissions", "auth")]
+    #[case::not_found(404, "videoNotFound", "not_found")]
+    #[case::server_error(503, "backendError", "transient")]
+    #[case::comments_disabled(403, "commentsDisabled", "other")]
+    fn classify_errors(#[case] code: u16, #[case] reason: &str, #[case] expected: &str) {
+        let class = match error(code, reason) {
+            AppError::QuotaExceeded(_) => "quota",
+            AppError::UpstreamUnauthorized(_) => "auth",
+            AppError::NotFound(_) => "not_found",
+            AppError::UpstreamUnavailable(_) => "transient",
+            _ => "other",
+        };
+        assert_eq!(class, expected);
+        assert_eq!(
+            RetryPolicy::is_retryable(&error(code, reason)),
+            expected == "transient"
+        );
+    }
+
+    #[test]
+    fn backoff_delay() {
+        let policy = RetryPolicy::default();
+        for failures in 1..10 {
+            let cap = Duration::from_secs(2u64.pow(failures - 1)).min(policy.max_delay);
+            let d = policy.delay(failures, None);
+            assert!(cap / 2 <= d && d <= cap, "{failures} : {d:?}");
+        }
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(7))),
+            Duration::from_secs(7)
+        );
+    }
+
+    #[rstest]
+    #[case("120", Some(120))]
+    #[case("Wed, 21 Oct 2015 07:28:30 GMT", Some(30))]
+    #[case("Wed, 21 Oct 2015 07:27:00 GMT", Some(0))]
+    #[case("soon", None)]
+    fn retry_after(#[case] value: &str, #[case] expected: Option<u64>) {
+        let now = DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            parse_retry_after(value, now),
+            expected.map(Duration::from_secs)
+        );
+    }
+}
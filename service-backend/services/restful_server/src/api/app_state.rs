@@ -1,17 +1,28 @@
 use crate::api::service::TimeStampSearchService;
 use leaky_bucket::RateLimiter;
 use std::sync::{Arc, RwLock};
+use usecase::song_performance_service::SongPerformanceService;
+use usecase::video_timestamp_service::VideoTimeStampService;
 
 #[derive(Clone)]
 pub struct AppState {
     pub timestamp_search: Box<TimeStampSearchService>,
+    pub song_performance: Box<SongPerformanceService>,
+    pub video_timestamp: Box<VideoTimeStampService>,
     pub limiter: Arc<RwLock<RateLimiter>>,
 }
 
 impl AppState {
-    pub fn new(timestamp_search: TimeStampSearchService, limiter: RateLimiter) -> Self {
+    pub fn new(
+        timestamp_search: TimeStampSearchService,
+        song_performance: SongPerformanceService,
+        video_timestamp: VideoTimeStampService,
+        limiter: RateLimiter,
+    ) -> Self {
         Self {
             timestamp_search: Box::new(timestamp_search),
+            song_performance: Box::new(song_performance),
+            video_timestamp: Box::new(video_timestamp),
             limiter: Arc::new(RwLock::new(limiter)),
         }
     }
@@ -1,2 +1,4 @@
 pub mod health;
+pub mod song_performance;
 pub mod timestamp_search;
+pub mod video_timestamps;
@@ -0,0 +1,25 @@
+use crate::api::app_state::AppState;
+use crate::api::response::SongPerformancesResponse;
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::response::{IntoResponse, Response};
+
+/// 同じ曲の歌唱回を動画をまたいで取得する
+pub async fn song_performances(
+    State(state): State<AppState>,
+    Path(title): Path<String>,
+) -> Result<Json<SongPerformancesResponse>, Response> {
+    tracing::debug!("song title : {}", title);
+    let r = state.song_performance.performances(&title).await;
+
+    match r {
+        Ok(r) => {
+            tracing::debug!("song performances succeed.");
+            Ok(Json(r.into()))
+        }
+        Err(e) => {
+            tracing::error!("Song performances failed");
+            Err(e.into_response())
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::api::app_state::AppState;
+use crate::api::request::VideoTimeStampsRequest;
+use crate::api::response::{VideoTimeStampTreeResponse, VideoTimeStampsResponse};
+use axum::Json;
+use axum::extract::{Path, Query, State};
+use axum::response::{IntoResponse, Response};
+use domains::value_objects::video_id::VideoId;
+
+/// 動画のタイムスタンプ一覧。`tree=true` の場合は見出しごとに入れ子にして返す
+pub async fn video_timestamps(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<VideoTimeStampsRequest>,
+) -> Result<Response, Response> {
+    let video_id = VideoId::new(&id).map_err(|e| {
+        tracing::error!("Invalid video id : {}", id);
+        e.into_response()
+    })?;
+
+    let r = if query.tree.unwrap_or(false) {
+        state
+            .video_timestamp
+            .timestamp_tree(&video_id)
+            .await
+            .map(|v| Json(VideoTimeStampTreeResponse::from((video_id, v))).into_response())
+    } else {
+        state
+            .video_timestamp
+            .timestamps(&video_id)
+            .await
+            .map(|v| Json(VideoTimeStampsResponse::from((video_id, v))).into_response())
+    };
+
+    match r {
+        Ok(r) => {
+            tracing::debug!("video timestamps succeed.");
+            Ok(r)
+        }
+        Err(e) => {
+            tracing::error!("Video timestamps failed");
+            Err(e.into_response())
+        }
+    }
+}
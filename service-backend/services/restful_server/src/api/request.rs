@@ -2,10 +2,16 @@ use chrono::{DateTime, Utc};
 use domains::repositories::internal_timestamp_search_repository::{
     Part, VideoTimestampSearchQuery,
 };
+use domains::value_objects::channel_id::ChannelId;
+use domains::value_objects::confidence::Confidence;
+use domains::value_objects::elapsed_time::ElapsedTime;
 use domains::value_objects::limit::Limit;
 use domains::value_objects::page::Page;
 use domains::value_objects::per_page::PerPage;
+use domains::value_objects::playlist_id::PlaylistId;
 use domains::value_objects::search_query_text::SearchQueryText;
+use domains::value_objects::song_artist::SongArtist;
+use domains::value_objects::video_duration::VideoDuration;
 use domains::value_objects::{video_id::VideoId, video_tag::VideoTag};
 use garde::Validate;
 use serde::Deserialize;
@@ -17,9 +23,18 @@ use std::str::FromStr;
 /// - keyword:    検索キーワード
 /// - ids:        ビデオID(option)
 /// - tags:       タグ検索(option)
+/// - channel_id: チャンネルID(option)
+/// - playlist_id: 再生リストID(option)
 /// - actual_start_time_from  : 配信開始時間区間指定（開始）(option)
 /// - actual_start_time_to    : 配信開始時間区間指定（終端）(option)
 /// - actual_start_time_at    : 配信開始時間区間指定（指定）(option)
+/// - elapsed_from            : 動画内の経過時間区間指定（開始, 秒）(option)
+/// - elapsed_to              : 動画内の経過時間区間指定（終端, 秒）(option)
+/// - min_video_length        : 動画の長さの下限（秒）(option)
+/// - max_video_length        : 動画の長さの上限（秒）(option)
+/// - artist                  : セットリストのアーティスト名（完全一致）(option)
+/// - min_confidence          : 抽出の確からしさの下限（0 ~ 100）(option)
+/// - include_unavailable     : 削除・非公開などで視聴できない動画も含める (option)
 /// - page                    : 頁数
 /// - per_page                : ページ毎の数
 ///
@@ -34,6 +49,10 @@ pub struct SearchTimeStampRequest {
     #[garde(skip)]
     pub tags: Option<Vec<VideoTag>>,
     #[garde(skip)]
+    pub channel_id: Option<ChannelId>,
+    #[garde(skip)]
+    pub playlist_id: Option<PlaylistId>,
+    #[garde(skip)]
     #[serde(rename = "startFrom")]
     pub actual_start_from: Option<DateTime<Utc>>,
     #[garde(skip)]
@@ -43,6 +62,20 @@ pub struct SearchTimeStampRequest {
     #[serde(rename = "startAt")]
     pub actual_start_at: Option<DateTime<Utc>>,
     #[garde(skip)]
+    pub elapsed_from: Option<ElapsedTime>,
+    #[garde(skip)]
+    pub elapsed_to: Option<ElapsedTime>,
+    #[garde(skip)]
+    pub min_video_length: Option<VideoDuration>,
+    #[garde(skip)]
+    pub max_video_length: Option<VideoDuration>,
+    #[garde(skip)]
+    pub artist: Option<SongArtist>,
+    #[garde(range(max = 100))]
+    pub min_confidence: Option<u8>,
+    #[garde(skip)]
+    pub include_unavailable: Option<bool>,
+    #[garde(skip)]
     pub parts: Option<String>,
     #[garde(range(min = 1, max = 1000))]
     pub page: Option<usize>,
@@ -50,6 +83,16 @@ pub struct SearchTimeStampRequest {
     pub per_page: Option<usize>,
 }
 
+///
+/// 動画のタイムスタンプ一覧リクエスト
+/// # Params
+/// - tree : 見出しごとに入れ子にして返す (option)
+///
+#[derive(Deserialize, Debug, Clone)]
+pub struct VideoTimeStampsRequest {
+    pub tree: Option<bool>,
+}
+
 ///
 /// 受け取ったリクエストを元にドメイン層の検索クエリに変換
 /// # Params
@@ -81,9 +124,21 @@ impl TryFrom<SearchTimeStampRequest> for VideoTimestampSearchQuery {
             query: SearchQueryText::new(&search_time_stamp.keyword).unwrap(),
             video_ids: search_time_stamp.ids,
             video_tags: search_time_stamp.tags,
+            channel_id: search_time_stamp.channel_id,
+            playlist_id: search_time_stamp.playlist_id,
             actual_start_from: search_time_stamp.actual_start_from,
             actual_start_to: search_time_stamp.actual_start_to,
             actual_start_at: search_time_stamp.actual_start_at,
+            elapsed_time_from: search_time_stamp.elapsed_from,
+            elapsed_time_to: search_time_stamp.elapsed_to,
+            video_duration_min: search_time_stamp.min_video_length,
+            video_duration_max: search_time_stamp.max_video_length,
+            artist: search_time_stamp.artist,
+            min_confidence: search_time_stamp
+                .min_confidence
+                .map(Confidence::new)
+                .transpose()?,
+            include_unavailable: search_time_stamp.include_unavailable.unwrap_or(false),
             parts,
             limit: Limit::new(1000)?,
             page: Page::new(search_time_stamp.page.unwrap_or(1))?,
@@ -2,11 +2,13 @@ use chrono::{DateTime, Utc};
 use domains::entities::video_timestamp::VideoTimestampEntity;
 use domains::repositories::internal_timestamp_search_repository::VideoTimestampSearchResult;
 use domains::value_objects::{
-    elapsed_time::ElapsedTime, thumbnail_url::ThumbnailUrl,
-    timestamp_description::TimeStampDescription, video_id::VideoId, video_tag::VideoTag,
-    video_title::VideoTitle,
+    channel_id::ChannelId, confidence::Confidence, elapsed_time::ElapsedTime,
+    song_artist::SongArtist, song_key::SongKey, song_tag::SongTag, song_title::SongTitle,
+    thumbnail_url::ThumbnailUrl, timestamp_id::TimestampId, video_duration::VideoDuration,
+    video_id::VideoId, video_tag::VideoTag, video_title::VideoTitle,
 };
 use serde::Serialize;
+use usecase::video_timestamp_service::TimeStampNode;
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -18,12 +20,63 @@ pub struct SearchTimeStampResponse {
     total_hits: usize,
 }
 
+/// 同じ曲の歌唱回一覧
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SongPerformancesResponse {
+    song_key: SongKey,
+    items: Vec<ResponseTimeStamp>,
+    total_hits: usize,
+}
+
+/// 動画のタイムスタンプ一覧
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoTimeStampsResponse {
+    video_id: VideoId,
+    items: Vec<ResponseTimeStamp>,
+}
+
+/// 動画のタイムスタンプ一覧（見出しごとの入れ子）
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoTimeStampTreeResponse {
+    video_id: VideoId,
+    items: Vec<ResponseTimeStampNode>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseTimeStampNode {
+    #[serde(flatten)]
+    timestamp: ResponseTimeStamp,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<ResponseTimeStampNode>,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResponseTimeStamp {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<TimestampId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent_id: Option<TimestampId>,
     video_id: VideoId,
     elapsed_time: ElapsedTime,
-    description: TimeStampDescription,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_time: Option<ElapsedTime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<ElapsedTime>,
+    /// 表示用に整えた説明文
+    description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    song_title: Option<SongTitle>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    artist: Option<SongArtist>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    song_tags: Vec<SongTag>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    confidence: Option<Confidence>,
     #[serde(skip_serializing_if = "Option::is_none")]
     video_details: Option<ResponseTimeStampVideoDetails>,
 }
@@ -41,6 +94,10 @@ pub struct ResponseTimeStampVideoDetails {
     published_at: Option<DateTime<Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     actual_start_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<VideoDuration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channel_id: Option<ChannelId>,
 }
 
 impl From<VideoTimestampSearchResult> for SearchTimeStampResponse {
@@ -57,12 +114,62 @@ impl From<VideoTimestampSearchResult> for SearchTimeStampResponse {
     }
 }
 
+impl From<(SongKey, Vec<VideoTimestampEntity>)> for SongPerformancesResponse {
+    fn from((song_key, items): (SongKey, Vec<VideoTimestampEntity>)) -> Self {
+        let items: Vec<ResponseTimeStamp> = items.into_iter().map(|e| e.into()).collect();
+        SongPerformancesResponse {
+            song_key,
+            total_hits: items.len(),
+            items,
+        }
+    }
+}
+
+impl From<(VideoId, Vec<VideoTimestampEntity>)> for VideoTimeStampsResponse {
+    fn from((video_id, items): (VideoId, Vec<VideoTimestampEntity>)) -> Self {
+        VideoTimeStampsResponse {
+            video_id,
+            items: items.into_iter().map(|e| e.into()).collect(),
+        }
+    }
+}
+
+impl From<(VideoId, Vec<TimeStampNode>)> for VideoTimeStampTreeResponse {
+    fn from((video_id, items): (VideoId, Vec<TimeStampNode>)) -> Self {
+        VideoTimeStampTreeResponse {
+            video_id,
+            items: items.into_iter().map(|n| n.into()).collect(),
+        }
+    }
+}
+
+impl From<TimeStampNode> for ResponseTimeStampNode {
+    fn from(node: TimeStampNode) -> Self {
+        ResponseTimeStampNode {
+            timestamp: node.timestamp.into(),
+            children: node.children.into_iter().map(|n| n.into()).collect(),
+        }
+    }
+}
+
 impl From<VideoTimestampEntity> for ResponseTimeStamp {
     fn from(entity: VideoTimestampEntity) -> Self {
         ResponseTimeStamp {
+            id: entity.id().ok(),
+            parent_id: entity.parent_id.clone(),
             video_id: entity.video_id.clone(),
             elapsed_time: entity.timestamp.elapsed_time,
-            description: entity.timestamp.description.clone(),
+            end_time: entity.timestamp.end_time,
+            duration: entity.timestamp.duration(),
+            description: entity.timestamp.description.display(),
+            song_title: entity.song.as_ref().map(|s| s.title.clone()),
+            artist: entity.song.as_ref().and_then(|s| s.artist.clone()),
+            song_tags: entity
+                .song
+                .as_ref()
+                .map(|s| s.tags.clone())
+                .unwrap_or_default(),
+            confidence: entity.confidence,
             video_details: ResponseTimeStampVideoDetails::try_from(entity).ok(),
         }
     }
@@ -78,6 +185,8 @@ impl TryFrom<VideoTimestampEntity> for ResponseTimeStampVideoDetails {
                 tags: detail.video_tags,
                 published_at: detail.published_at,
                 actual_start_at: detail.actual_start_at,
+                duration: detail.duration,
+                channel_id: detail.channel_id,
             });
         };
 
@@ -1,13 +1,17 @@
 use crate::api::app_state::AppState;
 use crate::api::handle::health::health_check;
+use crate::api::handle::song_performance::song_performances;
 use crate::api::handle::timestamp_search::search_timestamp;
+use crate::api::handle::video_timestamps::video_timestamps;
 use axum::Router;
 use axum::routing::get;
 
 pub fn router() -> Router<AppState> {
     let router = Router::new()
         .merge(build_health_check())
-        .merge(build_timestamp_search());
+        .merge(build_timestamp_search())
+        .merge(build_song_performances())
+        .merge(build_video_timestamps());
 
     Router::new().nest("/api/v1", router)
 }
@@ -20,6 +24,14 @@ fn build_timestamp_search() -> Router<AppState> {
     Router::new().route("/timestamp/search", get(search_timestamp))
 }
 
+fn build_song_performances() -> Router<AppState> {
+    Router::new().route("/songs/{title}/performances", get(song_performances))
+}
+
+fn build_video_timestamps() -> Router<AppState> {
+    Router::new().route("/videos/{id}/timestamps", get(video_timestamps))
+}
+
 #[cfg(test)]
 mod unit_tests {
     use super::*;
@@ -32,15 +44,21 @@ mod unit_tests {
         http::{Method, StatusCode},
         middleware,
     };
+    use domains::entities::video_timestamp::VideoTimestampEntity;
     use domains::repositories::internal_timestamp_search_repository::{
         InternalVideoTimeStampSearchRepository, VideoTimestampSearchQuery,
         VideoTimestampSearchResult,
     };
+    use domains::value_objects::limit::Limit;
     use domains::value_objects::page::Page;
     use domains::value_objects::per_page::PerPage;
+    use domains::value_objects::song_key::SongKey;
+    use domains::value_objects::video_id::VideoId;
     use errors::AppResult;
     use leaky_bucket::RateLimiter;
     use tower::ServiceExt;
+    use usecase::song_performance_service::SongPerformanceService;
+    use usecase::video_timestamp_service::VideoTimeStampService;
 
     pub struct TestVideoTimeStampSearchRepository {}
 
@@ -58,13 +76,31 @@ mod unit_tests {
                 total_hits: 0,
             })
         }
+
+        async fn find_timestamps_by_song_key(
+            &self,
+            _: &SongKey,
+            _: Limit,
+        ) -> AppResult<Vec<VideoTimestampEntity>> {
+            Ok(vec![])
+        }
+
+        async fn find_timestamps_by_video_id(
+            &self,
+            _: &VideoId,
+            _: Limit,
+        ) -> AppResult<Vec<VideoTimestampEntity>> {
+            Ok(vec![])
+        }
     }
 
     #[tokio::test]
     #[tracing_test::traced_test]
     async fn route_path_test() {
         let ts = Arc::new(TestVideoTimeStampSearchRepository {});
-        let service = TimeStampSearchService::new(ts);
+        let service = TimeStampSearchService::new(ts.clone());
+        let song_performance = SongPerformanceService::new(ts.clone());
+        let video_timestamp = VideoTimeStampService::new(ts);
 
         let limiter = RateLimiter::builder()
             .interval(core::time::Duration::from_secs(100))
@@ -72,7 +108,7 @@ mod unit_tests {
             .refill(50)
             .max(1000)
             .build();
-        let state = AppState::new(service, limiter);
+        let state = AppState::new(service, song_performance, video_timestamp, limiter);
 
         let app = router()
             .layer(middleware::from_fn_with_state(state.clone(), use_backet))
@@ -98,5 +134,70 @@ mod unit_tests {
 
         let res = app.clone().oneshot(req).await.unwrap();
         assert_eq!(res.status(), StatusCode::OK);
+
+        tracing::debug!("Request test : /api/v1/timestamp/search with elapsed time range");
+        let req = axum::http::Request::builder()
+            .method(Method::GET)
+            .uri("/api/v1/timestamp/search?q=text&elapsedFrom=60&elapsedTo=600")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        tracing::debug!("Request test : /api/v1/timestamp/search with artist");
+        let req = axum::http::Request::builder()
+            .method(Method::GET)
+            .uri("/api/v1/timestamp/search?q=text&artist=Artist")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        tracing::debug!("Request test : /api/v1/timestamp/search with minConfidence");
+        let req = axum::http::Request::builder()
+            .method(Method::GET)
+            .uri("/api/v1/timestamp/search?q=text&minConfidence=60")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        tracing::debug!("Request test : /api/v1/timestamp/search with channelId");
+        let req = axum::http::Request::builder()
+            .method(Method::GET)
+            .uri("/api/v1/timestamp/search?q=text&channelId=UC_x5XG1OV2P6uZZ5FSM9Ttw")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        tracing::debug!("Request test : /api/v1/songs/{{title}}/performances");
+        let req = axum::http::Request::builder()
+            .method(Method::GET)
+            .uri("/api/v1/songs/songtitle/performances")
+            .body(Body::empty())
+            .unwrap();
+
+        let res = app.clone().oneshot(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        tracing::debug!("Request test : /api/v1/videos/{{id}}/timestamps");
+        for uri in [
+            "/api/v1/videos/abcdefghijk/timestamps",
+            "/api/v1/videos/abcdefghijk/timestamps?tree=true",
+        ] {
+            let req = axum::http::Request::builder()
+                .method(Method::GET)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap();
+
+            let res = app.clone().oneshot(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
     }
 }
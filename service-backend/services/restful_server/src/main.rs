@@ -8,6 +8,8 @@ use leaky_bucket::RateLimiter;
 use std::sync::Arc;
 use tower_http::cors::CorsLayer;
 use tracing_subscriber::EnvFilter;
+use usecase::song_performance_service::SongPerformanceService;
+use usecase::video_timestamp_service::VideoTimeStampService;
 
 mod api;
 
@@ -30,7 +32,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         meilisearch::repositories::timestamp_search::create_meilisearch_timestamp_search_repository(
         ),
     );
-    let service = TimeStampSearchService::new(ts);
+    let service = TimeStampSearchService::new(ts.clone());
+    let song_performance = SongPerformanceService::new(ts.clone());
+    let video_timestamp = VideoTimeStampService::new(ts);
 
     tracing::trace!("Initialize RateLimiter.");
     let limiter = RateLimiter::builder()
@@ -42,7 +46,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing::trace!("RateLimiter initialized. : {:?}", limiter);
 
     tracing::trace!("Initialize AppState.");
-    let state = AppState::new(service, limiter);
+    let state = AppState::new(service, song_performance, video_timestamp, limiter);
     tracing::trace!("AppState initialized.");
 
     tracing::trace!("Initialize Router.");
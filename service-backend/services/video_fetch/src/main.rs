@@ -1,18 +1,55 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use domains::entities::channel::ChannelEntity;
+use domains::entities::registered_channel::RegisteredChannelEntity;
 use domains::entities::video::VideoEntity;
+use domains::entities::video_comment::VideoCommentEntity;
+use domains::entities::video_timestamp::VideoTimestampEntity;
+use domains::repositories::external_channel_repository::ExternalChannelRepository;
+use domains::repositories::external_video_repository::ExternalVideoRepository;
+use domains::repositories::internal_timestamp_repository::InternalVideoTimeStampRepository;
+use domains::repositories::internal_video_repository::InternalVideoRepository;
 use domains::value_objects::channel_id::ChannelId;
+use domains::value_objects::channel_name::ChannelName;
+use domains::value_objects::confidence::Confidence;
+use domains::value_objects::playlist_id::PlaylistId;
+use domains::value_objects::timestamp_source::TimeStampSource;
+use domains::value_objects::video_id::VideoId;
 use errors::{AppError, AppResult};
+use futures::StreamExt;
+use futures::channel::mpsc;
 use meilisearch::repositories::{
+    channel_crud::create_channel_crud_repository,
+    sync_state_crud::create_sync_state_crud_repository,
     timestamp_crud::create_timestamp_crud_repository, video_crud::create_video_crud_repository,
 };
+use std::collections::HashMap;
 use std::fs::File;
 use std::sync::Arc;
 use tracing_subscriber::EnvFilter;
+use usecase::broadcast_revisit_service::BroadcastRevisitService;
+use usecase::channel_registry_service::ChannelRegistryService;
+use usecase::channel_resolve_service::ChannelResolveService;
+use usecase::channel_sync_service::ChannelSyncService;
+use usecase::parse_report_service::ParseReportService;
+use usecase::setlist_parser_service::SetlistParserService;
 use usecase::timestamp_indexing_service::TimeStampIndexingService;
+use usecase::timestamp_merge_service::TimeStampMergeService;
+use usecase::timestamp_parser_rule::TimeStampParserRules;
 use usecase::timestamp_parser_service::TimeStampParserService;
+use usecase::timestamp_reindex_service::TimeStampReindexService;
+use usecase::timestamp_segment_service::TimeStampSegmentService;
 use usecase::video_fetch_service::VideoFetchService;
 use usecase::video_indexing_service::VideoIndexingService;
-use youtube::repositories::youtube_video::create_youtube_video_repository;
+use usecase::video_reconcile_service::VideoReconcileService;
+use websub::{WebSubConfig, WebSubState};
+use youtube::repositories::youtube_video::{
+    YoutubeVideoRepository, create_youtube_video_repository,
+};
+
+mod websub;
+
+/// 通知を受けてから取り込むまでに溜めておける動画数
+const WEBSUB_QUEUE_SIZE: usize = 1000;
 
 #[derive(Parser, Debug)]
 struct Args {
@@ -33,6 +70,69 @@ struct Args {
 
     #[arg(short, long, conflicts_with = "out_internal")]
     pub out_json: Option<String>,
+
+    #[arg(long, default_value = "false")]
+    pub skip_comments: bool,
+
+    #[arg(long, default_value = "100")]
+    pub num_comments: u32,
+
+    /// チャンネルごとのタイムスタンプ抽出ルール（TOML）
+    #[arg(long)]
+    pub parser_rules: Option<String>,
+
+    /// 取得対象のチャンネルの登録ファイル（TOML）。
+    /// 指定しない場合はチャンネルのインデックスに登録済みのチャンネルを取得する
+    #[arg(long)]
+    pub channels: Option<String>,
+
+    /// 取得対象のチャンネル（カンマ区切り）。チャンネルID・@handle・チャンネルや動画の URL で指定する。
+    /// 指定した場合は登録ファイル・登録済みのチャンネルを使わない
+    #[arg(long, value_delimiter = ',', conflicts_with = "channels")]
+    pub channel: Vec<String>,
+
+    /// 保存済みの取得状況を使わずに取得し直す。`--out-internal` の場合のみ有効
+    #[arg(long, default_value = "false")]
+    pub full_sync: bool,
+
+    /// チャンネルの動画に加えて取り込む再生リストのID（カンマ区切り）。
+    /// 指定した場合は、対象のチャンネルが無くても再生リストのみを取り込む
+    #[arg(long, value_delimiter = ',')]
+    pub playlists: Vec<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// 保存済みの動画を解析し直し、動画ごとの抽出結果の集計を出力する
+    Report {
+        /// これを下回る確からしさを誤検出の疑いとして数える
+        #[arg(long, default_value = "50")]
+        min_confidence: u8,
+    },
+    /// 保存済みの動画を取得元と照合し、削除・非公開になった動画を検索から除く
+    Reconcile,
+    /// 保存済みのタイムスタンプを現在のIDと検索用の項目で保存し直す。
+    /// IDの作り方や検索用の項目を変えた後に一度実行する
+    Reindex,
+    /// 配信予定・配信中の動画と終了して間もない配信を取得し直し、タイムスタンプを抽出し直す。
+    /// 定期的に実行する
+    Revisit {
+        /// 配信の終了後に取得し直す日数
+        #[arg(long, default_value = "7")]
+        days: u32,
+    },
+    /// @handle やチャンネル・動画の URL をチャンネルIDに解決して出力する
+    Resolve {
+        /// チャンネルID・@handle・チャンネルや動画の URL
+        #[arg(required = true)]
+        references: Vec<String>,
+    },
+    /// WebSub の通知を受け付け、追加・更新された動画をその都度取り込む。
+    /// `TSS_WEBSUB_PORT` で待ち受け、`TSS_WEBSUB_SECRET` で通知の署名を検証する
+    Websub,
 }
 
 #[tokio::main]
@@ -43,25 +143,138 @@ async fn main() -> AppResult<()> {
         .init();
 
     let args = Args::parse();
-    let videos = if args.in_external {
+    let mut rules = match args.parser_rules.as_ref() {
+        Some(path) => TimeStampParserRules::load(path)?,
+        None => TimeStampParserRules::default(),
+    };
+
+    match args.command {
+        Some(Command::Report { min_confidence }) => {
+            return report(rules, Confidence::new(min_confidence)?).await;
+        }
+        Some(Command::Reconcile) => return reconcile().await,
+        Some(Command::Reindex) => return reindex().await,
+        Some(Command::Revisit { days }) => return revisit(&args, rules, days).await,
+        Some(Command::Resolve { ref references }) => return resolve(references).await,
+        Some(Command::Websub) => return websub(&args, rules).await,
+        None => {}
+    }
+
+    let playlists = args
+        .playlists
+        .iter()
+        .map(|p| PlaylistId::new(p))
+        .collect::<AppResult<Vec<_>>>()?;
+    let ext_repo = match args.in_external {
+        true => Some(Arc::new(create_youtube_video_repository().await?)),
+        false => None,
+    };
+    let channels = match ext_repo.as_ref() {
+        Some(ext_repo) => match target_channels(&args, ext_repo).await {
+            Ok(c) => c,
+            // 登録済みのチャンネルが無い場合（チャンネルのインデックスが未作成の場合も含む）は、再生リストのみを取り込む
+            Err(AppError::InvalidInput(e)) if !playlists.is_empty() => {
+                tracing::warn!("Ingest playlists only : {}", e);
+                vec![]
+            }
+            Err(e) => return Err(e),
+        },
+        None => vec![],
+    };
+    for c in channels.iter() {
+        if let Some(profile) = c.parser_profile.as_ref() {
+            rules.assign_profile(&c.channel.id, profile)?;
+        }
+    }
+    let mut indexer = args.out_internal.then(|| {
+        Indexer::new(
+            rules,
+            Arc::new(create_video_crud_repository()),
+            Arc::new(create_timestamp_crud_repository()),
+        )
+    });
+
+    let mut comments: HashMap<VideoId, Vec<VideoCommentEntity>> = HashMap::new();
+    // 最後まで取得できた再生リスト。所属をこの内容に置き換える
+    let mut fetched_playlists = vec![];
+    let videos = if let Some(ext_repo) = ext_repo {
         tracing::info!("Load video from external service.");
-        let channel_id =
-            std::env::var("TSS_TARGET_CHANNEL_ID").expect("TSSEARCH_TARGET_CHANNEL_ID");
-        let ext_repo = Arc::new(create_youtube_video_repository().await);
         let video_fetch_service = VideoFetchService::new(ext_repo.clone());
-        let channel_id = ChannelId::new(&channel_id)?;
+        let sync_service = ChannelSyncService::new(
+            ext_repo.clone(),
+            Arc::new(create_sync_state_crud_repository()),
+        );
 
-        tracing::info!("fetch target channel {channel_id}");
+        let mut videos = vec![];
+        for c in channels.iter() {
+            let channel_id = &c.channel.id;
+            tracing::info!("fetch target channel {channel_id}");
 
-        if args.all {
-            video_fetch_service
-                .fetch_all_videos_by_channel_id(&channel_id)
-                .await?
-        } else {
-            video_fetch_service
-                .fetch_recent_video_by_channel_id(&channel_id, args.num_recent)
-                .await?
+            // 内部に保存する場合は、前回から追加・変更された動画のみを取得し、取得できた分から順に登録する
+            let v = if let Some(indexer) = indexer.as_mut() {
+                let count = (!args.all).then_some(c.num_recent);
+                sync_channel(
+                    &video_fetch_service,
+                    &sync_service,
+                    indexer,
+                    channel_id,
+                    count,
+                    &args,
+                )
+                .await
+                .map(|()| vec![])
+            } else if args.all {
+                video_fetch_service
+                    .fetch_all_videos_by_channel_id(channel_id)
+                    .await
+            } else {
+                video_fetch_service
+                    .fetch_recent_video_by_channel_id(channel_id, c.num_recent)
+                    .await
+            };
+            match v {
+                Ok(v) => videos.extend(v),
+                // 利用枠を使い切った場合は、取得済みの動画のみを保存して終える
+                Err(AppError::QuotaExceeded(e)) => {
+                    tracing::warn!("Quota exhausted. Stop fetching channels : {}", e);
+                    break;
+                }
+                Err(e) => {
+                    ext_repo.quota().save()?;
+                    return Err(e);
+                }
+            }
+        }
+
+        for playlist_id in playlists.iter() {
+            tracing::info!("fetch target playlist {playlist_id}");
+            match video_fetch_service
+                .fetch_videos_by_playlist_id(playlist_id)
+                .await
+            {
+                Ok(v) => {
+                    merge_playlist_videos(&mut videos, v);
+                    fetched_playlists.push(playlist_id.clone());
+                }
+                Err(AppError::QuotaExceeded(e)) => {
+                    tracing::warn!("Quota exhausted. Stop fetching playlists : {}", e);
+                    break;
+                }
+                Err(e) => {
+                    ext_repo.quota().save()?;
+                    return Err(e);
+                }
+            }
+        }
+
+        if !args.skip_comments {
+            tracing::info!("Load comments from external service.");
+            comments = fetch_comments(&video_fetch_service, &videos, args.num_comments).await;
         }
+
+        ext_repo.quota().save()?;
+        tracing::info!("{}", ext_repo.quota().report());
+        videos
     } else {
         tracing::info!("Load video entity from local json file.");
         let mut videos = Vec::new();
@@ -75,17 +288,152 @@ async fn main() -> AppResult<()> {
         videos
     };
 
-    if args.out_internal {
+    if let Some(mut indexer) = indexer {
         tracing::info!("Output video and timestamps from internal meilisearch.");
-        let int_repo = Arc::new(create_video_crud_repository());
-        let tss_repo = Arc::new(create_timestamp_crud_repository());
+        indexer
+            .detach_playlists(&fetched_playlists, &videos)
+            .await?;
+        indexer.index(&videos, &comments).await?;
+        indexer.index_linked().await?;
+    } else {
+        tracing::info!("Output video to json file.");
+        if let Some(file) = args.out_json {
+            let file = File::create(file).expect("Unable to open file");
+            serde_json::to_writer_pretty(file, &videos).expect("Unable to write to file");
+        } else {
+            println!("Output to json file is not set.");
+        }
+    }
+
+    Ok(())
+}
 
-        let parser = TimeStampParserService::new();
-        let video_indexing = VideoIndexingService::new(int_repo.clone());
-        let ts_indexing = TimeStampIndexingService::new(tss_repo.clone(), int_repo.clone());
+/// 再生リストから取得した動画を加える。チャンネルから取得済みの動画には所属のみを写す
+fn merge_playlist_videos(videos: &mut Vec<VideoEntity>, playlist_videos: Vec<VideoEntity>) {
+    for v in playlist_videos {
+        match videos.iter_mut().find(|x| x.id == v.id) {
+            Some(x) => v.playlists.iter().for_each(|p| x.add_playlist(p)),
+            None => videos.push(v),
+        }
+    }
+}
 
-        if video_indexing
-            .add_or_update_video_entities(&videos)
+/// 動画のコメント
+/// コメント無効の動画などは取得に失敗するため、スキップして続行する。
+/// 利用枠を使い切った場合は、取得済みの分のみを返す
+async fn fetch_comments<E: ExternalVideoRepository>(
+    service: &VideoFetchService<E>,
+    videos: &[VideoEntity],
+    num_comments: u32,
+) -> HashMap<VideoId, Vec<VideoCommentEntity>> {
+    let mut comments = HashMap::new();
+    for v in videos.iter() {
+        match service
+            .fetch_comments_by_video_id(&v.id, num_comments)
+            .await
+        {
+            Ok(c) => {
+                comments.insert(v.id.clone(), c);
+            }
+            Err(AppError::QuotaExceeded(e)) => {
+                tracing::warn!("Quota exhausted. Stop fetching comments : {}", e);
+                break;
+            }
+            Err(e) => tracing::warn!("Failed to fetch comments of {} : {}", v.id, e),
+        }
+    }
+    comments
+}
+
+/// チャンネルの前回から追加・変更された動画を取得し、取得できたまとまりごとにコメントの取得と登録を進める
+///
+/// チャンネル全体を保持しないため、動画の多いチャンネルでも使用メモリが増えない。
+/// 取得した動画をすべて登録できた場合のみ、取得状況を保存する。
+async fn sync_channel<E, V, T>(
+    fetch: &VideoFetchService<E>,
+    sync: &ChannelSyncService<E>,
+    indexer: &mut Indexer<V, T>,
+    channel_id: &ChannelId,
+    count: Option<u32>,
+    args: &Args,
+) -> AppResult<()>
+where
+    E: ExternalVideoRepository,
+    V: InternalVideoRepository,
+    T: InternalVideoTimeStampRepository,
+{
+    // 登録が追いつかない間は取得を待たせる
+    let (tx, mut rx) = mpsc::channel::<Vec<VideoEntity>>(1);
+    let consume = async move {
+        let mut indexed = 0;
+        while let Some(videos) = rx.next().await {
+            let comments = if args.skip_comments {
+                HashMap::new()
+            } else {
+                fetch_comments(fetch, &videos, args.num_comments).await
+            };
+            indexer.index(&videos, &comments).await?;
+            indexed += videos.len();
+        }
+        tracing::info!("Indexed videos count {} : {}", indexed, channel_id);
+        AppResult::Ok(())
+    };
+
+    let (state, indexed) = tokio::join!(sync.sync(channel_id, count, args.full_sync, tx), consume);
+    indexed?;
+    sync.save(&state?).await
+}
+
+/// 動画とタイムスタンプを内部のインデックスに登録する
+struct Indexer<V: InternalVideoRepository, T: InternalVideoTimeStampRepository> {
+    int_repo: Arc<V>,
+    tss_repo: Arc<T>,
+    parser: TimeStampParserService,
+    merger: TimeStampMergeService,
+    segmenter: TimeStampSegmentService,
+    setlist: SetlistParserService,
+    video_indexing: VideoIndexingService<V>,
+    ts_indexing: TimeStampIndexingService<T, V>,
+    /// 別の動画へのリンクから得たタイムスタンプ。リンク先の動画ごとにまとめて最後に登録する
+    linked: HashMap<VideoId, Vec<VideoTimestampEntity>>,
+    /// 解析し直した動画の保存済みタイムスタンプを置き換えるか
+    replace: bool,
+}
+
+impl<V: InternalVideoRepository, T: InternalVideoTimeStampRepository> Indexer<V, T> {
+    fn new(rules: TimeStampParserRules, int_repo: Arc<V>, tss_repo: Arc<T>) -> Self {
+        Self {
+            parser: TimeStampParserService::new().with_rules(rules),
+            merger: TimeStampMergeService::default(),
+            segmenter: TimeStampSegmentService::new(),
+            setlist: SetlistParserService::new(),
+            video_indexing: VideoIndexingService::new(int_repo.clone()),
+            ts_indexing: TimeStampIndexingService::new(tss_repo.clone(), int_repo.clone()),
+            int_repo,
+            tss_repo,
+            linked: HashMap::new(),
+            replace: false,
+        }
+    }
+
+    /// 登録済みの動画を解析し直す場合に指定する
+    ///
+    /// 概要欄やコメントが書き換えられると古いタイムスタンプが残るため、保存済みのものを消してから登録する。
+    fn with_replace(mut self, replace: bool) -> Self {
+        self.replace = replace;
+        self
+    }
+
+    async fn index(
+        &mut self,
+        videos: &[VideoEntity],
+        comments: &HashMap<VideoId, Vec<VideoCommentEntity>>,
+    ) -> AppResult<()> {
+        // タイムスタンプにも再生リストへの所属を持たせるため、保存済みの所属を引き継いでから登録する
+        let videos = &self.video_indexing.merge_stored_playlists(videos).await?;
+        if self
+            .video_indexing
+            .add_or_update_video_entities(videos)
             .await
             .is_err()
         {
@@ -94,22 +442,421 @@ async fn main() -> AppResult<()> {
             ));
         }
 
-        for v in videos {
-            if let Ok(tss) = parser.parse_video(&v) {
-                ts_indexing
-                    .add_or_update_timestamps(&v, tss.as_slice())
+        for v in videos.iter() {
+            if let Ok(mut tss) = self.parser.parse_video(v) {
+                if let Some(c) = comments.get(&v.id) {
+                    tss.extend(self.parser.parse_comments(v, c)?);
+                }
+                let (tss, others): (Vec<_>, Vec<_>) =
+                    tss.into_iter().partition(|t| t.video_id == v.id);
+                for t in others {
+                    self.linked.entry(t.video_id.clone()).or_default().push(t);
+                }
+                let tss = match self.replace {
+                    true => self.replace_stored(v, tss, comments.get(&v.id)).await?,
+                    false => tss,
+                };
+                self.add_timestamps(v, tss).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 取得し直した再生リストへの所属を、取得した動画のみに置き換える
+    ///
+    /// 所属を外した動画は、タイムスタンプにも所属を持たせているため保存し直す。
+    async fn detach_playlists(
+        &self,
+        playlists: &[PlaylistId],
+        videos: &[VideoEntity],
+    ) -> AppResult<()> {
+        for p in playlists.iter() {
+            for v in self.video_indexing.detach_playlist(p, videos).await? {
+                let tss = self
+                    .tss_repo
+                    .get_video_timestamp_entities_by_video_id(&v.id)
                     .await?;
+                if !tss.is_empty() {
+                    self.tss_repo
+                        .update_video_timestamp_entities(&v, &tss)
+                        .await?;
+                }
             }
         }
+        Ok(())
+    }
+
+    /// リンク先の動画ごとにまとめたタイムスタンプを登録する。登録済みの動画のみが対象
+    ///
+    /// 終了時間をリンク先の動画のタイムスタンプと合わせて導出するため、保存済みのものに加えて登録し直す。
+    async fn index_linked(&mut self) -> AppResult<()> {
+        for (id, tss) in std::mem::take(&mut self.linked) {
+            let Some(v) = self.int_repo.get_video_entity_by_id(&id).await? else {
+                tracing::warn!("Skip timestamps linked to unknown video {}", id);
+                continue;
+            };
+            let stored = self
+                .tss_repo
+                .get_video_timestamp_entities_by_video_id(&id)
+                .await?;
+            // 保存済みのものを先に置き、重複したときに保存済みのIDを残す
+            let mut all = self.strip_derived_end_times(&v, stored);
+            all.extend(tss);
+            self.add_timestamps(&v, all).await?;
+        }
+        Ok(())
+    }
+
+    /// 動画の保存済みタイムスタンプを消し、解析し直したものに置き換える
+    ///
+    /// 今回の解析で得られないもの（手動登録・取り込み、取得していないコメント由来のもの）は残す。
+    async fn replace_stored(
+        &self,
+        video: &VideoEntity,
+        tss: Vec<VideoTimestampEntity>,
+        comments: Option<&Vec<VideoCommentEntity>>,
+    ) -> AppResult<Vec<VideoTimestampEntity>> {
+        let reparsed = |s: &TimeStampSource| match s {
+            TimeStampSource::Description => true,
+            TimeStampSource::Comment { comment_id, .. } => {
+                comments.is_some_and(|c| c.iter().any(|c| &c.id == comment_id))
+            }
+            TimeStampSource::Manual | TimeStampSource::Import => false,
+        };
+        let stored = self
+            .tss_repo
+            .get_video_timestamp_entities_by_video_id(&video.id)
+            .await?;
+        let kept = stored
+            .into_iter()
+            .filter(|t| !t.sources.iter().all(reparsed))
+            .collect::<Vec<_>>();
+
+        self.tss_repo
+            .delete_video_timestamp_entity_by_video_id(&video.id)
+            .await?;
+        let mut all = tss;
+        all.extend(self.strip_derived_end_times(video, kept));
+        Ok(all)
+    }
+
+    /// 保存済みのタイムスタンプから、前後のタイムスタンプや動画の長さから導出した終了時間を外す。
+    /// 範囲で書かれた終了時間は残す
+    fn strip_derived_end_times(
+        &self,
+        video: &VideoEntity,
+        tss: Vec<VideoTimestampEntity>,
+    ) -> Vec<VideoTimestampEntity> {
+        let cleared = tss
+            .iter()
+            .cloned()
+            .map(|mut t| {
+                t.timestamp.end_time = None;
+                t
+            })
+            .collect();
+        let derived = self
+            .segmenter
+            .assign_end_times(video, cleared)
+            .into_iter()
+            .filter_map(|t| Some((t.id().ok()?, t.timestamp.end_time)))
+            .collect::<HashMap<_, _>>();
+
+        tss.into_iter()
+            .map(|mut t| {
+                let id = t.id().ok();
+                if id.and_then(|id| derived.get(&id)) == Some(&t.timestamp.end_time) {
+                    t.timestamp.end_time = None;
+                }
+                t
+            })
+            .collect()
+    }
+
+    async fn add_timestamps(
+        &self,
+        video: &VideoEntity,
+        tss: Vec<VideoTimestampEntity>,
+    ) -> AppResult<()> {
+        let tss = self.merger.merge(tss);
+        let tss = self.segmenter.assign_end_times(video, tss);
+        let tss = self.setlist.annotate(tss);
+        self.ts_indexing
+            .add_or_update_timestamps(video, tss.as_slice())
+            .await
+    }
+}
+
+/// 取得対象のチャンネル
+///
+/// `--channel` を指定した場合はそのチャンネルを、登録ファイルを指定した場合はその内容を、
+/// どちらも指定しない場合はインデックスに登録済みのものを使う。
+/// いずれも無い場合は `TSS_TARGET_CHANNEL_ID` の1チャンネルのみを対象とする。
+/// チャンネルID以外で指定したチャンネルは、取得元に問い合わせてチャンネルIDに解決する。
+async fn target_channels<E: ExternalChannelRepository>(
+    args: &Args,
+    ext_repo: &Arc<E>,
+) -> AppResult<Vec<RegisteredChannelEntity>> {
+    let resolver = ChannelResolveService::new(ext_repo.clone());
+    if !args.channel.is_empty() {
+        let mut channels = Vec::with_capacity(args.channel.len());
+        for c in args.channel.iter() {
+            let id = resolver.resolve(c).await?;
+            let channel = ChannelEntity::new(id.clone(), ChannelName::new(id.as_str())?);
+            channels.push(RegisteredChannelEntity::new(channel).with_num_recent(args.num_recent));
+        }
+        return Ok(channels);
+    }
+
+    let channels = match args.channels.as_ref() {
+        Some(path) => {
+            let channels = ChannelRegistryService::load(path, &resolver).await?;
+            if args.out_internal {
+                let registry =
+                    ChannelRegistryService::new(Arc::new(create_channel_crud_repository()));
+                registry.register(&channels).await?;
+            }
+            channels.into_iter().filter(|c| c.enabled).collect()
+        }
+        None => {
+            let registry = ChannelRegistryService::new(Arc::new(create_channel_crud_repository()));
+            registry.enabled_channels().await?
+        }
+    };
+    if !channels.is_empty() {
+        return Ok(channels);
+    }
+
+    let channel_id = std::env::var("TSS_TARGET_CHANNEL_ID").map_err(|_| {
+        AppError::InvalidInput(
+            "No channels registered and TSS_TARGET_CHANNEL_ID is not set".to_string(),
+        )
+    })?;
+    tracing::warn!("No channels registered. Use TSS_TARGET_CHANNEL_ID.");
+    let id = resolver.resolve(&channel_id).await?;
+    let channel = ChannelEntity::new(id.clone(), ChannelName::new(id.as_str())?);
+    Ok(vec![
+        RegisteredChannelEntity::new(channel).with_num_recent(args.num_recent),
+    ])
+}
+
+/// 保存済みの動画ごとに、タイムスタンプ数・順序の乱れ・重複・誤検出の疑いを出力する
+async fn report(rules: TimeStampParserRules, threshold: Confidence) -> AppResult<()> {
+    tracing::info!("Report parse results of videos in internal meilisearch.");
+    let int_repo = create_video_crud_repository();
+    let service = ParseReportService::new(TimeStampParserService::new().with_rules(rules))
+        .with_threshold(threshold);
+
+    let videos = int_repo.get_all_video_entities().await?;
+    println!("videoId\tcount\toutOfOrder\tduplicates\tsuspected");
+    let (mut count, mut suspected) = (0, 0);
+    for v in videos.iter() {
+        match service.report(v) {
+            Ok(r) => {
+                count += r.count;
+                suspected += r.suspected;
+                println!("{}", r);
+            }
+            Err(e) => tracing::warn!("Failed to parse {} : {}", v.id, e),
+        }
+    }
+    println!(
+        "videos {} timestamps {} suspected {}",
+        videos.len(),
+        count,
+        suspected
+    );
+    Ok(())
+}
+
+/// 保存済みの動画を、videos.list の1回分ずつ取得元と照合する
+async fn reconcile() -> AppResult<()> {
+    tracing::info!("Reconcile videos in internal meilisearch with external service.");
+    let ext_repo = Arc::new(create_youtube_video_repository().await?);
+    let int_repo = Arc::new(create_video_crud_repository());
+    let service = VideoReconcileService::new(
+        ext_repo.clone(),
+        int_repo.clone(),
+        Arc::new(create_timestamp_crud_repository()),
+    );
+
+    let videos = int_repo.get_all_video_entities().await?;
+    let mut changed = 0;
+    for batch in videos.chunks(50) {
+        match service.reconcile(batch).await {
+            Ok(v) => changed += v.len(),
+            Err(AppError::QuotaExceeded(e)) => {
+                tracing::warn!("Quota exhausted. Stop reconciling videos : {}", e);
+                break;
+            }
+            Err(e) => {
+                ext_repo.quota().save()?;
+                return Err(e);
+            }
+        }
+    }
+
+    ext_repo.quota().save()?;
+    tracing::info!("Reconciled videos {} changed {}", videos.len(), changed);
+    tracing::info!("{}", ext_repo.quota().report());
+    Ok(())
+}
+
+/// 保存済みのタイムスタンプを動画ごとに保存し直し、古いIDのドキュメントを消す
+async fn reindex() -> AppResult<()> {
+    tracing::info!("Reindex timestamps in internal meilisearch.");
+    let service = TimeStampReindexService::new(Arc::new(create_timestamp_crud_repository()));
+
+    let videos = create_video_crud_repository()
+        .get_all_video_entities()
+        .await?;
+    let mut reindexed = 0;
+    for v in videos.iter() {
+        reindexed += service.reindex(v).await?;
+    }
+    tracing::info!("Reindexed videos {} timestamps {}", videos.len(), reindexed);
+    Ok(())
+}
+
+/// 配信予定・配信中の動画と終了から `days` 日以内の配信を取得し直し、タイムスタンプを抽出し直す
+async fn revisit(args: &Args, mut rules: TimeStampParserRules, days: u32) -> AppResult<()> {
+    tracing::info!("Revisit broadcasts in internal meilisearch.");
+    let ext_repo = Arc::new(create_youtube_video_repository().await?);
+    for c in target_channels(args, &ext_repo).await?.iter() {
+        if let Some(profile) = c.parser_profile.as_ref() {
+            rules.assign_profile(&c.channel.id, profile)?;
+        }
+    }
+
+    let int_repo = Arc::new(create_video_crud_repository());
+    let service = BroadcastRevisitService::new(ext_repo.clone(), int_repo.clone())
+        .with_window(chrono::Duration::days(days.into()));
+
+    let videos = match service.revisit(chrono::Utc::now()).await {
+        Ok(v) => v,
+        Err(e) => {
+            ext_repo.quota().save()?;
+            return Err(e);
+        }
+    };
+    let comments = if args.skip_comments {
+        HashMap::new()
     } else {
-        tracing::info!("Output video to json file.");
-        if let Some(file) = args.out_json {
-            let file = File::create(file).expect("Unable to open file");
-            serde_json::to_writer_pretty(file, &videos).expect("Unable to write to file");
+        let fetch = VideoFetchService::new(ext_repo.clone());
+        fetch_comments(&fetch, &videos, args.num_comments).await
+    };
+    ext_repo.quota().save()?;
+    tracing::info!("{}", ext_repo.quota().report());
+
+    let mut indexer = Indexer::new(
+        rules,
+        int_repo,
+        Arc::new(create_timestamp_crud_repository()),
+    )
+    .with_replace(true);
+    indexer.index(&videos, &comments).await?;
+    indexer.index_linked().await
+}
+
+/// WebSub の通知を待ち受け、通知された動画を取り込む
+async fn websub(args: &Args, mut rules: TimeStampParserRules) -> AppResult<()> {
+    let config = WebSubConfig::from_env()?;
+    let ext_repo = Arc::new(create_youtube_video_repository().await?);
+    let channels = target_channels(args, &ext_repo).await?;
+    for c in channels.iter() {
+        if let Some(profile) = c.parser_profile.as_ref() {
+            rules.assign_profile(&c.channel.id, profile)?;
+        }
+    }
+
+    let (tx, rx) = tokio::sync::mpsc::channel(WEBSUB_QUEUE_SIZE);
+    let state = WebSubState::new(
+        config.secret.clone(),
+        channels.into_iter().map(|c| c.channel.id).collect(),
+        tx,
+    );
+    let listener = tokio::net::TcpListener::bind(config.listen_addr())
+        .await
+        .map_err(|e| AppError::InternalServerError(e.into()))?;
+    tracing::info!("WebSub receiver listen addr : {}", config.listen_addr());
+
+    let serve = async {
+        axum::serve(listener, websub::router(state))
+            .await
+            .map_err(|e| AppError::InternalServerError(e.into()))
+    };
+    tokio::try_join!(serve, ingest_notified(args, rules, ext_repo, rx))?;
+    Ok(())
+}
+
+/// 通知された動画を、videos.list の1回分ずつ取得して登録する
+///
+/// 取り込みに失敗しても待ち受けは続ける。取りこぼした動画は定期の取得で拾う。
+async fn ingest_notified(
+    args: &Args,
+    rules: TimeStampParserRules,
+    ext_repo: Arc<YoutubeVideoRepository>,
+    mut rx: tokio::sync::mpsc::Receiver<VideoId>,
+) -> AppResult<()> {
+    let fetch = VideoFetchService::new(ext_repo.clone());
+    // 通知は登録済みの動画の更新でも届くため、解析し直したタイムスタンプで置き換える
+    let mut indexer = Indexer::new(
+        rules,
+        Arc::new(create_video_crud_repository()),
+        Arc::new(create_timestamp_crud_repository()),
+    )
+    .with_replace(true);
+
+    let mut ids = Vec::new();
+    while rx.recv_many(&mut ids, 50).await > 0 {
+        // 同じ動画の通知は続けて届くことが多いため、まとめて1回だけ取り込む
+        let mut seen = std::collections::HashSet::new();
+        ids.retain(|id| seen.insert(id.clone()));
+
+        let videos = match ext_repo.fetch_videos_by_ids(&ids).await {
+            Ok(v) => v,
+            Err(e) => {
+                tracing::warn!("Failed to fetch notified videos : {}", e);
+                ids.clear();
+                continue;
+            }
+        };
+        ids.clear();
+        let comments = if args.skip_comments {
+            HashMap::new()
         } else {
-            println!("Output to json file is not set.");
+            fetch_comments(&fetch, &videos, args.num_comments).await
+        };
+        if let Err(e) = ext_repo.quota().save() {
+            tracing::warn!("Failed to save quota usage : {}", e);
+        }
+
+        if let Err(e) = indexer.index(&videos, &comments).await {
+            tracing::warn!("Failed to index notified videos : {}", e);
+            continue;
         }
+        if let Err(e) = indexer.index_linked().await {
+            tracing::warn!("Failed to index linked timestamps : {}", e);
+            continue;
+        }
+        tracing::info!("Indexed notified videos count {}", videos.len());
     }
+    Ok(())
+}
 
+/// チャンネルの指定ごとに、解決したチャンネルIDを出力する
+async fn resolve(references: &[String]) -> AppResult<()> {
+    let ext_repo = Arc::new(create_youtube_video_repository().await?);
+    let resolver = ChannelResolveService::new(ext_repo.clone());
+    for r in references.iter() {
+        match resolver.resolve(r).await {
+            Ok(id) => println!("{}\t{}", r, id),
+            Err(e) => {
+                ext_repo.quota().save()?;
+                return Err(e);
+            }
+        }
+    }
+    ext_repo.quota().save()?;
     Ok(())
 }
@@ -0,0 +1,389 @@
+//! WebSub（PubSubHubbub）の通知の受信
+//!
+//! ハブからの購読確認に応答し、動画の追加・更新の通知を受けた動画IDを取り込みの待ち行列に積む。
+//! 取り込み自体は待ち行列を受け取る側で行う。
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use domains::value_objects::channel_id::ChannelId;
+use domains::value_objects::video_id::VideoId;
+use errors::{AppError, AppResult};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+const ATOM_NS: &str = "http://www.w3.org/2005/Atom";
+const YT_NS: &str = "http://www.youtube.com/xml/schemas/2015";
+const TOPIC_PREFIX: &str = "https://www.youtube.com/xml/feeds/videos.xml?channel_id=";
+const SIGNATURE_HEADER: &str = "x-hub-signature";
+
+#[derive(Debug, Deserialize)]
+pub struct WebSubConfig {
+    pub port: u16,
+    /// 購読時にハブへ渡した `hub.secret`
+    pub secret: String,
+}
+
+impl WebSubConfig {
+    /// 空の secret では誰でも署名できてしまうため受け付けない
+    pub fn from_env() -> AppResult<Self> {
+        let config: Self = envy::prefixed("TSS_WEBSUB_")
+            .from_env()
+            .map_err(|e| AppError::InvalidInput(format!("WebSub config : {}", e)))?;
+        config.validate()
+    }
+
+    fn validate(self) -> AppResult<Self> {
+        if self.secret.trim().is_empty() {
+            return Err(AppError::InvalidInput(
+                "WebSub config : TSS_WEBSUB_SECRET must not be empty".to_string(),
+            ));
+        }
+        Ok(self)
+    }
+
+    pub fn listen_addr(&self) -> String {
+        format!("0.0.0.0:{}", self.port)
+    }
+}
+
+#[derive(Clone)]
+pub struct WebSubState {
+    secret: Arc<String>,
+    /// 通知を受け付けるチャンネル
+    channels: Arc<HashSet<ChannelId>>,
+    queue: mpsc::Sender<VideoId>,
+}
+
+impl WebSubState {
+    pub fn new(secret: String, channels: HashSet<ChannelId>, queue: mpsc::Sender<VideoId>) -> Self {
+        Self {
+            secret: Arc::new(secret),
+            channels: Arc::new(channels),
+            queue,
+        }
+    }
+}
+
+pub fn router(state: WebSubState) -> Router {
+    Router::new()
+        .route(
+            "/websub",
+            get(verify_subscription).post(receive_notification),
+        )
+        .with_state(state)
+}
+
+#[derive(Debug, Deserialize)]
+struct VerificationRequest {
+    #[serde(rename = "hub.mode")]
+    mode: String,
+    #[serde(rename = "hub.topic")]
+    topic: String,
+    #[serde(rename = "hub.challenge")]
+    challenge: Option<String>,
+    #[serde(rename = "hub.lease_seconds")]
+    lease_seconds: Option<u64>,
+}
+
+/// 購読の確認。受け付けるチャンネルのフィードのみ `hub.challenge` を返す
+///
+/// 購読解除はこちらから求めることがなく、第三者の求めで購読が外れないよう確認を拒む。
+async fn verify_subscription(
+    State(state): State<WebSubState>,
+    Query(req): Query<VerificationRequest>,
+) -> Response {
+    let channel = topic_channel(&req.topic).filter(|c| state.channels.contains(c));
+    match (req.mode.as_str(), channel, req.challenge) {
+        ("subscribe", Some(c), Some(challenge)) => {
+            tracing::info!("Verify {} {} lease {:?}", req.mode, c, req.lease_seconds);
+            (StatusCode::OK, challenge).into_response()
+        }
+        ("denied", _, _) => {
+            tracing::warn!("Subscription denied : {}", req.topic);
+            StatusCode::OK.into_response()
+        }
+        _ => {
+            tracing::warn!("Reject verification {} {}", req.mode, req.topic);
+            StatusCode::NOT_FOUND.into_response()
+        }
+    }
+}
+
+/// 動画の追加・更新の通知
+///
+/// 署名の一致しない通知もハブが再送しないよう 2xx を返し、内容は捨てる。
+async fn receive_notification(
+    State(state): State<WebSubState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    let signature = headers.get(SIGNATURE_HEADER).and_then(|v| v.to_str().ok());
+    if !signature.is_some_and(|s| verify_signature(state.secret.as_bytes(), s, &body)) {
+        tracing::warn!("Discard notification with invalid signature");
+        return StatusCode::ACCEPTED;
+    }
+
+    let entries = match std::str::from_utf8(&body)
+        .map_err(|e| AppError::InvalidInput(e.to_string()))
+        .and_then(parse_feed)
+    {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!("Discard malformed notification : {}", e);
+            return StatusCode::ACCEPTED;
+        }
+    };
+    for e in entries {
+        if !state.channels.contains(&e.channel_id) {
+            tracing::debug!(
+                "Skip video {} of unknown channel {}",
+                e.video_id,
+                e.channel_id
+            );
+            continue;
+        }
+        tracing::info!("Notified video {} : {}", e.video_id, e.channel_id);
+        // 待ち行列があふれた分は、定期の取得で拾う
+        if let Err(e) = state.queue.try_send(e.video_id) {
+            tracing::warn!("Drop notified video : {}", e);
+        }
+    }
+    StatusCode::ACCEPTED
+}
+
+/// `X-Hub-Signature`（`sha1=<hex>` など）が本文の HMAC と一致するか
+pub fn verify_signature(secret: &[u8], signature: &str, body: &[u8]) -> bool {
+    let Some((method, hex)) = signature.split_once('=') else {
+        return false;
+    };
+    let Ok(expected) = hex::decode(hex) else {
+        return false;
+    };
+    match method {
+        "sha1" => verify_mac::<Hmac<sha1::Sha1>>(secret, body, &expected),
+        "sha256" => verify_mac::<Hmac<sha2::Sha256>>(secret, body, &expected),
+        "sha512" => verify_mac::<Hmac<sha2::Sha512>>(secret, body, &expected),
+        _ => false,
+    }
+}
+
+fn verify_mac<M: Mac + hmac::digest::KeyInit>(secret: &[u8], body: &[u8], expected: &[u8]) -> bool {
+    let Ok(mut mac) = <M as Mac>::new_from_slice(secret) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(expected).is_ok()
+}
+
+/// 購読対象のフィードのチャンネル
+fn topic_channel(topic: &str) -> Option<ChannelId> {
+    topic
+        .strip_prefix(TOPIC_PREFIX)
+        .and_then(|id| ChannelId::new(id).ok())
+}
+
+/// 通知された動画
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedEntry {
+    pub video_id: VideoId,
+    pub channel_id: ChannelId,
+}
+
+/// 通知の Atom フィードから、追加・更新された動画を取り出す
+/// 削除の通知（`at:deleted-entry`）は含まない。削除は `reconcile` で反映する
+pub fn parse_feed(xml: &str) -> AppResult<Vec<FeedEntry>> {
+    let doc = roxmltree::Document::parse(xml)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid feed : {}", e)))?;
+    let entries = doc
+        .descendants()
+        .filter(|n| n.has_tag_name((ATOM_NS, "entry")))
+        .filter_map(|entry| {
+            let (Some(video_id), Some(channel_id)) =
+                (yt_text(entry, "videoId"), yt_text(entry, "channelId"))
+            else {
+                tracing::warn!("Skip entry without videoId or channelId");
+                return None;
+            };
+            match (VideoId::new(video_id), ChannelId::new(channel_id)) {
+                (Ok(video_id), Ok(channel_id)) => Some(FeedEntry {
+                    video_id,
+                    channel_id,
+                }),
+                _ => {
+                    tracing::warn!("Skip invalid entry {} : {}", video_id, channel_id);
+                    None
+                }
+            }
+        })
+        .collect();
+    Ok(entries)
+}
+
+fn yt_text<'a>(entry: roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    entry
+        .children()
+        .find(|n| n.has_tag_name((YT_NS, name)))
+        .and_then(|n| n.text())
+        .map(str::trim)
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Method, Request};
+    use rstest::rstest;
+    use tower::ServiceExt;
+
+    const CHANNEL_ID: &str = "UC_x5XG1OV2P6uZZ5FSM9Ttw";
+    const SECRET: &str = "secret";
+
+    fn feed(channel_id: &str) -> String {
+        format!(
+            r#"<?xml version='1.0' encoding='UTF-8'?>
+<feed xmlns:yt="http://www.youtube.com/xml/schemas/2015" xmlns="http://www.w3.org/2005/Atom">
+  <link rel="hub" href="https://pubsubhubbub.appspot.com"/>
+  <title>YouTube video feed</title>
+  <entry>
+    <id>yt:video:aqz-KE-bpKQ</id>
+    <yt:videoId>aqz-KE-bpKQ</yt:videoId>
+    <yt:channelId>{channel_id}</yt:channelId>
+    <title>Video title</title>
+  </entry>
+</feed>"#
+        )
+    }
+
+    fn sign(body: &str) -> String {
+        let mut mac = Hmac::<sha1::Sha1>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(body.as_bytes());
+        format!("sha1={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    fn app() -> (Router, mpsc::Receiver<VideoId>) {
+        let (tx, rx) = mpsc::channel(10);
+        let channels = HashSet::from([ChannelId::new(CHANNEL_ID).unwrap()]);
+        (
+            router(WebSubState::new(SECRET.to_string(), channels, tx)),
+            rx,
+        )
+    }
+
+    #[test]
+    fn parse_updated_entries() -> anyhow::Result<()> {
+        let entries = parse_feed(&feed(CHANNEL_ID))?;
+        assert_eq!(
+            entries,
+            vec![FeedEntry {
+                video_id: VideoId::new("aqz-KE-bpKQ")?,
+                channel_id: ChannelId::new(CHANNEL_ID)?,
+            }]
+        );
+
+        let deleted = r#"<feed xmlns:at="http://purl.org/atompub/tombstones/1.0" xmlns="http://www.w3.org/2005/Atom">
+  <at:deleted-entry ref="yt:video:aqz-KE-bpKQ" when="2026-10-18T00:00:00+00:00"/>
+</feed>"#;
+        assert!(parse_feed(deleted)?.is_empty());
+        assert!(parse_feed("<feed>").is_err());
+        Ok(())
+    }
+
+    #[rstest]
+    #[case::secret("secret", true)]
+    #[case::empty("", false)]
+    #[case::blank("  ", false)]
+    fn validate_config(#[case] secret: &str, #[case] valid: bool) {
+        let config = WebSubConfig {
+            port: 8090,
+            secret: secret.to_string(),
+        };
+        assert_eq!(config.validate().is_ok(), valid);
+    }
+
+    #[rstest]
+    #[case::sha1(sign("body"), true)]
+    #[case::wrong_sha256("sha256=00".to_string(), false)]
+    #[case::other_body(sign("other"), false)]
+    #[case::unknown_method("md5=00".to_string(), false)]
+    #[case::malformed("sha1".to_string(), false)]
+    fn verify_signatures(#[case] signature: String, #[case] expected: bool) {
+        assert_eq!(
+            verify_signature(SECRET.as_bytes(), &signature, b"body"),
+            expected
+        );
+    }
+
+    #[test]
+    fn verify_sha256_signature() {
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(b"body");
+        let signature = format!("sha256={}", hex::encode(mac.finalize().into_bytes()));
+        assert!(verify_signature(SECRET.as_bytes(), &signature, b"body"));
+    }
+
+    #[rstest]
+    #[case::registered(CHANNEL_ID, "subscribe", StatusCode::OK)]
+    #[case::unsubscribe(CHANNEL_ID, "unsubscribe", StatusCode::NOT_FOUND)]
+    #[case::unknown_channel("UC_1234567890abcdefghijk", "subscribe", StatusCode::NOT_FOUND)]
+    #[case::unknown_mode(CHANNEL_ID, "unknown", StatusCode::NOT_FOUND)]
+    #[tokio::test]
+    async fn verify_subscriptions(
+        #[case] channel_id: &str,
+        #[case] mode: &str,
+        #[case] status: StatusCode,
+    ) {
+        let (app, _rx) = app();
+        let uri = format!(
+            "/websub?hub.mode={mode}&hub.topic={}&hub.challenge=abc&hub.lease_seconds=432000",
+            url_encode(&format!("{TOPIC_PREFIX}{channel_id}"))
+        );
+        let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let res = app.oneshot(req).await.unwrap();
+        assert_eq!(res.status(), status);
+        if status == StatusCode::OK {
+            let body = axum::body::to_bytes(res.into_body(), 1024).await.unwrap();
+            assert_eq!(body.as_ref(), b"abc");
+        }
+    }
+
+    #[rstest]
+    #[case::signed(CHANNEL_ID, true, true)]
+    #[case::unsigned(CHANNEL_ID, false, false)]
+    #[case::unknown_channel("UC_1234567890abcdefghijk", true, false)]
+    #[tokio::test]
+    async fn enqueue_notified_videos(
+        #[case] channel_id: &str,
+        #[case] signed: bool,
+        #[case] enqueued: bool,
+    ) {
+        let (app, mut rx) = app();
+        let body = feed(channel_id);
+        let mut req = Request::builder().method(Method::POST).uri("/websub");
+        if signed {
+            req = req.header("X-Hub-Signature", sign(&body));
+        }
+        let res = app
+            .oneshot(req.body(Body::from(body)).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::ACCEPTED);
+        assert_eq!(
+            rx.try_recv().ok(),
+            enqueued.then(|| VideoId::new("aqz-KE-bpKQ").unwrap())
+        );
+    }
+
+    fn url_encode(s: &str) -> String {
+        s.replace(':', "%3A")
+            .replace('/', "%2F")
+            .replace('?', "%3F")
+            .replace('=', "%3D")
+    }
+}
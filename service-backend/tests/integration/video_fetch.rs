@@ -1,38 +1,376 @@
+use domains::entities::video::VideoEntity;
+use domains::entities::video_timestamp::VideoTimestampEntity;
+use domains::repositories::internal_timestamp_repository::InternalVideoTimeStampRepository;
+use domains::repositories::internal_video_repository::InternalVideoRepository;
+use domains::value_objects::broadcast_state::BroadcastState;
 use domains::value_objects::channel_id::ChannelId;
-use meilisearch::repositories::timestamp_crud::create_timestamp_crud_repository;
-use meilisearch::repositories::video_crud::create_video_crud_repository;
+use domains::value_objects::elapsed_time::ElapsedTime;
+use domains::value_objects::playlist_id::PlaylistId;
+use domains::value_objects::timestamp::TimeStamp;
+use domains::value_objects::timestamp_description::TimeStampDescription;
+use domains::value_objects::timestamp_id::TimestampId;
+use domains::value_objects::video_availability::VideoAvailability;
+use domains::value_objects::video_id::VideoId;
+use futures::StreamExt;
+use futures::channel::mpsc;
 use rstest::rstest;
 use std::sync::Arc;
+use test_utils::repositories::{
+    InMemorySyncStateRepository, InMemoryTimeStampRepository, InMemoryVideoRepository,
+};
+use test_utils::youtube::{CHANNEL_ID, FakeYouTubeServer};
+use usecase::broadcast_revisit_service::BroadcastRevisitService;
+use usecase::channel_resolve_service::ChannelResolveService;
+use usecase::channel_sync_service::ChannelSyncService;
 use usecase::timestamp_indexing_service::TimeStampIndexingService;
 use usecase::timestamp_parser_service::TimeStampParserService;
+use usecase::timestamp_reindex_service::TimeStampReindexService;
 use usecase::video_fetch_service::VideoFetchService;
 use usecase::video_indexing_service::VideoIndexingService;
-use youtube::repositories::youtube_video::create_youtube_video_repository;
+use usecase::video_reconcile_service::VideoReconcileService;
+use youtube::repositories::youtube_video::create_youtube_video_repository_with;
 
 #[rstest]
 #[tokio::test]
 async fn fetch_and_update_video_timestamps() -> anyhow::Result<()> {
-    let yt = Arc::new(create_youtube_video_repository().await);
+    let server = FakeYouTubeServer::start().await;
+    let yt = Arc::new(create_youtube_video_repository_with(&server.config()).await?);
     let fetch = VideoFetchService::new(yt.clone());
 
-    let channel = ChannelId::new("abc-def-ghi")?;
+    let channel = ChannelId::new(CHANNEL_ID)?;
     let vs = fetch.fetch_recent_video_by_channel_id(&channel, 10).await?;
+    assert_eq!(vs.len(), 3);
 
-    if !vs.is_empty() {
-        let v_crud = Arc::new(create_video_crud_repository());
-        let vis = VideoIndexingService::new(v_crud.clone());
-        vis.add_or_update_video_entities(vs.as_slice()).await?;
+    let v_repo = Arc::new(InMemoryVideoRepository::default());
+    let vis = VideoIndexingService::new(v_repo.clone());
+    assert_eq!(vis.add_or_update_video_entities(vs.as_slice()).await?, 3);
+    // 変更の無い動画は書き込まない
+    assert_eq!(vis.add_or_update_video_entities(vs.as_slice()).await?, 0);
 
-        let parser = TimeStampParserService::new();
-        let v_repo = Arc::new(create_video_crud_repository());
-        let ts_repo = Arc::new(create_timestamp_crud_repository());
-        let tis = TimeStampIndexingService::new(ts_repo.clone(), v_repo.clone());
+    let parser = TimeStampParserService::new();
+    let ts_repo = Arc::new(InMemoryTimeStampRepository::default());
+    let tis = TimeStampIndexingService::new(ts_repo.clone(), v_repo.clone());
 
-        for v in vs.iter() {
-            let tss = parser.parse_video(v)?;
-            tis.add_or_update_timestamps(v, tss.as_slice()).await?;
-        }
+    for v in vs.iter() {
+        let comments = fetch.fetch_comments_by_video_id(&v.id, 100).await?;
+        let mut tss = parser.parse_video(v)?;
+        tss.extend(parser.parse_comments(v, &comments)?);
+        tis.add_or_update_timestamps(v, tss.as_slice()).await?;
     }
 
+    assert_eq!(v_repo.get_all_video_entities().await?.len(), 3);
+    let tss = ts_repo.get_all_video_timestamp_entities().await?;
+    let count = |id: &str| tss.iter().filter(|t| t.video_id.as_str() == id).count();
+    assert_eq!(count("aqz-KE-bpKQ"), 3);
+    assert_eq!(count("dQw4w9WgXcQ"), 2);
+    assert_eq!(count("M7lc1UVf-VE"), 1);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn fail_to_fetch_unknown_channel() -> anyhow::Result<()> {
+    let server = FakeYouTubeServer::start().await;
+    let yt = Arc::new(create_youtube_video_repository_with(&server.config()).await?);
+    let fetch = VideoFetchService::new(yt.clone());
+
+    // 空の再生リストIDで取得を続けず、エラーを返す
+    let unknown = ChannelId::new("UC_1234567890abcdefghijk")?;
+    assert!(
+        fetch
+            .fetch_recent_video_by_channel_id(&unknown, 2)
+            .await
+            .is_err()
+    );
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn sync_only_updated_videos() -> anyhow::Result<()> {
+    let server = FakeYouTubeServer::start().await;
+    let yt = Arc::new(create_youtube_video_repository_with(&server.config()).await?);
+    let sync =
+        ChannelSyncService::new(yt.clone(), Arc::new(InMemorySyncStateRepository::default()));
+    let channel = ChannelId::new(CHANNEL_ID)?;
+
+    // 取得できたまとまりをすべて受け取る
+    let sync_videos = |count: Option<u32>, full: bool| {
+        let sync = &sync;
+        let channel = &channel;
+        async move {
+            let (tx, rx) = mpsc::channel(1);
+            let (state, batches) = tokio::join!(
+                sync.sync(channel, count, full, tx),
+                rx.collect::<Vec<Vec<VideoEntity>>>()
+            );
+            anyhow::Ok((batches.concat(), state?))
+        }
+    };
+
+    // 初回は直近の件数のみ
+    let (v, state) = sync_videos(Some(2), false).await?;
+    assert_eq!(v.len(), 2);
+    // 取得状況を保存するまでは、同じ動画を取得し直す
+    assert_eq!(sync_videos(Some(2), false).await?.0, v);
+    sync.save(&state).await?;
+
+    // 全件を確認しても、取得済みの動画は返さない
+    let (v, state) = sync_videos(None, false).await?;
+    let ids = v.iter().map(|v| v.id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, vec!["M7lc1UVf-VE"]);
+    sync.save(&state).await?;
+
+    assert!(sync_videos(Some(2), false).await?.0.is_empty());
+    assert_eq!(sync_videos(None, true).await?.0.len(), 3);
+
+    assert!(yt.quota().report().total() > 0);
+    Ok(())
+}
+
+#[rstest]
+#[case::unauthorized("playlistItems", 401)]
+#[case::forbidden("playlistItems", 403)]
+#[case::videos_unauthorized("videos", 401)]
+#[tokio::test]
+async fn fail_sync_on_upstream_errors(
+    #[case] endpoint: &str,
+    #[case] status: usize,
+) -> anyhow::Result<()> {
+    let server = FakeYouTubeServer::start_failing(endpoint, status).await;
+    let yt = Arc::new(create_youtube_video_repository_with(&server.config()).await?);
+    let sync =
+        ChannelSyncService::new(yt.clone(), Arc::new(InMemorySyncStateRepository::default()));
+    let channel = ChannelId::new(CHANNEL_ID)?;
+
+    // 全件の確認でも、中断として記録せずに失敗する
+    let (tx, rx) = mpsc::channel(1);
+    let (state, batches) = tokio::join!(
+        sync.sync(&channel, None, false, tx),
+        rx.collect::<Vec<Vec<VideoEntity>>>()
+    );
+    assert!(state.is_err(), "{:?}", state);
+    assert!(batches.is_empty());
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn stream_all_videos_in_batches() -> anyhow::Result<()> {
+    let server = FakeYouTubeServer::start().await;
+    let yt = Arc::new(create_youtube_video_repository_with(&server.config()).await?);
+    let fetch = VideoFetchService::new(yt.clone());
+    let channel = ChannelId::new(CHANNEL_ID)?;
+
+    let (tx, rx) = mpsc::channel(1);
+    let (sent, batches) = tokio::join!(
+        fetch.stream_all_videos_by_channel_id(&channel, tx),
+        rx.collect::<Vec<Vec<VideoEntity>>>()
+    );
+    sent?;
+
+    // まとめて取得した場合と同じ順に届く
+    let streamed = batches.concat();
+    let all = fetch.fetch_all_videos_by_channel_id(&channel).await?;
+    assert_eq!(streamed.len(), 3);
+    assert_eq!(
+        streamed.iter().map(|v| &v.id).collect::<Vec<_>>(),
+        all.iter().map(|v| &v.id).collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn reconcile_removed_videos() -> anyhow::Result<()> {
+    let server = FakeYouTubeServer::start().await;
+    let yt = Arc::new(create_youtube_video_repository_with(&server.config()).await?);
+    let fetch = VideoFetchService::new(yt.clone());
+    let channel = ChannelId::new(CHANNEL_ID)?;
+
+    // 取得元から消えた動画を1件混ぜて保存する
+    let mut vs = fetch.fetch_all_videos_by_channel_id(&channel).await?;
+    let removed = VideoEntity {
+        id: VideoId::new("removed0000")?,
+        ..vs[0].clone()
+    };
+    vs.push(removed.clone());
+
+    let v_repo = Arc::new(InMemoryVideoRepository::default());
+    let ts_repo = Arc::new(InMemoryTimeStampRepository::default());
+    VideoIndexingService::new(v_repo.clone())
+        .add_or_update_video_entities(&vs)
+        .await?;
+    let parser = TimeStampParserService::new();
+    let tis = TimeStampIndexingService::new(ts_repo.clone(), v_repo.clone());
+    tis.add_or_update_timestamps(&removed, &parser.parse_video(&removed)?)
+        .await?;
+
+    let service = VideoReconcileService::new(yt.clone(), v_repo.clone(), ts_repo.clone());
+    let changed = service.reconcile(&vs).await?;
+    assert_eq!(changed.len(), 1);
+    assert_eq!(changed[0].id, removed.id);
+
+    let stored = v_repo.get_video_entity_by_id(&removed.id).await?.unwrap();
+    assert_eq!(stored.availability, VideoAvailability::Unavailable);
+    // タイムスタンプは消さずに残す
+    assert_eq!(
+        ts_repo
+            .get_video_timestamp_entities_by_video_id(&removed.id)
+            .await?
+            .len(),
+        3
+    );
+
+    // 変化の無い動画は更新しない
+    let stored = v_repo.get_all_video_entities().await?;
+    assert!(service.reconcile(&stored).await?.is_empty());
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn reindex_timestamps_with_old_ids() -> anyhow::Result<()> {
+    let server = FakeYouTubeServer::start().await;
+    let yt = Arc::new(create_youtube_video_repository_with(&server.config()).await?);
+    let fetch = VideoFetchService::new(yt.clone());
+    let channel = ChannelId::new(CHANNEL_ID)?;
+    let v = fetch.fetch_recent_video_by_channel_id(&channel, 1).await?[0].clone();
+
+    // 見出しを古い形式のIDで指すタイムスタンプを保存しておく
+    let ts = |seconds: u64, description: &str| -> anyhow::Result<VideoTimestampEntity> {
+        Ok(VideoTimestampEntity::new(
+            v.id.clone(),
+            TimeStamp::new(
+                ElapsedTime::new(seconds)?,
+                TimeStampDescription::new(description)?,
+            )?,
+        ))
+    };
+    let heading = ts(60, "Part 1")?;
+    let old_id = format!("{}-60-0123456789abcdef", v.id).parse::<TimestampId>()?;
+    let child = ts(90, "Song")?.with_parent_id(Some(old_id));
+    let orphan = ts(120, "Other")?
+        .with_parent_id(Some(format!("{}-999-0123", v.id).parse::<TimestampId>()?));
+    let ts_repo = Arc::new(InMemoryTimeStampRepository::default());
+    ts_repo
+        .add_video_timestamp_entities(&v, &[heading.clone(), child, orphan])
+        .await?;
+
+    let service = TimeStampReindexService::new(ts_repo.clone());
+    assert_eq!(service.reindex(&v).await?, 3);
+
+    let tss = ts_repo
+        .get_video_timestamp_entities_by_video_id(&v.id)
+        .await?;
+    let parent_of = |seconds: u64| {
+        tss.iter()
+            .find(|t| t.timestamp.elapsed_time == ElapsedTime::new(seconds).unwrap())
+            .and_then(|t| t.parent_id.clone())
+    };
+    assert_eq!(tss.len(), 3);
+    assert_eq!(parent_of(90), Some(heading.id()?));
+    assert_eq!(parent_of(120), None);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn revisit_recently_ended_streams() -> anyhow::Result<()> {
+    let server = FakeYouTubeServer::start().await;
+    let yt = Arc::new(create_youtube_video_repository_with(&server.config()).await?);
+    let fetch = VideoFetchService::new(yt.clone());
+    let channel = ChannelId::new(CHANNEL_ID)?;
+    let now = chrono::Utc::now();
+
+    // 登録時点の状態として、配信予定・7日以内に終了・それより前に終了、を保存する
+    let vs = fetch.fetch_all_videos_by_channel_id(&channel).await?;
+    let stored = vec![
+        VideoEntity {
+            broadcast_state: BroadcastState::Upcoming,
+            ..vs[0].clone()
+        },
+        VideoEntity {
+            broadcast_state: BroadcastState::Archived,
+            actual_end_at: Some(now - chrono::Duration::days(1)),
+            ..vs[1].clone()
+        },
+        VideoEntity {
+            broadcast_state: BroadcastState::Archived,
+            actual_end_at: Some(now - chrono::Duration::days(30)),
+            ..vs[2].clone()
+        },
+    ];
+    let v_repo = Arc::new(InMemoryVideoRepository::default());
+    VideoIndexingService::new(v_repo.clone())
+        .add_or_update_video_entities(&stored)
+        .await?;
+
+    let service = BroadcastRevisitService::new(yt.clone(), v_repo.clone());
+    let revisited = service.revisit(now).await?;
+    let mut ids = revisited.iter().map(|v| v.id.as_str()).collect::<Vec<_>>();
+    ids.sort();
+    let mut expected = vec![vs[0].id.as_str(), vs[1].id.as_str()];
+    expected.sort();
+    assert_eq!(ids, expected);
+
+    let service = service.with_window(chrono::Duration::days(60));
+    assert_eq!(service.revisit(now).await?.len(), 3);
+    Ok(())
+}
+
+#[rstest]
+#[tokio::test]
+async fn fetch_playlist_videos() -> anyhow::Result<()> {
+    let server = FakeYouTubeServer::start().await;
+    let yt = Arc::new(create_youtube_video_repository_with(&server.config()).await?);
+    let fetch = VideoFetchService::new(yt.clone());
+
+    let playlist = PlaylistId::new("PLx0sYbCqOb8TBPRdmBHs5Iftvv9TPboYG")?;
+    let vs = fetch.fetch_videos_by_playlist_id(&playlist).await?;
+    assert_eq!(vs.len(), 3);
+    assert!(vs.iter().all(|v| v.playlists == vec![playlist.clone()]));
+
+    let v_repo = Arc::new(InMemoryVideoRepository::default());
+    let vis = VideoIndexingService::new(v_repo.clone());
+    assert_eq!(vis.add_or_update_video_entities(vs.as_slice()).await?, 3);
+
+    // チャンネルから取得し直した動画でも、再生リストへの所属は失わない
+    let channel = ChannelId::new(CHANNEL_ID)?;
+    let vs = fetch.fetch_recent_video_by_channel_id(&channel, 10).await?;
+    assert!(vs.iter().all(|v| v.playlists.is_empty()));
+    assert_eq!(vis.add_or_update_video_entities(vs.as_slice()).await?, 0);
+    let merged = vis.merge_stored_playlists(vs.as_slice()).await?;
+    assert!(merged.iter().all(|v| v.playlists == vec![playlist.clone()]));
+    assert!(
+        v_repo
+            .get_all_video_entities()
+            .await?
+            .iter()
+            .all(|v| v.playlists == vec![playlist.clone()])
+    );
+    Ok(())
+}
+
+#[rstest]
+#[case::handle("@GoogleDevelopers")]
+#[case::handle_url("https://www.youtube.com/@googledevelopers/videos")]
+#[case::video_url("https://youtu.be/aqz-KE-bpKQ")]
+#[case::watch_url("https://www.youtube.com/watch?v=dQw4w9WgXcQ")]
+#[case::channel_url("https://www.youtube.com/channel/UC_x5XG1OV2P6uZZ5FSM9Ttw")]
+#[tokio::test]
+async fn resolve_channel_references(#[case] reference: &str) -> anyhow::Result<()> {
+    let server = FakeYouTubeServer::start().await;
+    let yt = Arc::new(create_youtube_video_repository_with(&server.config()).await?);
+    let resolver = ChannelResolveService::new(yt.clone());
+
+    assert_eq!(
+        resolver.resolve(reference).await?,
+        ChannelId::new(CHANNEL_ID)?
+    );
+    assert!(matches!(
+        resolver.resolve("@unknown-handle").await,
+        Err(errors::AppError::NotFound(_))
+    ));
     Ok(())
 }
@@ -1 +1,2 @@
-
+pub mod repositories;
+pub mod youtube;
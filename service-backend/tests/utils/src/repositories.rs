@@ -0,0 +1,227 @@
+//! Meilisearch の代わりに、メモリ上に保存するリポジトリ
+use chrono::{DateTime, Utc};
+use domains::entities::channel_sync_state::ChannelSyncStateEntity;
+use domains::entities::video::VideoEntity;
+use domains::entities::video_timestamp::VideoTimestampEntity;
+use domains::repositories::internal_sync_state_repository::InternalSyncStateRepository;
+use domains::repositories::internal_timestamp_repository::InternalVideoTimeStampRepository;
+use domains::repositories::internal_video_repository::InternalVideoRepository;
+use domains::value_objects::channel_id::ChannelId;
+use domains::value_objects::playlist_id::PlaylistId;
+use domains::value_objects::timestamp_id::TimestampId;
+use domains::value_objects::video_id::VideoId;
+use errors::AppResult;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+#[derive(Default)]
+pub struct InMemoryVideoRepository {
+    db: Mutex<BTreeMap<String, VideoEntity>>,
+}
+
+#[async_trait::async_trait]
+impl InternalVideoRepository for InMemoryVideoRepository {
+    async fn add_video_entity(&self, video_entity: &VideoEntity) -> AppResult<()> {
+        self.add_video_entities(std::slice::from_ref(video_entity))
+            .await
+    }
+
+    async fn add_video_entities(&self, video_entities: &[VideoEntity]) -> AppResult<()> {
+        let mut db = self.db.lock().unwrap();
+        for v in video_entities {
+            db.entry(v.id.to_string()).or_insert_with(|| v.clone());
+        }
+        Ok(())
+    }
+
+    async fn update_video_entity(&self, video_entity: &VideoEntity) -> AppResult<()> {
+        self.update_video_entities(std::slice::from_ref(video_entity))
+            .await
+    }
+
+    async fn update_video_entities(&self, video_entities: &[VideoEntity]) -> AppResult<()> {
+        let mut db = self.db.lock().unwrap();
+        for v in video_entities {
+            db.insert(v.id.to_string(), v.clone());
+        }
+        Ok(())
+    }
+
+    async fn find_video_entity_by_id(&self, video_id: &VideoId) -> AppResult<bool> {
+        Ok(self.db.lock().unwrap().contains_key(video_id.as_str()))
+    }
+
+    async fn get_video_entity_by_id(&self, video_id: &VideoId) -> AppResult<Option<VideoEntity>> {
+        Ok(self.db.lock().unwrap().get(video_id.as_str()).cloned())
+    }
+
+    async fn get_video_entities_by_ids(
+        &self,
+        video_ids: &[VideoId],
+    ) -> AppResult<Vec<VideoEntity>> {
+        let db = self.db.lock().unwrap();
+        Ok(video_ids
+            .iter()
+            .filter_map(|id| db.get(id.as_str()).cloned())
+            .collect())
+    }
+
+    async fn get_all_video_entities(&self) -> AppResult<Vec<VideoEntity>> {
+        Ok(self.db.lock().unwrap().values().cloned().collect())
+    }
+
+    async fn get_video_entities_by_playlist_id(
+        &self,
+        playlist_id: &PlaylistId,
+    ) -> AppResult<Vec<VideoEntity>> {
+        Ok(self
+            .db
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|v| v.playlists.contains(playlist_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_recent_broadcast_entities(
+        &self,
+        ended_after: DateTime<Utc>,
+    ) -> AppResult<Vec<VideoEntity>> {
+        Ok(self
+            .db
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|v| v.is_recent_broadcast(ended_after))
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_video_entity_by_id(&self, video_id: &VideoId) -> AppResult<()> {
+        self.db.lock().unwrap().remove(video_id.as_str());
+        Ok(())
+    }
+
+    async fn delete_all_video_entities(&self) -> AppResult<()> {
+        self.db.lock().unwrap().clear();
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemoryTimeStampRepository {
+    db: Mutex<BTreeMap<String, VideoTimestampEntity>>,
+}
+
+#[async_trait::async_trait]
+impl InternalVideoTimeStampRepository for InMemoryTimeStampRepository {
+    async fn add_video_timestamp_entity(
+        &self,
+        video_entity: &VideoEntity,
+        timestamp_entity: &VideoTimestampEntity,
+    ) -> AppResult<()> {
+        self.add_video_timestamp_entities(video_entity, std::slice::from_ref(timestamp_entity))
+            .await
+    }
+
+    async fn add_video_timestamp_entities(
+        &self,
+        video_entity: &VideoEntity,
+        entities: &[VideoTimestampEntity],
+    ) -> AppResult<()> {
+        self.update_video_timestamp_entities(video_entity, entities)
+            .await
+    }
+
+    async fn update_video_timestamp_entity(
+        &self,
+        video_entity: &VideoEntity,
+        entity: &VideoTimestampEntity,
+    ) -> AppResult<()> {
+        self.update_video_timestamp_entities(video_entity, std::slice::from_ref(entity))
+            .await
+    }
+
+    async fn update_video_timestamp_entities(
+        &self,
+        _: &VideoEntity,
+        entities: &[VideoTimestampEntity],
+    ) -> AppResult<()> {
+        let mut db = self.db.lock().unwrap();
+        for t in entities {
+            db.insert(t.id()?.to_string(), t.clone());
+        }
+        Ok(())
+    }
+
+    async fn find_video_timestamp_entity_by_id(&self, id: &TimestampId) -> AppResult<bool> {
+        Ok(self.db.lock().unwrap().contains_key(id.as_str()))
+    }
+
+    async fn get_video_timestamp_entity_by_id(
+        &self,
+        id: &TimestampId,
+    ) -> AppResult<Option<VideoTimestampEntity>> {
+        Ok(self.db.lock().unwrap().get(id.as_str()).cloned())
+    }
+
+    async fn get_all_video_timestamp_entities(&self) -> AppResult<Vec<VideoTimestampEntity>> {
+        Ok(self.db.lock().unwrap().values().cloned().collect())
+    }
+
+    async fn get_video_timestamp_entities_by_video_id(
+        &self,
+        video_id: &VideoId,
+    ) -> AppResult<Vec<VideoTimestampEntity>> {
+        Ok(self
+            .db
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|t| &t.video_id == video_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_video_timestamp_entity_by_id(&self, video_id: &VideoId) -> AppResult<()> {
+        self.delete_video_timestamp_entity_by_video_id(video_id)
+            .await
+    }
+
+    async fn delete_video_timestamp_entity_by_video_id(&self, video_id: &VideoId) -> AppResult<()> {
+        self.db
+            .lock()
+            .unwrap()
+            .retain(|_, t| &t.video_id != video_id);
+        Ok(())
+    }
+
+    async fn delete_all_video_timestamp_entities(&self) -> AppResult<()> {
+        self.db.lock().unwrap().clear();
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct InMemorySyncStateRepository {
+    db: Mutex<BTreeMap<String, ChannelSyncStateEntity>>,
+}
+
+#[async_trait::async_trait]
+impl InternalSyncStateRepository for InMemorySyncStateRepository {
+    async fn get_sync_state(
+        &self,
+        channel_id: &ChannelId,
+    ) -> AppResult<Option<ChannelSyncStateEntity>> {
+        Ok(self.db.lock().unwrap().get(channel_id.as_str()).cloned())
+    }
+
+    async fn save_sync_state(&self, state: &ChannelSyncStateEntity) -> AppResult<()> {
+        self.db
+            .lock()
+            .unwrap()
+            .insert(state.channel_id.to_string(), state.clone());
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+//! YouTube Data API の代わりに、同梱のフィクスチャを返すローカルの HTTP サーバー
+use mockito::{Matcher, Mock, Request, ServerGuard};
+use serde_json::Value;
+use youtube::config::YouTubeClient;
+
+/// フィクスチャのチャンネル
+pub const CHANNEL_ID: &str = "UC_x5XG1OV2P6uZZ5FSM9Ttw";
+
+const CHANNELS: &str = include_str!("../json/youtube/channels.json");
+const PLAYLIST_ITEMS: &str = include_str!("../json/youtube/playlist_items.json");
+const VIDEOS: &str = include_str!("../json/youtube/videos.json");
+const COMMENT_THREADS: &str = include_str!("../json/youtube/comment_threads.json");
+
+pub struct FakeYouTubeServer {
+    server: ServerGuard,
+    _mocks: Vec<Mock>,
+}
+
+impl FakeYouTubeServer {
+    /// channels / playlistItems / videos / commentThreads の list を受け付けるサーバーを起動する
+    ///
+    /// - channels, videos : `id` で絞り込む。channels は `forHandle` でも絞り込む
+    /// - playlistItems    : `maxResults` ごとに区切り、`pageToken` で続きを返す
+    /// - commentThreads   : `videoId` で絞り込む
+    pub async fn start() -> Self {
+        Self::start_with(None).await
+    }
+
+    /// `endpoint` のみ `status` のエラーを返すサーバーを起動する
+    pub async fn start_failing(endpoint: &str, status: usize) -> Self {
+        Self::start_with(Some((endpoint, status))).await
+    }
+
+    async fn start_with(failing: Option<(&str, usize)>) -> Self {
+        let mut server = mockito::Server::new_async().await;
+        let mut mocks = Vec::new();
+        for (endpoint, fixture) in [
+            ("channels", CHANNELS),
+            ("playlistItems", PLAYLIST_ITEMS),
+            ("videos", VIDEOS),
+            ("commentThreads", COMMENT_THREADS),
+        ] {
+            let mock = server
+                .mock("GET", format!("/youtube/v3/{}", endpoint).as_str())
+                .match_query(Matcher::Any)
+                .with_header("content-type", "application/json");
+            let mock = match failing {
+                Some((e, status)) if e == endpoint => {
+                    mock.with_status(status).with_body(error_body(status))
+                }
+                _ => {
+                    let fixture: Value = serde_json::from_str(fixture).unwrap();
+                    mock.with_body_from_request(move |req| respond(endpoint, &fixture, req))
+                }
+            };
+            mocks.push(mock.create_async().await);
+        }
+
+        Self {
+            server,
+            _mocks: mocks,
+        }
+    }
+
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    /// このサーバーに接続する YouTube クライアントの設定
+    pub fn config(&self) -> YouTubeClient {
+        YouTubeClient::with_api_key("fake-api-key", &self.url())
+    }
+}
+
+/// YouTube Data API のエラーの本文
+fn error_body(status: usize) -> String {
+    let reason = match status {
+        401 => "authError",
+        403 => "forbidden",
+        _ => "backendError",
+    };
+    serde_json::json!({
+        "error": {
+            "code": status,
+            "message": reason,
+            "errors": [{ "reason": reason, "message": reason }],
+        }
+    })
+    .to_string()
+}
+
+fn respond(endpoint: &str, fixture: &Value, req: &Request) -> Vec<u8> {
+    let query = req.path_and_query().split_once('?').map_or("", |(_, q)| q);
+    let params: Vec<(String, String)> = url::form_urlencoded::parse(query.as_bytes())
+        .into_owned()
+        .collect();
+    // 複数の値は `id=a,b` と `id=a&id=b` のどちらでも受け付ける
+    let param = |name: &'static str| {
+        params
+            .iter()
+            .filter(move |(k, _)| k == name)
+            .flat_map(|(_, v)| v.split(','))
+    };
+
+    let items = fixture["items"].as_array().cloned().unwrap_or_default();
+    let mut body = fixture.clone();
+    match endpoint {
+        "channels" if param("forHandle").next().is_some() => {
+            // ハンドルは `@` の有無と大文字・小文字を区別しない
+            let handle = param("forHandle").next().unwrap_or_default();
+            let handle = format!("@{}", handle.trim_start_matches('@')).to_lowercase();
+            body["items"] = items
+                .into_iter()
+                .filter(|i| i["snippet"]["customUrl"].as_str() == Some(handle.as_str()))
+                .collect();
+        }
+        "channels" | "videos" => {
+            let ids: Vec<&str> = param("id").collect();
+            body["items"] = items
+                .into_iter()
+                .filter(|i| ids.contains(&i["id"].as_str().unwrap_or_default()))
+                .collect();
+        }
+        "playlistItems" => {
+            let start: usize = param("pageToken")
+                .next()
+                .and_then(|t| t.parse().ok())
+                .unwrap_or(0);
+            let size: usize = param("maxResults")
+                .next()
+                .and_then(|m| m.parse().ok())
+                .unwrap_or(5);
+            let end = (start + size).min(items.len());
+            if end < items.len() {
+                body["nextPageToken"] = Value::from(end.to_string());
+            }
+            body["items"] = items[start.min(end)..end].to_vec().into();
+        }
+        "commentThreads" => {
+            let video_id = param("videoId").next().unwrap_or_default().to_string();
+            body["items"] = items
+                .into_iter()
+                .filter(|i| i["snippet"]["videoId"].as_str() == Some(video_id.as_str()))
+                .collect();
+        }
+        _ => {}
+    }
+    serde_json::to_vec(&body).unwrap()
+}